@@ -1,7 +1,8 @@
 pub mod error;
 pub mod project;
 
-use clap::Parser;
+use crate::compiler::function_table::{DEFAULT_MAX_CALL_DEPTH, MAX_CALL_DEPTH};
+use clap::{Parser, Subcommand};
 use error::CLIError;
 use project::Project;
 use serde::Serialize;
@@ -33,6 +34,46 @@ pub struct Args {
     /// Additional flag that depends on --init
     #[arg(short, default_value_t = false)]
     pub y: bool,
+
+    /// Maximum number of nested function calls
+    #[arg(long, default_value_t = DEFAULT_MAX_CALL_DEPTH, value_parser = max_depth)]
+    pub max_depth: usize,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// The `--max-depth` allows at least one call and at most [`MAX_CALL_DEPTH`]
+fn max_depth(value: &str) -> Result<usize, String> {
+    let depth: usize = value
+        .parse()
+        .map_err(|_| format!("'{value}' isn't a number of calls"))?;
+    if (1..=MAX_CALL_DEPTH).contains(&depth) {
+        Ok(depth)
+    } else {
+        Err(format!("the depth must be between 1 and {MAX_CALL_DEPTH}"))
+    }
+}
+
+/// The modes that don't run the program
+#[derive(Subcommand, Debug, Serialize, PartialEq)]
+pub enum Command {
+    /// Check the types of the whole program without running it
+    Check {
+        /// Name of the file to check
+        #[arg(default_value_t = String::from("./main.atlas"))]
+        root_file: String,
+    },
+
+    /// Show the type of the code at a line and a column of the file, for the editors
+    Hover {
+        /// Name of the file
+        root_file: String,
+        /// Line, starting at 1
+        line: usize,
+        /// Column, starting at 1
+        column: usize,
+    },
 }
 
 impl IntoIterator for Args {
@@ -45,10 +86,7 @@ impl IntoIterator for Args {
 
         // If it's a JSON Object We transform their key-value in a tuple
         if let Value::Object(map) = json_value {
-            map.into_iter()
-                .map(|(key, value)| (key, value))
-                .collect::<Vec<_>>()
-                .into_iter()
+            map.into_iter().collect::<Vec<_>>().into_iter()
         } else {
             // If it's not a JSON Object. Let's return a Empty iterator
             vec![].into_iter()
@@ -59,7 +97,7 @@ impl IntoIterator for Args {
 impl Args {
     /// Check if the introduced file doesn't is empty because in this case there isn't a file to read.
     pub fn verify(&self) -> Result<Project, CLIError> {
-        if !self.root_file.trim().is_empty() {
+        if !self.file().trim().is_empty() {
             let file = self.verify_file();
             if let Err(error) = file {
                 return Err(error);
@@ -71,11 +109,19 @@ impl Args {
         Ok(Project { files: vec![] })
     }
 
+    /// The file to run, or the file of `atlas check` and `atlas hover`
+    pub fn file(&self) -> &str {
+        match &self.command {
+            Some(Command::Check { root_file } | Command::Hover { root_file, .. }) => root_file,
+            None => &self.root_file,
+        }
+    }
+
     /// Check if the alleged file exist in this path, it's a directory or it has a correct extension.
     ///
     /// Otherwise, If it has a correct extension and it's a file so let's continue with the parse.
     fn verify_file(&self) -> Result<Project, CLIError> {
-        let path = Path::new(&self.root_file);
+        let path = Path::new(self.file());
 
         if !path.is_file() {
             return Err(CLIError::InvalidPath);
@@ -88,9 +134,9 @@ impl Args {
             return Err(CLIError::InvalidExtension);
         }
         let project = Project::new(vec![path.to_path_buf()]);
-        return match project {
+        match project {
             Ok(pr) => Ok(pr),
             Err(error) => panic!("{error}"),
-        };
+        }
     }
 }
@@ -0,0 +1,277 @@
+use std::sync::Arc;
+
+use super::{
+    elements::{operator::Operator, token::Token},
+    span::Span,
+    types::Types,
+};
+
+/// An expression, which is any piece of code that produces a value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind {
+    /// A value written directly in the code, such as `10`, `"hola"` or `true`
+    Literal(Token),
+
+    /// A reference to a variable
+    Variable(Arc<str>),
+
+    /// An operator applied to only one operand, such as `-a`
+    Unary {
+        operator: Operator,
+        operand: Box<Expr>,
+    },
+
+    /// An operator applied to two operands, such as `a + b`
+    Binary {
+        operator: Operator,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+
+    /// A call to a function, such as `print(a)`. The name can be a variable that holds a
+    /// function.
+    Call { callee: Arc<str>, args: Vec<Arg> },
+
+    /// A call to the function given by an expression, such as `make_adder(1)(2)`
+    Invoke { callee: Box<Expr>, args: Vec<Arg> },
+
+    /// An anonymous function, `func(x: Int32) -> Int32 { x * 2 }` or `|x| x * 2`. Its name is
+    /// [`LAMBDA`] and the value of the last expression of its body is returned.
+    Lambda(Box<FunctionDecl>),
+
+    /// `[a, b, c]`
+    List(Vec<Expr>),
+
+    /// `{ key: value, ... }`, the entries keep the order in which they are written
+    Map(Vec<(Expr, Expr)>),
+
+    /// `Name { field: value, ... }`, the construction of a struct
+    Struct {
+        name: Arc<str>,
+        fields: Vec<(Arc<str>, Expr)>,
+    },
+
+    /// `target.name`, the access to a field of a struct
+    Field { target: Box<Expr>, name: Arc<str> },
+
+    /// `target?.name`, the field of an optional struct or `none` when the target is `none`
+    OptionalField { target: Box<Expr>, name: Arc<str> },
+
+    /// `match value { pattern => body, ... }`, the body of the first arm whose pattern fits
+    Match {
+        value: Box<Expr>,
+        arms: Vec<MatchArm>,
+    },
+
+    /// `target[index]`, or a slice `target[start..end]` when the index is a range
+    Index { target: Box<Expr>, index: Box<Expr> },
+
+    /// `start..end step n` or `start..=end step n`
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        step: Option<Box<Expr>>,
+        inclusive: bool,
+    },
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+/// An argument of a call, `value` or `name: value`. The named arguments go after the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arg {
+    pub name: Option<Arc<str>>,
+    pub value: Expr,
+}
+
+/// The left side of an arm of a `match`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// `_`, fits any value
+    Wildcard,
+
+    /// A value like `1`, `-2.5`, `"hola"` or `true`
+    Literal(Token),
+
+    /// A name that takes the value, unless it's a variant without payload like `Empty`
+    Identifier(Arc<str>),
+
+    /// `Circle(r)`, a variant with the patterns of its payload
+    Variant { name: Arc<str>, args: Vec<Pattern> },
+}
+
+/// `pattern => body`, an expression or a block
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Vec<Stmt>,
+    pub span: Span,
+}
+
+/// A statement, which is any piece of code that is executed but doesn't produce a value by itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StmtKind {
+    /// `var name: Type = value` or `const name: Type = value`
+    VarDecl {
+        name: Arc<str>,
+        var_type: Types,
+        value: Expr,
+        mutable: bool,
+    },
+
+    /// `name = value` or any compound assignment like `name += value`
+    Assign {
+        name: Arc<str>,
+        operator: Operator,
+        value: Expr,
+    },
+
+    /// `list[index] = value`, `point.x = value` or a compound assignment, the target is an
+    /// [`ExprKind::Index`] or an [`ExprKind::Field`] that can be nested like `lines[0].start.x`
+    AssignTo {
+        target: Expr,
+        operator: Operator,
+        value: Expr,
+    },
+
+    /// An expression used as a statement, its value is part of the results
+    Expr(Expr),
+
+    /// A list of statements between braces with their own scope
+    Block(Vec<Stmt>),
+
+    /// `if condition { ... } else if condition { ... } else { ... }`
+    ///
+    /// An `else if` is stored as an `else` whose body is only the inner `If`.
+    If {
+        condition: Expr,
+        then_branch: Vec<Stmt>,
+        else_branch: Option<Vec<Stmt>>,
+    },
+
+    /// `while condition { ... }`
+    While { condition: Expr, body: Vec<Stmt> },
+
+    /// `for variable in iterable { ... }`
+    For {
+        variable: Arc<str>,
+        iterable: Expr,
+        body: Vec<Stmt>,
+    },
+
+    /// `break`, finish the innermost loop
+    Break,
+
+    /// `continue`, go to the next iteration of the innermost loop
+    Continue,
+
+    /// `return value` or `return`, finish the function
+    Return(Option<Expr>),
+}
+
+impl Stmt {
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+/// The name of the anonymous functions
+pub const LAMBDA: &str = "lambda";
+
+/// A parameter in a function declaration: `name: Type = default`, or `...name: Type` that
+/// takes the rest of the values as a list
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: Arc<str>,
+    pub var_type: Types,
+    pub default: Option<Expr>,
+    pub variadic: bool,
+    pub span: Span,
+}
+
+/// `func name<T, ...>(params) -> Type { body }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionDecl {
+    pub name: Arc<str>,
+    /// The type parameters, empty if the function isn't generic
+    pub generics: Vec<Arc<str>>,
+    pub params: Vec<Param>,
+    pub return_type: Types,
+    pub body: Vec<Stmt>,
+    pub span: Span,
+}
+
+/// A field in a struct declaration: `name: Type`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: Arc<str>,
+    pub var_type: Types,
+    pub span: Span,
+}
+
+/// `struct Name<T, ...> { field: Type, ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructDecl {
+    pub name: Arc<str>,
+    pub generics: Vec<Arc<str>>,
+    pub fields: Vec<Field>,
+    pub span: Span,
+}
+
+impl StructDecl {
+    /// The declared type of the field `name`
+    pub fn field_type(&self, name: &str) -> Option<&Types> {
+        self.fields
+            .iter()
+            .find(|field| &*field.name == name)
+            .map(|field| &field.var_type)
+    }
+}
+
+/// A variant in an enum declaration: `Name` or `Name(Type, ...)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: Arc<str>,
+    pub fields: Vec<Types>,
+    pub span: Span,
+}
+
+/// `enum Name<T, ...> { Variant, Variant(Type, ...), ... }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumDecl {
+    pub name: Arc<str>,
+    pub generics: Vec<Arc<str>>,
+    pub variants: Vec<Variant>,
+    pub span: Span,
+}
+
+impl EnumDecl {
+    pub fn variant(&self, name: &str) -> Option<&Variant> {
+        self.variants.iter().find(|variant| &*variant.name == name)
+    }
+}
+
+/// Each element that can be found at the top level of a program.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Function(FunctionDecl),
+    Struct(StructDecl),
+    Enum(EnumDecl),
+    Stmt(Stmt),
+}
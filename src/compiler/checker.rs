@@ -0,0 +1,1579 @@
+use std::{collections::HashMap, fmt, rc::Rc, sync::Arc};
+
+use crate::{
+    std::standard_library,
+    types::basic::number::{
+        double::Double, float::Float, hpint::HPInt, int32::Int32, int64::Int64,
+    },
+};
+
+use super::{
+    ast::{
+        Arg, EnumDecl, Expr, ExprKind, FunctionDecl, Item, MatchArm, Param, Pattern, Stmt,
+        StmtKind, StructDecl, Variant,
+    },
+    elements::{operator::Operator, token::Token},
+    error::{function_error::FunctionError, parse_error::ParseError, spanned::Located},
+    evaluator::{STACK_RED_ZONE, STACK_SEGMENT},
+    function::Function,
+    function_table::Func,
+    iteration::Range,
+    span::Span,
+    types::{Bindings, Types},
+};
+
+/// A variable known by the checker, its type is in [`Checker::symbols`]
+#[derive(Debug, Clone)]
+struct Binding {
+    id: usize,
+    mutable: bool,
+    /// Place of the declaration
+    span: Span,
+}
+
+/// A variable or an argument of the program with the type that the checker knows of it. The
+/// unknown parts of the type are filled with the uses that come after the declaration, like
+/// `xs.push(1)` after `var xs = []`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: Arc<str>,
+    pub var_type: Types,
+}
+
+/// The type of the code at a place, given by [`Checker::hover`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hover {
+    /// The name of the variable, if the place is a variable
+    pub name: Option<Arc<str>>,
+    pub var_type: Types,
+    pub span: Span,
+}
+
+impl fmt::Display for Hover {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{name}: {}", self.var_type),
+            None => write!(f, "{}", self.var_type),
+        }
+    }
+}
+
+/// The function whose body is being checked
+#[derive(Debug, Clone)]
+struct Returns {
+    name: Arc<str>,
+    return_type: Types,
+}
+
+/// This struct checks the types of a whole program without executing it, it's used by
+/// `atlas check`.
+///
+/// The rules are the same that the [`Evaluator`](super::evaluator::Evaluator) uses when the
+/// code runs, but every branch is checked and the errors are collected instead of stopping at
+/// the first one. A type that can't be known before running, like the value returned by the
+/// standard library, is `Inferred` and fits anywhere.
+///
+/// The types without annotation are inferred from the code: a variable from its value and from
+/// the places where it's used later, and an argument of a function from its body and from the
+/// values passed in the calls.
+#[derive(Debug, Default)]
+pub struct Checker {
+    /// The variables of each scope, the last one is the innermost
+    scopes: Vec<HashMap<Arc<str>, Binding>>,
+    /// Every variable declared in the program, a [`Binding`] is an index of this list
+    symbols: Vec<Symbol>,
+    /// The places where each symbol is declared or used
+    uses: Vec<(Span, usize)>,
+    /// The type of each expression, by its place
+    expr_types: HashMap<Span, Types>,
+    functions: HashMap<Arc<str>, FunctionDecl>,
+    structs: HashMap<Arc<str>, StructDecl>,
+    enums: Vec<EnumDecl>,
+    /// The names of the functions of the standard library
+    std: Vec<String>,
+    /// The functions around the current position, the last one is the innermost
+    returns: Vec<Returns>,
+    errors: Vec<ParseError>,
+}
+
+impl Checker {
+    // MARK: Creation
+    pub fn new() -> Self {
+        Checker {
+            scopes: vec![HashMap::new()],
+            std: standard_library().into_keys().collect(),
+            ..Default::default()
+        }
+    }
+
+    // MARK: Check
+    /// Check every item of the program, returning all the errors found
+    pub fn check(&mut self, program: &[Item]) -> Result<(), Vec<ParseError>> {
+        // The declarations can be used before the place where they are written
+        for item in program {
+            match item {
+                Item::Function(function) => self.declare_function(function),
+                Item::Struct(structure) => self.declare_struct(structure),
+                Item::Enum(enumeration) => self.declare_enum(enumeration),
+                Item::Stmt(_) => {}
+            }
+        }
+        for item in program {
+            match item {
+                Item::Struct(structure) => {
+                    for field in &structure.fields {
+                        self.check_type(&field.var_type, field.span);
+                    }
+                }
+                Item::Enum(enumeration) => {
+                    for variant in &enumeration.variants {
+                        for field in &variant.fields {
+                            self.check_type(field, variant.span);
+                        }
+                    }
+                }
+                Item::Function(_) => {}
+                Item::Stmt(_) => {}
+            }
+        }
+
+        // The body of a function tells the type of the arguments without annotation, like
+        // `xs` in `xs.push(1)`. They follow the order of the declarations, so the errors are
+        // always the same.
+        for item in program {
+            let Item::Function(function) = item else {
+                continue;
+            };
+            let untyped = self
+                .functions
+                .get(&function.name)
+                .filter(|declared| declared.span == function.span)
+                .filter(|declared| {
+                    declared
+                        .params
+                        .iter()
+                        .any(|param| param.var_type.has_inferred())
+                })
+                .cloned();
+            if let Some(untyped) = untyped {
+                self.infer_params(&untyped);
+            }
+        }
+
+        let statements: Vec<Stmt> = program
+            .iter()
+            .filter_map(|item| match item {
+                Item::Stmt(stmt) => Some(stmt.clone()),
+                _ => None,
+            })
+            .collect();
+        self.statements(&statements);
+
+        // The bodies see every global variable, like when they are called. The arguments have
+        // the types inferred from the calls.
+        for item in program {
+            if let Item::Function(function) = item {
+                let inferred = self
+                    .functions
+                    .get(&function.name)
+                    .filter(|inferred| inferred.span == function.span)
+                    .cloned();
+                self.function(&inferred.unwrap_or_else(|| function.clone()));
+            }
+        }
+
+        // The function bodies are checked at the end, but the errors follow the code
+        let mut errors = std::mem::take(&mut self.errors);
+        errors.sort_by_key(|error| error.span().map(|span| span.start));
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The type of the code at the byte `offset` of the file, after checking the program. A
+    /// variable shows the type inferred with all its uses.
+    pub fn hover(&self, file_id: usize, offset: usize) -> Option<Hover> {
+        let contains =
+            |span: &Span| span.file_id == file_id && (span.start..span.end).contains(&offset);
+        let variables = self
+            .uses
+            .iter()
+            .filter(|(span, _)| contains(span))
+            .map(|(span, id)| {
+                let symbol = &self.symbols[*id];
+                Hover {
+                    name: Some(symbol.name.clone()),
+                    var_type: symbol.var_type.clone(),
+                    span: *span,
+                }
+            });
+        let expressions = self
+            .expr_types
+            .iter()
+            .filter(|(span, _)| contains(span))
+            .map(|(span, var_type)| Hover {
+                name: None,
+                var_type: var_type.clone(),
+                span: *span,
+            });
+        // The innermost piece of code, a variable before the expression with the same place
+        variables
+            .chain(expressions)
+            .min_by_key(|hover| hover.span.len())
+    }
+
+    /// Every variable and argument of the program with its inferred type
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    fn error(&mut self, error: ParseError, span: Span) {
+        self.errors.push(error.at(span));
+    }
+
+    // MARK: Declarations
+    fn declare_function(&mut self, function: &FunctionDecl) {
+        if self.functions.contains_key(&function.name)
+            || self.std.contains(&function.name.to_string())
+        {
+            let error = ParseError::DefinedFunction(function.name.to_string());
+            return self.error(error, function.span);
+        }
+        self.functions
+            .insert(function.name.clone(), function.clone());
+    }
+
+    fn declare_struct(&mut self, structure: &StructDecl) {
+        if self.has_type(&structure.name) {
+            return self.defined_type(&structure.name, structure.span);
+        }
+        self.structs
+            .insert(structure.name.clone(), structure.clone());
+    }
+
+    fn declare_enum(&mut self, enumeration: &EnumDecl) {
+        if self.has_type(&enumeration.name) {
+            return self.defined_type(&enumeration.name, enumeration.span);
+        }
+        for variant in &enumeration.variants {
+            if let Some((other, _)) = self.variant(&variant.name) {
+                let error = ParseError::DefinedType(format!(
+                    "The variant '{}' is already declared in '{}'.",
+                    variant.name, other.name
+                ));
+                return self.error(error, variant.span);
+            }
+        }
+        self.enums.push(enumeration.clone());
+    }
+
+    fn defined_type(&mut self, name: &str, span: Span) {
+        let error = ParseError::DefinedType(format!("The type '{name}' is already declared."));
+        self.error(error, span);
+    }
+
+    fn has_type(&self, name: &str) -> bool {
+        self.structs.contains_key(name) || self.enums.iter().any(|other| &*other.name == name)
+    }
+
+    /// The enum with a variant called `name` and the variant
+    fn variant(&self, name: &str) -> Option<(&EnumDecl, &Variant)> {
+        self.enums.iter().find_map(|enumeration| {
+            enumeration
+                .variant(name)
+                .map(|variant| (enumeration, variant))
+        })
+    }
+
+    /// The type parameters of the struct or the enum `name`
+    fn generics(&self, name: &str) -> Option<&[Arc<str>]> {
+        match self.structs.get(name) {
+            Some(structure) => Some(&structure.generics),
+            None => self
+                .enums
+                .iter()
+                .find(|other| &*other.name == name)
+                .map(|enumeration| &enumeration.generics[..]),
+        }
+    }
+
+    /// Check that every struct or enum used in `types` is declared with its type parameters
+    fn check_type(&mut self, types: &Types, span: Span) {
+        match types {
+            Types::Custom(name, _) if !self.has_type(name) => {
+                let error = ParseError::UndefinedType(format!("The type '{name}' doesn't exist."));
+                self.error(error, span);
+            }
+            Types::Custom(name, args) => {
+                let expected = self.generics(name).map_or(0, <[_]>::len);
+                // Without them the type parameters aren't known, like in `p: Pair`
+                if !args.is_empty() && args.len() != expected {
+                    let error = ParseError::TypeError(format!(
+                        "The type '{name}' has {expected} type parameters but it receives {}.",
+                        args.len()
+                    ));
+                    self.error(error, span);
+                }
+                for arg in args {
+                    self.check_type(arg, span);
+                }
+            }
+            Types::List(item) | Types::Option(item) => self.check_type(item, span),
+            Types::Map(key, value) => {
+                self.check_type(key, span);
+                self.check_type(value, span);
+            }
+            _ => {}
+        }
+    }
+
+    // MARK: Functions
+    /// Check the body of a function or a lambda in a new scope with its arguments, returning
+    /// the types of the arguments after checking the body
+    fn function(&mut self, function: &FunctionDecl) -> Vec<Types> {
+        self.scopes.push(HashMap::new());
+        let mut params = Vec::with_capacity(function.params.len());
+        for param in &function.params {
+            self.check_type(&param.var_type, param.span);
+            let mut var_type = param.var_type.clone();
+            if let Some(default) = &param.default {
+                let default_type = self.expr(default);
+                self.fits(&default_type, &param.var_type, default.span, || {
+                    format!("default value of '{}'", param.name)
+                });
+                var_type = var_type.refine(&default_type);
+            }
+            params.push(self.declare(&param.name, var_type, true, param.span));
+        }
+        self.check_type(&function.return_type, function.span);
+
+        self.returns.push(Returns {
+            name: function.name.clone(),
+            return_type: function.return_type.clone(),
+        });
+        self.statements(&function.body);
+        self.returns.pop();
+        self.scopes.pop();
+        let params = params
+            .into_iter()
+            .map(|id| self.symbols[id].var_type.clone())
+            .collect();
+
+        // Without `return` the function gives back `Void`
+        let returns_value = !matches!(function.return_type, Types::Void | Types::Inferred);
+        if returns_value && !function.body.iter().any(Self::finishes) {
+            let error = FunctionError::DifferentReturnType(format!(
+                "The function '{}' must return <{}> but it can finish without a 'return'.",
+                function.name, function.return_type
+            ));
+            self.error(error.into(), function.span);
+        }
+        params
+    }
+
+    /// Infer the types of the arguments without annotation from the body of the function. The
+    /// errors are found again when the body is checked with those types.
+    fn infer_params(&mut self, function: &FunctionDecl) {
+        let (errors, symbols, uses) = (self.errors.len(), self.symbols.len(), self.uses.len());
+        let types = self.function(function);
+        self.errors.truncate(errors);
+        self.symbols.truncate(symbols);
+        self.uses.truncate(uses);
+
+        for (index, var_type) in types.iter().enumerate() {
+            self.infer_param(&function.name, index, var_type);
+        }
+    }
+
+    /// Fill the unknown parts of the type of an argument of the function `name`
+    fn infer_param(&mut self, name: &str, index: usize, known: &Types) {
+        if let Some(param) = self
+            .functions
+            .get_mut(name)
+            .and_then(|function| function.params.get_mut(index))
+        {
+            param.var_type = param.var_type.refine(known);
+        }
+    }
+
+    /// Check the values passed to a function with the rules of
+    /// [`Function::call`](super::function::Function::call), returning the types that they give
+    /// to the type parameters
+    fn arguments(
+        &mut self,
+        function: &FunctionDecl,
+        args: &[(Arg, Types)],
+        span: Span,
+    ) -> Bindings {
+        let name = function.name.to_string();
+        let params = &function.params;
+        let variadic = params.last().is_some_and(|param| param.variadic);
+        let fixed = params.len() - usize::from(variadic);
+        let positional = args.iter().filter(|(arg, _)| arg.name.is_none()).count();
+        if positional > fixed && !variadic {
+            let error = FunctionError::InvalidNumberOfArgs(format!(
+                "The function '{name}' accepts {fixed} arguments but it receives {positional}."
+            ));
+            self.error(error.into(), span);
+            return Bindings::new();
+        }
+
+        let mut given = vec![false; params.len()];
+        let mut rest = false;
+        let mut bindings = Bindings::new();
+        for (position, (arg, arg_type)) in args.iter().enumerate() {
+            let index = match &arg.name {
+                Some(arg_name) => match params.iter().position(|param| param.name == *arg_name) {
+                    Some(index) => index,
+                    None => {
+                        let error = FunctionError::UnknownArgument {
+                            function: name.clone(),
+                            argument: arg_name.to_string(),
+                        };
+                        self.error(error.into(), arg.value.span);
+                        continue;
+                    }
+                },
+                None if position < fixed => position,
+                None => {
+                    rest = true;
+                    let param = &params[fixed];
+                    let Types::List(item) = &param.var_type else {
+                        unreachable!("the type of a variadic argument is a list");
+                    };
+                    self.argument(&name, param, item, arg_type, &arg.value, &mut bindings);
+                    self.infer_param(&name, fixed, &Types::List(Box::new(arg_type.clone())));
+                    continue;
+                }
+            };
+
+            let param = &params[index];
+            if given[index] {
+                let error = FunctionError::RepeatedArgument {
+                    function: name.clone(),
+                    argument: param.name.to_string(),
+                };
+                self.error(error.into(), arg.value.span);
+                continue;
+            }
+            given[index] = true;
+            let expected = &param.var_type;
+            self.argument(&name, param, expected, arg_type, &arg.value, &mut bindings);
+            self.infer_param(&name, index, arg_type);
+        }
+
+        if variadic && rest && given[fixed] {
+            let error = FunctionError::RepeatedArgument {
+                function: name.clone(),
+                argument: params[fixed].name.to_string(),
+            };
+            self.error(error.into(), span);
+        }
+        let missing = params
+            .iter()
+            .zip(&given)
+            .find(|(param, given)| !**given && param.default.is_none() && !param.variadic);
+        if let Some((param, _)) = missing {
+            let error = FunctionError::MissingArgument {
+                function: name,
+                argument: param.name.to_string(),
+            };
+            self.error(error.into(), span);
+        }
+        bindings
+    }
+
+    /// Check a value passed to a parameter, the type parameters of the function take their
+    /// types from the first value passed to them
+    fn argument(
+        &mut self,
+        function: &str,
+        param: &Param,
+        expected: &Types,
+        found: &Types,
+        value: &Expr,
+        bindings: &mut Bindings,
+    ) {
+        if !expected.unify(found, bindings) {
+            let error = FunctionError::ArgumentType {
+                function: function.to_string(),
+                argument: param.name.to_string(),
+                expected: expected.substitute(bindings).to_string(),
+                found: found.to_string(),
+            };
+            self.error(error.into(), value.span);
+        }
+        self.refine(value, &expected.substitute(bindings).without_generics());
+    }
+
+    /// The type parameters that didn't get a type can be any type
+    fn unknown_generics(generics: &[Arc<str>], bindings: &mut Bindings) {
+        for generic in generics {
+            bindings
+                .entry(generic.to_string())
+                .or_insert(Types::Inferred);
+        }
+    }
+
+    /// The types of the type parameters `generics` of a struct or an enum in a type like
+    /// `Pair<Int32, Str>`, the ones that aren't given can be any type
+    fn type_args(generics: &[Arc<str>], args: &[Types]) -> Bindings {
+        generics
+            .iter()
+            .enumerate()
+            .map(|(position, generic)| {
+                let arg = args.get(position).cloned().unwrap_or(Types::Inferred);
+                (generic.to_string(), arg)
+            })
+            .collect()
+    }
+
+    // MARK: Variables
+    /// Declare a variable in the current scope, returning its symbol. A constant of the same
+    /// scope can't be declared again.
+    fn declare(&mut self, name: &Arc<str>, var_type: Types, mutable: bool, span: Span) -> usize {
+        let constant = self
+            .scopes
+            .last()
+            .and_then(|scope| scope.get(name))
+            .filter(|binding| !binding.mutable)
+            .map(|binding| binding.span);
+        if let Some(declaration) = constant {
+            let error = ParseError::ConstReassignment {
+                name: name.to_string(),
+                declaration,
+                span,
+            };
+            self.error(error, span);
+        }
+        let id = self.symbols.len();
+        self.symbols.push(Symbol {
+            name: name.clone(),
+            var_type,
+        });
+        self.uses.push((span, id));
+        let binding = Binding { id, mutable, span };
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.clone(), binding);
+        }
+        id
+    }
+
+    /// The variable visible with this name, from the innermost scope to the outermost one
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+    }
+
+    /// Check if the variable is declared in the innermost scope, the code that assigns it
+    /// always runs after the declaration
+    fn is_local(&self, name: &str, binding: &Binding) -> bool {
+        self.scopes
+            .last()
+            .and_then(|scope| scope.get(name))
+            .is_some_and(|local| local.id == binding.id)
+    }
+
+    fn type_of(&self, binding: &Binding) -> Types {
+        self.symbols[binding.id].var_type.clone()
+    }
+
+    /// Fill the unknown parts of the type of the place where the value of the expression is
+    /// stored, like `xs` in `xs[0]` or `m` in `m["key"]`
+    fn refine(&mut self, place: &Expr, known: &Types) {
+        match &place.kind {
+            ExprKind::Variable(name) => {
+                if let Some(binding) = self.lookup(name) {
+                    let symbol = &mut self.symbols[binding.id];
+                    symbol.var_type = symbol.var_type.refine(known);
+                }
+            }
+            ExprKind::Index { target, index } => {
+                let known = match self.expr_types.get(&target.span) {
+                    Some(Types::List(_)) => Types::List(Box::new(known.clone())),
+                    Some(Types::Map(..)) => {
+                        let key = self.expr_types.get(&index.span).cloned();
+                        Types::Map(
+                            Box::new(key.unwrap_or(Types::Inferred)),
+                            Box::new(known.clone()),
+                        )
+                    }
+                    _ => return,
+                };
+                self.refine(target, &known);
+            }
+            _ => {}
+        }
+    }
+
+    fn in_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(HashMap::new());
+        f(self);
+        self.scopes.pop();
+    }
+
+    // MARK: Statements
+    /// Check the statements of a block, the code after a `return`, a `break` or a `continue`
+    /// never runs
+    fn statements(&mut self, body: &[Stmt]) {
+        let mut finished = false;
+        for stmt in body {
+            if finished {
+                let error = ParseError::UnreachableCode(
+                    "This code is after a statement that always finishes the block.".into(),
+                );
+                self.error(error, stmt.span);
+                finished = false;
+            }
+            self.stmt(stmt);
+            finished |= Self::finishes(stmt);
+        }
+    }
+
+    /// Check a statement. Like in the evaluator, the nested code comes back here and to
+    /// [`Checker::expr`], so both grow the Rust stack when it's running out.
+    fn stmt(&mut self, stmt: &Stmt) {
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || self.stmt_kind(stmt))
+    }
+
+    fn stmt_kind(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::VarDecl {
+                name,
+                var_type,
+                value,
+                mutable,
+            } => {
+                // The lambda captures the scope, so it can call itself with the variable
+                if let ExprKind::Lambda(_) = value.kind {
+                    self.declare(name, Types::Function, *mutable, stmt.span);
+                }
+                let value_type = self.expr(value);
+                self.check_type(var_type, stmt.span);
+                let var_type = if *var_type == Types::Inferred {
+                    value_type
+                } else {
+                    self.refine(value, var_type);
+                    if !Self::can_fit(&value_type, var_type) {
+                        let error = ParseError::TypeError(format!(
+                            "The type of '{name}' must be <{var_type}> but it's <{value_type}>."
+                        ));
+                        self.error(error, value.span);
+                    }
+                    var_type.clone()
+                };
+                self.declare(name, var_type, *mutable, stmt.span);
+            }
+
+            StmtKind::Assign {
+                name,
+                operator,
+                value,
+            } => {
+                let value_type = self.expr(value);
+                let Some(binding) = self.lookup(name) else {
+                    let error =
+                        ParseError::UndefinedVariable(format!("Variable {name} not defined"));
+                    return self.error(error, stmt.span);
+                };
+                if !binding.mutable {
+                    let error = ParseError::ConstReassignment {
+                        name: name.to_string(),
+                        declaration: binding.span,
+                        span: stmt.span,
+                    };
+                    return self.error(error, stmt.span);
+                }
+                let var_type = self.type_of(&binding);
+                let new_type = if *operator == Operator::Assign {
+                    value_type
+                } else {
+                    self.binary(operator, &var_type, &value_type, stmt.span)
+                };
+                // A collection or an optional keeps its type, the unknown parts take the type of
+                // the new value
+                let keeps_type =
+                    matches!(var_type, Types::List(_) | Types::Map(..) | Types::Option(_));
+                if keeps_type {
+                    self.fits(&new_type, &var_type, value.span, || {
+                        format!("type of '{name}'")
+                    });
+                }
+                self.symbols[binding.id].var_type = if keeps_type {
+                    var_type.refine(&new_type)
+                } else if new_type == var_type || self.is_local(name, &binding) {
+                    new_type
+                } else {
+                    // The assignment is in a branch, a loop or a function that may not run, so
+                    // after it the variable can have both types
+                    Types::Inferred
+                };
+            }
+
+            StmtKind::AssignTo {
+                target,
+                operator,
+                value,
+            } => {
+                let value_type = self.expr(value);
+                let place_type = self.expr(target);
+                self.check_constant(target, stmt.span);
+                let value_type = if *operator == Operator::Assign {
+                    value_type
+                } else {
+                    self.binary(operator, &place_type, &value_type, stmt.span)
+                };
+                self.refine(target, &value_type);
+                self.fits(&value_type, &place_type, value.span, || {
+                    match &target.kind {
+                        ExprKind::Field { name, .. } => format!("field '{name}'"),
+                        _ => "element".to_string(),
+                    }
+                });
+            }
+
+            StmtKind::Expr(expr) => {
+                self.expr(expr);
+            }
+
+            StmtKind::Block(body) => self.in_scope(|checker| checker.statements(body)),
+
+            StmtKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.condition(condition);
+                match (&condition.kind, else_branch) {
+                    (ExprKind::Literal(Token::Boolean(false)), _) => {
+                        self.unreachable_branch(then_branch, "the condition is always 'false'")
+                    }
+                    (ExprKind::Literal(Token::Boolean(true)), Some(else_branch)) => {
+                        self.unreachable_branch(else_branch, "the condition is always 'true'")
+                    }
+                    _ => {}
+                }
+                self.in_scope(|checker| checker.statements(then_branch));
+                if let Some(else_branch) = else_branch {
+                    self.in_scope(|checker| checker.statements(else_branch));
+                }
+            }
+
+            StmtKind::While { condition, body } => {
+                self.condition(condition);
+                if let ExprKind::Literal(Token::Boolean(false)) = condition.kind {
+                    self.unreachable_branch(body, "the condition is always 'false'");
+                }
+                self.in_scope(|checker| checker.statements(body));
+            }
+
+            StmtKind::For {
+                variable,
+                iterable,
+                body,
+            } => {
+                let item_type = match self.expr(iterable) {
+                    Types::List(item) => *item,
+                    Types::Map(key, _) => *key,
+                    Types::Inferred => Types::Inferred,
+                    iterable_type => {
+                        let error = ParseError::TypeError(format!(
+                            "Only a <List>, a <Map> or a range can be iterated but it's <{iterable_type}>."
+                        ));
+                        self.error(error, iterable.span);
+                        Types::Inferred
+                    }
+                };
+                // `for variable in `, without the body
+                let head = Span::new(stmt.span.file_id, stmt.span.start, iterable.span.start);
+                self.in_scope(|checker| {
+                    checker.declare(variable, item_type, true, head);
+                    checker.statements(body);
+                });
+            }
+
+            StmtKind::Return(value) => {
+                let value_type = match value {
+                    Some(value) => self.expr(value),
+                    None => Types::Void,
+                };
+                let Some(function) = self.returns.last().cloned() else {
+                    return;
+                };
+                if let Some(value) = value {
+                    self.refine(value, &function.return_type);
+                }
+                if !Self::can_fit(&value_type, &function.return_type) {
+                    let error = FunctionError::DifferentReturnType(format!(
+                        "The function '{}' must return <{}> but it returns <{value_type}>.",
+                        function.name, function.return_type
+                    ));
+                    self.error(error.into(), stmt.span);
+                }
+            }
+
+            StmtKind::Break | StmtKind::Continue => {}
+        }
+    }
+
+    fn unreachable_branch(&mut self, body: &[Stmt], reason: &str) {
+        if let Some(first) = body.first() {
+            let error = ParseError::UnreachableCode(format!("This branch never runs, {reason}."));
+            self.error(error, first.span);
+        }
+    }
+
+    /// A constant can't change, not even an element or a field of its value
+    fn check_constant(&mut self, target: &Expr, span: Span) {
+        match &target.kind {
+            ExprKind::Index { target, .. } | ExprKind::Field { target, .. } => {
+                self.check_constant(target, span)
+            }
+            ExprKind::Variable(name) => match self.lookup(name) {
+                Some(binding) if !binding.mutable => {
+                    let error = ParseError::ConstReassignment {
+                        name: name.to_string(),
+                        declaration: binding.span,
+                        span,
+                    };
+                    self.error(error, span);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Check if the statement never lets the execution go to the next one
+    fn finishes(stmt: &Stmt) -> bool {
+        match &stmt.kind {
+            StmtKind::Return(_) | StmtKind::Break | StmtKind::Continue => true,
+            StmtKind::Block(body) => body.iter().any(Self::finishes),
+            StmtKind::If {
+                then_branch,
+                else_branch: Some(else_branch),
+                ..
+            } => then_branch.iter().any(Self::finishes) && else_branch.iter().any(Self::finishes),
+            // A loop that can only finish with `return`
+            StmtKind::While { condition, body } => {
+                condition.kind == ExprKind::Literal(Token::Boolean(true))
+                    && !body.iter().any(Self::breaks)
+            }
+            StmtKind::Expr(Expr {
+                kind: ExprKind::Match { arms, .. },
+                ..
+            }) => arms.iter().all(|arm| arm.body.iter().any(Self::finishes)),
+            _ => false,
+        }
+    }
+
+    /// Check if the statement can finish the loop where it is
+    fn breaks(stmt: &Stmt) -> bool {
+        match &stmt.kind {
+            StmtKind::Break => true,
+            StmtKind::Block(body) => body.iter().any(Self::breaks),
+            StmtKind::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                then_branch.iter().any(Self::breaks)
+                    || else_branch
+                        .as_ref()
+                        .is_some_and(|body| body.iter().any(Self::breaks))
+            }
+            StmtKind::Expr(Expr {
+                kind: ExprKind::Match { arms, .. },
+                ..
+            }) => arms.iter().any(|arm| arm.body.iter().any(Self::breaks)),
+            _ => false,
+        }
+    }
+
+    // MARK: Expressions
+    /// The type of the value of the expression, `Inferred` if it can't be known
+    fn expr(&mut self, expr: &Expr) -> Types {
+        let expr_type = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || self.expr_kind(expr));
+        self.expr_types.insert(expr.span, expr_type.clone());
+        expr_type
+    }
+
+    fn expr_kind(&mut self, expr: &Expr) -> Types {
+        match &expr.kind {
+            ExprKind::Literal(value) => Types::inferred(value).unwrap_or(Types::Inferred),
+
+            ExprKind::Variable(name) => {
+                if let Some(binding) = self.lookup(name) {
+                    self.uses.push((expr.span, binding.id));
+                    return self.type_of(&binding);
+                }
+                if let Some((enumeration, variant)) = self.variant(name) {
+                    let enumeration = Types::custom_of(
+                        &enumeration.name,
+                        &enumeration.generics,
+                        &Bindings::new(),
+                    );
+                    let values = variant.fields.len();
+                    if values > 0 {
+                        let error = ParseError::TypeError(format!(
+                            "The variant '{name}' has {values} values but it receives 0."
+                        ));
+                        self.error(error, expr.span);
+                    }
+                    return enumeration;
+                }
+                if self.functions.contains_key(name) || self.std.contains(&name.to_string()) {
+                    return Types::Function;
+                }
+                let error =
+                    ParseError::UndefinedVariable(format!("The variable '{name}' doesn't exist."));
+                self.error(error, expr.span);
+                Types::Inferred
+            }
+
+            ExprKind::Unary { operator, operand } => {
+                let operand_type = self.expr(operand);
+                let Some(operand) = sample(&operand_type) else {
+                    return Types::Inferred;
+                };
+                match operator.execute_unary(operand) {
+                    Ok(value) => Types::from(value),
+                    Err(error) => {
+                        self.error(error, expr.span);
+                        Types::Inferred
+                    }
+                }
+            }
+
+            ExprKind::Binary {
+                operator: Operator::And | Operator::Or,
+                left,
+                right,
+            } => {
+                self.condition(left);
+                self.condition(right);
+                Types::Boolean
+            }
+
+            ExprKind::Binary {
+                operator: Operator::Coalesce,
+                left,
+                right,
+            } => {
+                let value = self.expr(left);
+                let default = self.expr(right);
+                self.coalesce(&value, &default, right.span)
+            }
+
+            ExprKind::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let left = self.expr(left);
+                let right = self.expr(right);
+                self.binary(operator, &left, &right, expr.span)
+            }
+
+            ExprKind::Call { callee, args } => self.call(callee, args, expr.span),
+
+            ExprKind::Invoke { callee, args } => {
+                let callee_type = self.expr(callee);
+                self.callable(&callee_type, callee.span);
+                for arg in args {
+                    self.expr(&arg.value);
+                }
+                Types::Inferred
+            }
+
+            ExprKind::Lambda(function) => {
+                self.function(function);
+                Types::Function
+            }
+
+            ExprKind::Range {
+                start,
+                end,
+                step,
+                inclusive,
+            } => {
+                let start = self.expr(start);
+                let end = self.expr(end);
+                let step = step.as_ref().map(|step| self.expr(step));
+                let limits = (sample(&start), sample(&end));
+                let (Some(start), Some(end)) = limits else {
+                    return Types::List(Box::new(Types::Inferred));
+                };
+                let step = match step.as_ref().map(sample) {
+                    Some(None) => return Types::List(Box::new(Types::Inferred)),
+                    step => step.flatten(),
+                };
+                match Range::new(start, end, step, *inclusive) {
+                    Ok(range) => Types::List(Box::new(range.item_type().clone())),
+                    Err(error) => {
+                        self.error(error, expr.span);
+                        Types::List(Box::new(Types::Inferred))
+                    }
+                }
+            }
+
+            ExprKind::List(items) => {
+                let types: Vec<(Types, Span)> = items
+                    .iter()
+                    .map(|item| (self.expr(item), item.span))
+                    .collect();
+                Types::List(Box::new(self.same_types(&types, "elements of a list")))
+            }
+
+            ExprKind::Map(entries) => {
+                let mut keys = Vec::with_capacity(entries.len());
+                let mut values = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    let key_type = self.expr(key);
+                    if !matches!(
+                        key_type,
+                        Types::Int32
+                            | Types::Int64
+                            | Types::HPInt
+                            | Types::String
+                            | Types::Str
+                            | Types::Boolean
+                            | Types::Inferred
+                    ) {
+                        let error = ParseError::TypeError(format!(
+                            "The keys of a map can't be <{key_type}>."
+                        ));
+                        self.error(error, key.span);
+                    }
+                    keys.push((key_type, key.span));
+                    values.push((self.expr(value), value.span));
+                }
+                Types::Map(
+                    Box::new(self.same_types(&keys, "keys of a map")),
+                    Box::new(self.same_types(&values, "values of a map")),
+                )
+            }
+
+            ExprKind::Struct { name, fields } => self.structure(name, fields, expr.span),
+
+            ExprKind::Field { target, name } => match self.expr(target) {
+                Types::Option(_) => {
+                    let error = ParseError::TypeError(format!(
+                        "The value can be 'none', use '?.{name}' to get the field only when it isn't 'none'."
+                    ));
+                    self.error(error, target.span);
+                    Types::Inferred
+                }
+                target_type => self.field(target_type, name, target.span, expr.span),
+            },
+
+            // The field of the value without `none`, the result can be `none`
+            ExprKind::OptionalField { target, name } => {
+                let target_type = match self.expr(target) {
+                    Types::Option(item) => *item,
+                    target_type => target_type,
+                };
+                match self.field(target_type, name, target.span, expr.span) {
+                    field_type @ (Types::Option(_) | Types::Inferred) => field_type,
+                    field_type => Types::Option(Box::new(field_type)),
+                }
+            }
+
+            ExprKind::Match { value, arms } => {
+                let value_type = self.expr(value);
+                self.arms(&value_type, arms)
+            }
+
+            ExprKind::Index { target, index } => {
+                let target_type = self.expr(target);
+                match (target_type, &index.kind) {
+                    (Types::List(item), ExprKind::Range { .. }) => {
+                        self.expr(index);
+                        Types::List(item)
+                    }
+                    (Types::List(item), _) => {
+                        let index_type = self.expr(index);
+                        if !index_type.is_integer() && index_type != Types::Inferred {
+                            let error = ParseError::TypeError(format!(
+                                "The index of a list must be an integer but it's <{index_type}>."
+                            ));
+                            self.error(error, index.span);
+                        }
+                        *item
+                    }
+                    (Types::Map(_, value), _) => {
+                        self.expr(index);
+                        *value
+                    }
+                    (Types::Inferred, _) => {
+                        self.expr(index);
+                        Types::Inferred
+                    }
+                    (target_type, _) => {
+                        self.expr(index);
+                        let error = ParseError::TypeError(format!(
+                            "Only a <List> or a <Map> can be indexed but it's <{target_type}>."
+                        ));
+                        self.error(error, target.span);
+                        Types::Inferred
+                    }
+                }
+            }
+        }
+    }
+
+    /// The type of the field `name` of a value of the type `target_type`
+    fn field(&mut self, target_type: Types, name: &str, target: Span, span: Span) -> Types {
+        match target_type {
+            Types::Inferred => Types::Inferred,
+            Types::Custom(structure, args) if self.structs.contains_key(structure.as_str()) => {
+                let declared = &self.structs[structure.as_str()];
+                let bindings = Self::type_args(&declared.generics, &args);
+                let field_type = declared
+                    .field_type(name)
+                    .map(|field_type| field_type.substitute(&bindings));
+                field_type.unwrap_or_else(|| {
+                    let error = ParseError::UndefinedField {
+                        structure,
+                        field: name.to_string(),
+                    };
+                    self.error(error, span);
+                    Types::Inferred
+                })
+            }
+            target_type => {
+                let error = ParseError::TypeError(format!(
+                    "Only a struct has fields but it's <{target_type}>."
+                ));
+                self.error(error, target);
+                Types::Inferred
+            }
+        }
+    }
+
+    /// `value ?? default`, the type of the value without `none`. The result can only be `none`
+    /// when the default can be `none` too.
+    fn coalesce(&mut self, value: &Types, default: &Types, span: Span) -> Types {
+        let item = match value {
+            Types::Inferred => return Types::Inferred,
+            Types::Option(item) => (**item).clone(),
+            value => value.clone(),
+        };
+        if !Self::can_fit(default, &Types::Option(Box::new(item.clone()))) {
+            let error = ParseError::TypeError(format!(
+                "The default value of '??' must be <{item}> but it's <{default}>."
+            ));
+            self.error(error, span);
+            return item;
+        }
+        match default {
+            Types::Option(default) => Types::Option(Box::new(item.refine(default))),
+            default => item.refine(default),
+        }
+    }
+
+    /// The type of the result of a binary operator, found executing it with a value of each type.
+    /// The size of an integer result of `*`, `**` or `//` depends on the values, so it isn't
+    /// known.
+    fn binary(&mut self, operator: &Operator, left: &Types, right: &Types, span: Span) -> Types {
+        let (Some(left), Some(right)) = (sample(left), sample(right)) else {
+            return Types::Inferred;
+        };
+        match operator.execute(left, right).map(Types::from) {
+            Ok(result) if result.is_integer() && operator.widens() => Types::Inferred,
+            Ok(result) => result,
+            Err(error) => {
+                self.error(error, span);
+                Types::Inferred
+            }
+        }
+    }
+
+    fn condition(&mut self, condition: &Expr) {
+        let condition_type = self.expr(condition);
+        if let Some(Err(error)) = sample(&condition_type).map(|value| value.as_condition()) {
+            self.error(error, condition.span);
+        }
+    }
+
+    fn callable(&mut self, callee_type: &Types, span: Span) {
+        if !matches!(callee_type, Types::Function | Types::Inferred) {
+            let error = ParseError::TypeError(format!(
+                "Only a function can be called but it's <{callee_type}>."
+            ));
+            self.error(error, span);
+        }
+    }
+
+    /// `callee(args)`, a variable that holds a function, a variant, a function of the program
+    /// or one of the standard library
+    fn call(&mut self, callee: &Arc<str>, args: &[Arg], span: Span) -> Types {
+        let args: Vec<(Arg, Types)> = args
+            .iter()
+            .map(|arg| (arg.clone(), self.expr(&arg.value)))
+            .collect();
+
+        if let Some(binding) = self.lookup(callee) {
+            self.callable(&self.type_of(&binding), span);
+            return Types::Inferred;
+        }
+
+        if let Some((enumeration, variant)) = self.variant(callee) {
+            let (enumeration, variant) = (enumeration.clone(), variant.clone());
+            let bindings = self.variant_values(&variant, &args, span);
+            return Types::custom_of(&enumeration.name, &enumeration.generics, &bindings);
+        }
+
+        if let Some(function) = self.functions.get(callee).cloned() {
+            let mut bindings = self.arguments(&function, &args, span);
+            Self::unknown_generics(&function.generics, &mut bindings);
+            return function.return_type.substitute(&bindings);
+        }
+
+        if self.std.contains(&callee.to_string()) {
+            // The functions of the standard library don't declare the names of their arguments
+            if let Some((arg, _)) = args.iter().find(|(arg, _)| arg.name.is_some()) {
+                let error = FunctionError::UnknownArgument {
+                    function: callee.to_string(),
+                    argument: arg.name.as_deref().unwrap_or_default().to_string(),
+                };
+                self.error(error.into(), arg.value.span);
+            }
+            // They return the changed collection
+            return match &**callee {
+                "push" => self.push(&args),
+                "remove" => match args.first() {
+                    Some((_, map_type @ Types::Map(..))) => map_type.clone(),
+                    _ => Types::Inferred,
+                },
+                _ => Types::Inferred,
+            };
+        }
+
+        let error =
+            ParseError::UndefinedFunction(format!("The function '{callee}' doesn't exist."));
+        self.error(error, span);
+        Types::Inferred
+    }
+
+    /// `push(xs, value)` or `xs.push(value)`, the value gives the type of the elements of `xs`.
+    /// The call has the type of the new list.
+    fn push(&mut self, args: &[(Arg, Types)]) -> Types {
+        let [(list, list_type), (value, value_type)] = args else {
+            return Types::Inferred;
+        };
+        let with_value = Types::List(Box::new(value_type.clone()));
+        match list_type {
+            Types::List(item) if **item != Types::Inferred => {
+                if !Self::can_fit(value_type, item) {
+                    let error = ParseError::TypeError(format!(
+                        "The push function can't add a <{value_type}> to a <{list_type}>."
+                    ));
+                    self.error(error, value.value.span);
+                }
+                list_type.clone()
+            }
+            Types::List(_) | Types::Inferred => {
+                self.refine(&list.value, &with_value);
+                with_value
+            }
+            list_type => {
+                let error = ParseError::TypeError(format!(
+                    "The first argument of push must be a <List> but it's <{list_type}>."
+                ));
+                self.error(error, list.value.span);
+                Types::Inferred
+            }
+        }
+    }
+
+    /// Check the values of a variant, returning the types that they give to the type
+    /// parameters of the enum
+    fn variant_values(&mut self, variant: &Variant, args: &[(Arg, Types)], span: Span) -> Bindings {
+        let name = &variant.name;
+        let mut bindings = Bindings::new();
+        if let Some((arg, _)) = args.iter().find(|(arg, _)| arg.name.is_some()) {
+            let error = ParseError::SyntaxError(format!(
+                "The values of the variant '{name}' can't be named."
+            ));
+            self.error(error, arg.value.span);
+            return bindings;
+        }
+        if args.len() != variant.fields.len() {
+            let error = ParseError::TypeError(format!(
+                "The variant '{name}' has {} values but it receives {}.",
+                variant.fields.len(),
+                args.len()
+            ));
+            self.error(error, span);
+            return bindings;
+        }
+        for (position, ((arg, arg_type), field)) in args.iter().zip(&variant.fields).enumerate() {
+            self.fits_generic(arg_type, field, &mut bindings, arg.value.span, || {
+                format!("value {} of '{name}'", position + 1)
+            });
+        }
+        bindings
+    }
+
+    /// `Name { field: value, ... }`, every field must be given once with its declared type. The
+    /// values give the types of the type parameters of the struct.
+    fn structure(&mut self, name: &Arc<str>, fields: &[(Arc<str>, Expr)], span: Span) -> Types {
+        let values: Vec<(Arc<str>, Types, Span)> = fields
+            .iter()
+            .map(|(field, value)| (field.clone(), self.expr(value), value.span))
+            .collect();
+        let Some(structure) = self.structs.get(name).cloned() else {
+            let error = ParseError::UndefinedType(format!("The type '{name}' doesn't exist."));
+            self.error(error, span);
+            return Types::Inferred;
+        };
+
+        let mut bindings = Bindings::new();
+        for (position, (field, value_type, value_span)) in values.iter().enumerate() {
+            let Some(field_type) = structure.field_type(field) else {
+                let error = ParseError::UndefinedField {
+                    structure: name.to_string(),
+                    field: field.to_string(),
+                };
+                self.error(error, *value_span);
+                continue;
+            };
+            if values[..position].iter().any(|(other, ..)| other == field) {
+                let error = ParseError::SyntaxError(format!("The field '{field}' is given twice."));
+                self.error(error, *value_span);
+                continue;
+            }
+            self.fits_generic(value_type, field_type, &mut bindings, *value_span, || {
+                format!("field '{field}' of '{name}'")
+            });
+        }
+        for field in &structure.fields {
+            if !values.iter().any(|(given, ..)| *given == field.name) {
+                let error = ParseError::TypeError(format!(
+                    "The field '{}' of '{name}' is missing.",
+                    field.name
+                ));
+                self.error(error, span);
+            }
+        }
+        Types::custom_of(name, &structure.generics, &bindings)
+    }
+
+    /// Check the arms of a `match`, its type is the type of every arm if they have the same
+    fn arms(&mut self, value_type: &Types, arms: &[MatchArm]) -> Types {
+        let mut arm_types = vec![];
+        let mut catch_all = false;
+        for arm in arms {
+            if catch_all {
+                let error = ParseError::UnreachableCode(
+                    "This arm never runs, a pattern before it fits any value.".into(),
+                );
+                self.error(error, arm.span);
+            }
+            catch_all |= self.is_catch_all(&arm.pattern);
+
+            self.scopes.push(HashMap::new());
+            // The pattern, without the body
+            let head = match arm.body.first() {
+                Some(first) => Span::new(arm.span.file_id, arm.span.start, first.span.start),
+                None => arm.span,
+            };
+            self.pattern(&arm.pattern, value_type, head);
+            let (last, body) = match arm.body.split_last() {
+                Some((
+                    Stmt {
+                        kind: StmtKind::Expr(last),
+                        ..
+                    },
+                    body,
+                )) => (Some(last), body),
+                _ => (None, &arm.body[..]),
+            };
+            self.statements(body);
+            arm_types.push(match last {
+                Some(last) => self.expr(last),
+                None => Types::Void,
+            });
+            self.scopes.pop();
+        }
+
+        match arm_types.split_first() {
+            Some((first, others)) if others.iter().all(|other| other == first) => first.clone(),
+            _ => Types::Inferred,
+        }
+    }
+
+    fn is_catch_all(&self, pattern: &Pattern) -> bool {
+        match pattern {
+            Pattern::Wildcard => true,
+            Pattern::Identifier(name) => self.variant(name).is_none(),
+            _ => false,
+        }
+    }
+
+    /// Declare the names that the pattern gives a value to
+    fn pattern(&mut self, pattern: &Pattern, value_type: &Types, span: Span) {
+        match pattern {
+            Pattern::Identifier(name) if self.variant(name).is_none() => {
+                self.declare(name, value_type.clone(), true, span);
+            }
+            Pattern::Variant { name, args } => {
+                let fields = match self.variant(name) {
+                    Some((enumeration, variant)) => {
+                        // The values have the types of the type parameters of the matched value
+                        let bindings = match value_type {
+                            Types::Custom(value_enum, types)
+                                if *value_enum == *enumeration.name =>
+                            {
+                                Self::type_args(&enumeration.generics, types)
+                            }
+                            _ => Self::type_args(&enumeration.generics, &[]),
+                        };
+                        variant
+                            .fields
+                            .iter()
+                            .map(|field| field.substitute(&bindings))
+                            .collect()
+                    }
+                    None => vec![],
+                };
+                for (position, arg) in args.iter().enumerate() {
+                    let field = fields.get(position).cloned().unwrap_or(Types::Inferred);
+                    self.pattern(arg, &field, span);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The type of the items of a collection, they must have the same type
+    fn same_types(&mut self, types: &[(Types, Span)], what: &str) -> Types {
+        let mut known = types
+            .iter()
+            .filter(|(item_type, _)| *item_type != Types::Inferred);
+        let Some((item_type, _)) = known.next() else {
+            return Types::Inferred;
+        };
+        for (other, span) in known {
+            let same = match other {
+                Types::List(_) | Types::Map(..) => other.can_convert(item_type),
+                _ => other == item_type,
+            };
+            if !same {
+                let error = ParseError::TypeError(format!(
+                    "The {what} must have the same type, <{item_type}> and <{other}>."
+                ));
+                self.error(error, *span);
+            }
+        }
+        item_type.clone()
+    }
+
+    /// Check that a value can be stored in a place with the type `declared`
+    fn fits(&mut self, value: &Types, declared: &Types, span: Span, what: impl FnOnce() -> String) {
+        if !Self::can_fit(value, declared) {
+            let error = ParseError::TypeError(format!(
+                "The {} must be <{declared}> but it's <{value}>.",
+                what()
+            ));
+            self.error(error, span);
+        }
+    }
+
+    /// Like [`Checker::fits`], but the type parameters in `declared` take the type of the
+    /// first value stored in them
+    fn fits_generic(
+        &mut self,
+        value: &Types,
+        declared: &Types,
+        bindings: &mut Bindings,
+        span: Span,
+        what: impl FnOnce() -> String,
+    ) {
+        if !declared.unify(value, bindings) {
+            let error = ParseError::TypeError(format!(
+                "The {} must be <{}> but it's <{value}>.",
+                what(),
+                declared.substitute(bindings)
+            ));
+            self.error(error, span);
+        }
+    }
+
+    fn can_fit(value: &Types, declared: &Types) -> bool {
+        *value == Types::Inferred
+            || *declared == Types::Inferred
+            || value == declared
+            || value.can_convert(declared)
+    }
+}
+
+/// A value of the type, to know what an operator does with it. `None` if the type isn't known.
+fn sample(types: &Types) -> Option<Token> {
+    let value = match types {
+        Types::Int32 => Token::Int32(Int32::new(1)),
+        Types::Int64 => Token::Int64(Int64::new(1)),
+        Types::HPInt => Token::HPInt(HPInt::new(1)),
+        Types::Float => Token::Float(Float::new(1.0)),
+        Types::Double => Token::Double(Double::new(1.0)),
+        Types::String => Token::String("a".into()),
+        Types::Str => Token::Str("a".into()),
+        Types::Boolean => Token::Boolean(true),
+        Types::Void => Token::Void,
+        Types::List(item) => Token::List(sample(item).into_iter().collect()),
+        Types::Map(key, value) => Token::Map(sample(key).zip(sample(value)).into_iter().collect()),
+        // An operator can't use a value that can be `none`
+        Types::Option(_) => Token::None,
+        Types::Custom(name, _) => Token::Struct {
+            name: name.as_str().into(),
+            fields: vec![],
+        },
+        Types::Function => Token::Function(Rc::new(Func::User(Function::new(
+            "sample".into(),
+            Types::Inferred,
+            vec![],
+            vec![],
+            0,
+        )))),
+        // The type of a type parameter isn't known inside the generic declaration
+        Types::Generic(_) | Types::Inferred => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod checker_test {
+    use crate::compiler::{evaluator::Evaluator, lexer::Lexer, parser::Parser};
+
+    use super::*;
+
+    #[test]
+    fn sample_test() {
+        assert_eq!(sample(&Types::Inferred), None);
+        assert_eq!(
+            sample(&Types::List(Box::new(Types::Inferred))),
+            Some(Token::List(vec![]))
+        );
+
+        // The operators give the same type as when the program runs
+        let code = "var a = 1 + 2.5\nvar b = 2.5 * 3\nvar c = \"a\" + 'b'\nvar d = 1 < 2\n";
+        let program = Parser::new(Lexer::new(code).lex().unwrap())
+            .parse()
+            .unwrap();
+        let mut checker = Checker::new();
+        assert!(checker.check(&program).is_ok());
+
+        let mut evaluator = Evaluator::new(None, None);
+        evaluator.evaluate(&program).unwrap();
+        for (name, variable) in evaluator.get_variables() {
+            let binding = &checker.scopes[0][name.as_str()];
+            assert_eq!(checker.type_of(binding), variable.var_type);
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use std::io::{self, IsTerminal};
+
+use colored::{Color, Colorize};
+
+use super::{
+    error::{
+        function_error::FunctionError, lexic_error::LexicError, parse_error::ParseError,
+        spanned::Located,
+    },
+    span::{SourceMap, Span},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+impl Level {
+    fn name(&self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Level::Error => Color::Red,
+            Level::Warning => Color::Yellow,
+        }
+    }
+}
+
+/// A piece of code pointed by a [`Diagnostic`] with an optional explanation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    /// The primary label is the cause of the error, the others only give context
+    pub primary: bool,
+}
+
+/// An error or warning ready to be shown to the user, in the style of rustc:
+///
+/// ```text
+/// error: UndefinedVariable: The variable 'c' doesn't exist.
+///  --> main.atlas:2:13
+///   |
+/// 2 | var b = a + c
+///   |             ^
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    // MARK: Creation
+    pub fn error(message: impl Into<String>) -> Self {
+        Diagnostic {
+            level: Level::Error,
+            message: message.into(),
+            labels: vec![],
+            help: vec![],
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Diagnostic {
+            level: Level::Warning,
+            ..Diagnostic::error(message)
+        }
+    }
+
+    /// Point to the code which caused the diagnostic
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: true,
+        });
+        self
+    }
+
+    /// Point to code related with the diagnostic
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    // MARK: Render
+    /// Print the diagnostic in the stderr, with colors only if the stdout is a terminal
+    pub fn emit(&self, sources: &SourceMap) {
+        eprintln!("{}", self.render(sources, io::stdout().is_terminal()));
+    }
+
+    pub fn render(&self, sources: &SourceMap, color: bool) -> String {
+        let paint = |text: &str, color_name: Color| -> String {
+            if color {
+                text.color(color_name).bold().to_string()
+            } else {
+                text.to_string()
+            }
+        };
+        let level_color = self.level.color();
+        let message = if color {
+            self.message.bold().to_string()
+        } else {
+            self.message.clone()
+        };
+        let mut out = format!("{}: {message}", paint(self.level.name(), level_color));
+
+        // Every label that can be found in the sources, with its location and the source line
+        let mut lines = vec![];
+        for label in &self.labels {
+            let file_id = label.span.file_id;
+            let Some(location) = sources.location(label.span) else {
+                continue;
+            };
+            let (Some(text), Some(offset)) = (
+                sources.line(file_id, location.line),
+                sources.line_offset(file_id, location.line),
+            ) else {
+                continue;
+            };
+            // Multiline spans are underlined until the end of the first line
+            let start = label.span.start - offset;
+            let end = (label.span.end - offset).min(text.len());
+            let width = text
+                .get(start..end.max(start))
+                .map_or(1, |underlined| underlined.chars().count().max(1));
+            lines.push((location, width, label, text));
+        }
+
+        let Some((location, ..)) = lines
+            .iter()
+            .find(|(.., label, _)| label.primary)
+            .or(lines.first())
+        else {
+            return self.render_help(out, 0, &paint);
+        };
+        out.push_str(&format!("\n{}{location}", paint(" --> ", Color::Blue)));
+
+        lines.sort_by_key(|(location, ..)| (location.line, location.column));
+        let gutter = lines
+            .iter()
+            .map(|(location, ..)| location.line.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let empty_gutter = paint(&format!("{} |", " ".repeat(gutter)), Color::Blue);
+        out.push_str(&format!("\n{empty_gutter}"));
+
+        let mut previous_line = None;
+        for (location, width, label, text) in &lines {
+            let line = (location.line, label.span.file_id);
+            if previous_line != Some(line) {
+                let number = paint(&format!("{:>gutter$} |", location.line), Color::Blue);
+                out.push_str(&format!("\n{number} {text}"));
+                previous_line = Some(line);
+            }
+
+            let (mark, mark_color) = if label.primary {
+                ("^", level_color)
+            } else {
+                ("-", Color::Blue)
+            };
+            let mut underline = " ".repeat(location.column - 1) + &mark.repeat(*width);
+            if !label.message.is_empty() {
+                underline.push(' ');
+                underline.push_str(&label.message);
+            }
+            out.push_str(&format!(
+                "\n{empty_gutter} {}",
+                paint(&underline, mark_color)
+            ));
+        }
+
+        self.render_help(out, gutter, &paint)
+    }
+
+    fn render_help(
+        &self,
+        mut out: String,
+        gutter: usize,
+        paint: &dyn Fn(&str, Color) -> String,
+    ) -> String {
+        for help in &self.help {
+            let prefix = paint(&format!("{} = help:", " ".repeat(gutter)), Color::Blue);
+            out.push_str(&format!("\n{prefix} {help}"));
+        }
+        out
+    }
+}
+
+// MARK: Conversions
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        let mut diagnostic = Diagnostic::error(error.to_string());
+        if let Some(span) = error.span() {
+            diagnostic = diagnostic.with_label(span, "");
+        }
+
+        match error.kind() {
+            ParseError::UndefinedEOF => {
+                diagnostic.with_help("check that every '(' and '{' has been closed")
+            }
+            ParseError::ConstReassignment {
+                name, declaration, ..
+            } => diagnostic
+                .with_secondary(
+                    *declaration,
+                    format!("'{name}' is declared as a constant here"),
+                )
+                .with_help(format!("declare '{name}' with 'var' to change its value")),
+            ParseError::DefinedVariable(_) => diagnostic.with_help(
+                "use '=' to change the value of the variable instead of declaring it again",
+            ),
+            ParseError::Function(error) => {
+                let mut inner = Diagnostic::from(error);
+                if inner.labels.is_empty() {
+                    inner.labels = diagnostic.labels;
+                }
+                inner
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&FunctionError> for Diagnostic {
+    fn from(error: &FunctionError) -> Self {
+        match error {
+            FunctionError::ExecutionError(error) => Diagnostic::from(error.as_ref()),
+            error => {
+                let diagnostic = Diagnostic::error(error.to_string());
+                match error.span() {
+                    Some(span) => diagnostic.with_label(span, ""),
+                    None => diagnostic,
+                }
+            }
+        }
+    }
+}
+
+impl From<&LexicError> for Diagnostic {
+    fn from(error: &LexicError) -> Self {
+        let diagnostic = Diagnostic::error(error.to_string());
+        let diagnostic = match error.span() {
+            Some(span) => diagnostic.with_label(span, ""),
+            None => diagnostic,
+        };
+
+        match error.kind() {
+            LexicError::InvalidEscape(_) => diagnostic
+                .with_help("the valid escapes are '\\n', '\\r', '\\t', '\\\\' and the quote"),
+            _ => diagnostic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod diagnostics_test {
+    use super::*;
+
+    #[test]
+    fn render_test() {
+        let mut sources = SourceMap::new();
+        let id = sources.add("main.atlas", "var a = 1\nvar b = a + c\n");
+
+        let diagnostic = Diagnostic::error("UndefinedVariable: c")
+            .with_label(Span::new(id, 22, 23), "not found")
+            .with_secondary(Span::new(id, 4, 5), "a variable declared here")
+            .with_help("declare it with 'var'");
+
+        assert_eq!(
+            diagnostic.render(&sources, false),
+            "error: UndefinedVariable: c
+ --> main.atlas:2:13
+  |
+1 | var a = 1
+  |     - a variable declared here
+2 | var b = a + c
+  |             ^ not found
+  = help: declare it with 'var'"
+        );
+    }
+
+    #[test]
+    fn without_span_test() {
+        let diagnostic = Diagnostic::from(&ParseError::DivisionByZero);
+        assert_eq!(
+            diagnostic.render(&SourceMap::new(), false),
+            "error: DivisionByZero: You can't Divide whatever by Zero."
+        );
+    }
+}
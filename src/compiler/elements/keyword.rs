@@ -11,18 +11,27 @@ use crate::compiler::error::lexic_error::LexicError;
 /// - `Var`: Represents the `var` keyword.
 /// - `Const`: Represents the `const` keyword.
 /// - `For`: Represents the `for` keyword.
+/// - `In`: Represents the `in` keyword.
+/// - `Step`: Represents the `step` keyword.
 /// - `While`: Represents the `while` keyword.
+/// - `Break`: Represents the `break` keyword.
+/// - `Continue`: Represents the `continue` keyword.
 /// - `If`: Represents the `if` keyword.
 /// - `Else`: Represents the `else` keyword.
 /// - `Function`: Represents the `func` keyword.
+/// - `Return`: Represents the `return` keyword.
+/// - `Struct`: Represents the `struct` keyword.
+/// - `Enum`: Represents the `enum` keyword.
+/// - `Match`: Represents the `match` keyword.
 /// - `EOF`: Represents the end of file.
 /// - `True`: Represents the `true` keyword or a true boolean value.
 /// - `False`: Represents the `false` keyword or a false boolean value.
+/// - `None`: Represents the `none` keyword, the value of an optional without value.
 ///
 /// # Example
 ///
 /// ```
-/// use crate::compiler::elements::keyword::Keyword;
+/// use atlas_lang::compiler::elements::keyword::Keyword;
 /// use std::str::FromStr;
 ///
 /// let keyword = Keyword::from_str("var").unwrap();
@@ -34,13 +43,22 @@ pub enum Keyword {
     Var,
     Const,
     For,
+    In,
+    Step,
     While,
+    Break,
+    Continue,
     If,
     Else,
     Function,
+    Return,
+    Struct,
+    Enum,
+    Match,
     EOF,
     True,
     False,
+    None,
 }
 
 impl Display for Keyword {
@@ -49,13 +67,22 @@ impl Display for Keyword {
             Keyword::Var => write!(f, "Var"),
             Keyword::Const => write!(f, "Const"),
             Keyword::For => write!(f, "For"),
+            Keyword::In => write!(f, "In"),
+            Keyword::Step => write!(f, "Step"),
             Keyword::While => write!(f, "While"),
+            Keyword::Break => write!(f, "Break"),
+            Keyword::Continue => write!(f, "Continue"),
             Keyword::If => write!(f, "If"),
             Keyword::Else => write!(f, "Else"),
             Keyword::Function => write!(f, "Function"),
+            Keyword::Return => write!(f, "Return"),
+            Keyword::Struct => write!(f, "Struct"),
+            Keyword::Enum => write!(f, "Enum"),
+            Keyword::Match => write!(f, "Match"),
             Keyword::EOF => write!(f, "EOF"),
             Keyword::True => write!(f, "True"),
             Keyword::False => write!(f, "False"),
+            Keyword::None => write!(f, "None"),
             // _ => String::new(),
         }
     }
@@ -64,18 +91,29 @@ impl Display for Keyword {
 impl FromStr for Keyword {
     type Err = LexicError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        return match s.trim() {
+        match s.trim() {
             "var" => Ok(Keyword::Var),
             "const" => Ok(Keyword::Const),
             "for" => Ok(Keyword::For),
+            "in" => Ok(Keyword::In),
+            "step" => Ok(Keyword::Step),
             "while" => Ok(Keyword::While),
+            "break" => Ok(Keyword::Break),
+            "continue" => Ok(Keyword::Continue),
             "if" => Ok(Keyword::If),
             "else" => Ok(Keyword::Else),
             "EOF" => Ok(Keyword::EOF),
             "func" => Ok(Keyword::Function),
+            "return" => Ok(Keyword::Return),
+            "struct" => Ok(Keyword::Struct),
+            "enum" => Ok(Keyword::Enum),
+            "match" => Ok(Keyword::Match),
+            "true" => Ok(Keyword::True),
+            "false" => Ok(Keyword::False),
+            "none" => Ok(Keyword::None),
             _ => Err(LexicError::InvalidKeyword(format!(
                 "the word '{s}' isn't a Keyword."
             ))),
-        };
+        }
     }
 }
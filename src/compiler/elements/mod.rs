@@ -1,4 +1,3 @@
 pub mod keyword;
-pub mod operation;
 pub mod operator;
 pub mod token;
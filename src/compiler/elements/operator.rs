@@ -6,6 +6,7 @@ use crate::{
 };
 
 use super::token::Token;
+use std::fmt;
 
 /// Represents all possible operators in the language, including:
 /// - Assignment operators (e.g., `=`),
@@ -72,54 +73,138 @@ pub enum Operator {
 
     StrictEqual,
 
+    NotEqual,
+
+    StrictNotEqual,
+
+    /// Bitwise and, `a & b`
+    BitAnd,
+
+    /// Bitwise and and Assignation
+    BitAndAssign,
+
+    /// Bitwise or, `a | b`
+    BitOr,
+
+    /// Bitwise or and Assignation
+    BitOrAssign,
+
+    /// Bitwise exclusive or, `a ^ b`
+    BitXor,
+
+    /// Bitwise exclusive or and Assignation
+    BitXorAssign,
+
+    /// Bitwise not, `~a`
+    BitNot,
+
+    /// Shift the bits to the left, `a << b`
+    ShiftLeft,
+
+    /// Shift to the left and Assignation
+    ShiftLeftAssign,
+
+    /// Shift the bits to the right keeping the sign, `a >> b`
+    ShiftRight,
+
+    /// Shift to the right and Assignation
+    ShiftRightAssign,
+
+    /// Logical and, `a && b`
+    And,
+
+    /// Logical or, `a || b`
+    Or,
+
+    /// Logical not, `!a`
+    Not,
+
+    /// The value of an optional or a default value if it's `none`, `a ?? b`
+    Coalesce,
+
+    /// Range without the end, `0..10`
+    Range,
+
+    /// Range with the end, `0..=10`
+    RangeInclusive,
+
     /// useless operator, It doesn't have use
     Null,
 }
 
-impl ToString for Operator {
-    fn to_string(&self) -> String {
-        match self {
-            Operator::Assign => String::from("="),
-            Operator::Add => String::from("+"),
-            Operator::AddAssign => String::from("+="),
-            Operator::Sub => String::from("-"),
-            Operator::SubAssign => String::from("-="),
-            Operator::Mul => String::from("*"),
-            Operator::MulAssign => String::from("*="),
-            Operator::Pow => String::from("**"),
-            Operator::PowAssign => String::from("**="),
-            Operator::Div => String::from("/"),
-            Operator::DivAssign => String::from("/="),
-            Operator::DivInt => String::from("//"),
-            Operator::DivIntAssign => String::from("//="),
-            Operator::Mod => String::from("%"),
-            Operator::ModAssign => String::from("%="),
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match self {
+            Operator::Assign => "=",
+            Operator::Add => "+",
+            Operator::AddAssign => "+=",
+            Operator::Sub => "-",
+            Operator::SubAssign => "-=",
+            Operator::Mul => "*",
+            Operator::MulAssign => "*=",
+            Operator::Pow => "**",
+            Operator::PowAssign => "**=",
+            Operator::Div => "/",
+            Operator::DivAssign => "/=",
+            Operator::DivInt => "//",
+            Operator::DivIntAssign => "//=",
+            Operator::Mod => "%",
+            Operator::ModAssign => "%=",
 
             // MARK: Comparation
-            Operator::Greater => String::from(">"),
-            Operator::GreaterOrEqual => String::from(">="),
-            Operator::Lower => String::from("<"),
-            Operator::LowerOrEqual => String::from("<="),
-            Operator::Equal => String::from("=="),
-            Operator::StrictEqual => String::from("==="),
-
-            Operator::Null => String::from("null"),
-            // _ => String::from(""),
-        }
+            Operator::Greater => ">",
+            Operator::GreaterOrEqual => ">=",
+            Operator::Lower => "<",
+            Operator::LowerOrEqual => "<=",
+            Operator::Equal => "==",
+            Operator::StrictEqual => "===",
+            Operator::NotEqual => "!=",
+            Operator::StrictNotEqual => "!==",
+
+            // MARK: Bitwise
+            Operator::BitAnd => "&",
+            Operator::BitAndAssign => "&=",
+            Operator::BitOr => "|",
+            Operator::BitOrAssign => "|=",
+            Operator::BitXor => "^",
+            Operator::BitXorAssign => "^=",
+            Operator::BitNot => "~",
+            Operator::ShiftLeft => "<<",
+            Operator::ShiftLeftAssign => "<<=",
+            Operator::ShiftRight => ">>",
+            Operator::ShiftRightAssign => ">>=",
+
+            // MARK: Logical
+            Operator::And => "&&",
+            Operator::Or => "||",
+            Operator::Not => "!",
+            Operator::Coalesce => "??",
+
+            // MARK: Ranges
+            Operator::Range => "..",
+            Operator::RangeInclusive => "..=",
+
+            Operator::Null => "null",
+        };
+        write!(f, "{operator}")
     }
 }
 
 #[allow(dead_code)]
 impl Operator {
     pub fn execute(&self, left: Token, right: Token) -> Result<Token, ParseError> {
-        let mut left = left;
-        let mut right = right;
-        if let Token::Operation(mut op) = left {
-            left = op.resolve().unwrap();
-        }
-        if let Token::Operation(mut op) = right {
-            right = op.resolve().unwrap();
+        let compares = matches!(
+            self,
+            Self::Equal
+                | Self::StrictEqual
+                | Self::NotEqual
+                | Self::StrictNotEqual
+                | Self::Coalesce
+        );
+        if !compares && (left == Token::None || right == Token::None) {
+            return Err(self.none_error());
         }
+
         match self {
             Self::Add | Self::AddAssign => match (left, right) {
                 (Token::Int32(val1), Token::Int32(val2)) => {
@@ -410,7 +495,7 @@ impl Operator {
                             return Ok(Token::Double(Double::from(*num1)));
                         }
 
-                        return Ok(Token::Double(Double::from(num1.powi(*power))));
+                        Ok(Token::Double(Double::from(num1.powi(*power))))
                     }
 
                     _ => Err(ParseError::InvalidOperation {
@@ -620,6 +705,17 @@ impl Operator {
                 }),
             },
 
+            Self::BitAnd
+            | Self::BitAndAssign
+            | Self::BitOr
+            | Self::BitOrAssign
+            | Self::BitXor
+            | Self::BitXorAssign
+            | Self::ShiftLeft
+            | Self::ShiftLeftAssign
+            | Self::ShiftRight
+            | Self::ShiftRightAssign => self.bitwise(left, right),
+
             Self::Greater => match (left, right.clone()) {
                 (Token::Int32(num), num2) => match num2 {
                     Token::Int32(int32) => Ok(Token::Boolean(*num > *int32)),
@@ -684,13 +780,16 @@ impl Operator {
                 (Token::String(str1), Token::String(str2)) => Ok(Token::Boolean(str1 > str2)),
                 (Token::Str(str1), Token::Str(str2)) => Ok(Token::Boolean(str1 > str2)),
                 (Token::String(str1), Token::Str(str2)) => {
-                    Ok(Token::Boolean(str1.to_string() > str2.to_string()))
+                    Ok(Token::Boolean(str1 > str2.to_string()))
                 }
                 (Token::Str(str1), Token::String(str2)) => {
-                    Ok(Token::Boolean(str1.to_string() > str2.to_string()))
+                    Ok(Token::Boolean(str1.to_string() > str2))
                 }
 
-                _ => todo!("Cmp"),
+                (left, right) => Err(ParseError::NotOrd {
+                    type1: Types::from(left).to_string(),
+                    type2: Types::from(right).to_string(),
+                }),
             },
 
             Self::GreaterOrEqual => match (left, right.clone()) {
@@ -757,13 +856,16 @@ impl Operator {
                 (Token::String(str1), Token::String(str2)) => Ok(Token::Boolean(str1 >= str2)),
                 (Token::Str(str1), Token::Str(str2)) => Ok(Token::Boolean(str1 >= str2)),
                 (Token::String(str1), Token::Str(str2)) => {
-                    Ok(Token::Boolean(str1.to_string() >= str2.to_string()))
+                    Ok(Token::Boolean(str1 >= str2.to_string()))
                 }
                 (Token::Str(str1), Token::String(str2)) => {
-                    Ok(Token::Boolean(str1.to_string() >= str2.to_string()))
+                    Ok(Token::Boolean(str1.to_string() >= str2))
                 }
 
-                _ => todo!("Cmp"),
+                (left, right) => Err(ParseError::NotOrd {
+                    type1: Types::from(left).to_string(),
+                    type2: Types::from(right).to_string(),
+                }),
             },
 
             Self::Lower => match (left, right.clone()) {
@@ -830,22 +932,25 @@ impl Operator {
                 (Token::String(str1), Token::String(str2)) => Ok(Token::Boolean(str1 < str2)),
                 (Token::Str(str1), Token::Str(str2)) => Ok(Token::Boolean(str1 < str2)),
                 (Token::String(str1), Token::Str(str2)) => {
-                    Ok(Token::Boolean(str1.to_string() < str2.to_string()))
+                    Ok(Token::Boolean(str1 < str2.to_string()))
                 }
                 (Token::Str(str1), Token::String(str2)) => {
-                    Ok(Token::Boolean(str1.to_string() < str2.to_string()))
+                    Ok(Token::Boolean(str1.to_string() < str2))
                 }
 
-                _ => todo!("Cmp"),
+                (left, right) => Err(ParseError::NotOrd {
+                    type1: Types::from(left).to_string(),
+                    type2: Types::from(right).to_string(),
+                }),
             },
 
             Self::LowerOrEqual => match (left, right.clone()) {
                 (Token::Int32(num), num2) => match num2 {
-                    Token::Int32(int32) => Ok(Token::Boolean(*num > *int32)),
-                    Token::Int64(int64) => Ok(Token::Boolean(*num > *int64 as i32)),
-                    Token::HPInt(hpint) => Ok(Token::Boolean(*num > *hpint as i32)),
-                    Token::Float(float) => Ok(Token::Boolean(*num > *float as i32)),
-                    Token::Double(double) => Ok(Token::Boolean(*num > *double as i32)),
+                    Token::Int32(int32) => Ok(Token::Boolean(*num <= *int32)),
+                    Token::Int64(int64) => Ok(Token::Boolean(*num <= *int64 as i32)),
+                    Token::HPInt(hpint) => Ok(Token::Boolean(*num <= *hpint as i32)),
+                    Token::Float(float) => Ok(Token::Boolean(*num <= *float as i32)),
+                    Token::Double(double) => Ok(Token::Boolean(*num <= *double as i32)),
                     _ => Err(ParseError::NotOrd {
                         type1: String::from("Int32"),
                         type2: Types::from(num2).to_string(),
@@ -853,11 +958,11 @@ impl Operator {
                 },
 
                 (Token::Int64(num), num2) => match num2 {
-                    Token::Int32(int32) => Ok(Token::Boolean(*num > *int32 as i64)),
-                    Token::Int64(int64) => Ok(Token::Boolean(*num > *int64)),
-                    Token::HPInt(hpint) => Ok(Token::Boolean(*num > *hpint as i64)),
-                    Token::Float(float) => Ok(Token::Boolean(*num > *float as i64)),
-                    Token::Double(double) => Ok(Token::Boolean(*num > *double as i64)),
+                    Token::Int32(int32) => Ok(Token::Boolean(*num <= *int32 as i64)),
+                    Token::Int64(int64) => Ok(Token::Boolean(*num <= *int64)),
+                    Token::HPInt(hpint) => Ok(Token::Boolean(*num <= *hpint as i64)),
+                    Token::Float(float) => Ok(Token::Boolean(*num <= *float as i64)),
+                    Token::Double(double) => Ok(Token::Boolean(*num <= *double as i64)),
                     _ => Err(ParseError::NotOrd {
                         type1: String::from("Int64"),
                         type2: Types::from(num2).to_string(),
@@ -865,11 +970,11 @@ impl Operator {
                 },
 
                 (Token::HPInt(num), num2) => match num2 {
-                    Token::Int32(int32) => Ok(Token::Boolean(*num > *int32 as i128)),
-                    Token::Int64(int64) => Ok(Token::Boolean(*num > *int64 as i128)),
-                    Token::HPInt(hpint) => Ok(Token::Boolean(*num > *hpint)),
-                    Token::Float(float) => Ok(Token::Boolean(*num > *float as i128)),
-                    Token::Double(double) => Ok(Token::Boolean(*num > *double as i128)),
+                    Token::Int32(int32) => Ok(Token::Boolean(*num <= *int32 as i128)),
+                    Token::Int64(int64) => Ok(Token::Boolean(*num <= *int64 as i128)),
+                    Token::HPInt(hpint) => Ok(Token::Boolean(*num <= *hpint)),
+                    Token::Float(float) => Ok(Token::Boolean(*num <= *float as i128)),
+                    Token::Double(double) => Ok(Token::Boolean(*num <= *double as i128)),
                     _ => Err(ParseError::NotOrd {
                         type1: String::from("HPInt"),
                         type2: Types::from(num2).to_string(),
@@ -877,11 +982,11 @@ impl Operator {
                 },
 
                 (Token::Float(num), num2) => match num2 {
-                    Token::Int32(int32) => Ok(Token::Boolean(*num > *int32 as f32)),
-                    Token::Int64(int64) => Ok(Token::Boolean(*num > *int64 as f32)),
-                    Token::HPInt(hpint) => Ok(Token::Boolean(*num > *hpint as f32)),
-                    Token::Float(float) => Ok(Token::Boolean(*num > *float)),
-                    Token::Double(double) => Ok(Token::Boolean(*num > *double as f32)),
+                    Token::Int32(int32) => Ok(Token::Boolean(*num <= *int32 as f32)),
+                    Token::Int64(int64) => Ok(Token::Boolean(*num <= *int64 as f32)),
+                    Token::HPInt(hpint) => Ok(Token::Boolean(*num <= *hpint as f32)),
+                    Token::Float(float) => Ok(Token::Boolean(*num <= *float)),
+                    Token::Double(double) => Ok(Token::Boolean(*num <= *double as f32)),
                     _ => Err(ParseError::NotOrd {
                         type1: String::from("Float"),
                         type2: Types::from(num2).to_string(),
@@ -889,11 +994,11 @@ impl Operator {
                 },
 
                 (Token::Double(num), num2) => match num2 {
-                    Token::Int32(int32) => Ok(Token::Boolean(*num > *int32 as f64)),
-                    Token::Int64(int64) => Ok(Token::Boolean(*num > *int64 as f64)),
-                    Token::HPInt(hpint) => Ok(Token::Boolean(*num > *hpint as f64)),
-                    Token::Float(float) => Ok(Token::Boolean(*num > *float as f64)),
-                    Token::Double(double) => Ok(Token::Boolean(*num > *double)),
+                    Token::Int32(int32) => Ok(Token::Boolean(*num <= *int32 as f64)),
+                    Token::Int64(int64) => Ok(Token::Boolean(*num <= *int64 as f64)),
+                    Token::HPInt(hpint) => Ok(Token::Boolean(*num <= *hpint as f64)),
+                    Token::Float(float) => Ok(Token::Boolean(*num <= *float as f64)),
+                    Token::Double(double) => Ok(Token::Boolean(*num <= *double)),
                     _ => Err(ParseError::NotOrd {
                         type1: String::from("Double"),
                         type2: Types::from(num2).to_string(),
@@ -903,19 +1008,40 @@ impl Operator {
                 (Token::String(str1), Token::String(str2)) => Ok(Token::Boolean(str1 <= str2)),
                 (Token::Str(str1), Token::Str(str2)) => Ok(Token::Boolean(str1 <= str2)),
                 (Token::String(str1), Token::Str(str2)) => {
-                    Ok(Token::Boolean(str1.to_string() <= str2.to_string()))
+                    Ok(Token::Boolean(str1 <= str2.to_string()))
                 }
                 (Token::Str(str1), Token::String(str2)) => {
-                    Ok(Token::Boolean(str1.to_string() <= str2.to_string()))
+                    Ok(Token::Boolean(str1.to_string() <= str2))
                 }
 
-                _ => todo!("Cmp"),
+                (left, right) => Err(ParseError::NotOrd {
+                    type1: Types::from(left).to_string(),
+                    type2: Types::from(right).to_string(),
+                }),
             },
 
             Self::Equal => Ok(Token::Boolean(left == right)),
             Self::StrictEqual => Ok(Token::Boolean(
                 left == right && Types::from(left) == Types::from(right),
             )),
+            Self::NotEqual => Ok(Token::Boolean(left != right)),
+            Self::StrictNotEqual => Ok(Token::Boolean(
+                left != right || Types::from(left) != Types::from(right),
+            )),
+
+            // Both sides are already evaluated here, the evaluator skips the right side when
+            // the left one decides the result
+            Self::And => Ok(Token::Boolean(
+                left.as_condition()? && right.as_condition()?,
+            )),
+            Self::Or => Ok(Token::Boolean(
+                left.as_condition()? || right.as_condition()?,
+            )),
+            // Like `&&` and `||`, the evaluator only runs the right side if it's needed
+            Self::Coalesce => match left {
+                Token::None => Ok(right),
+                left => Ok(left),
+            },
 
             _ => todo!("Hola mundo"),
         }
@@ -1091,7 +1217,12 @@ impl Operator {
     fn mul_integer_integer(left: Integer, right: Integer) -> Result<Token, ParseError> {
         match (left, right) {
             (Integer::Int32(num_1), Integer::Int32(num_2)) => {
-                Ok(Token::Int64(Int64::from((*num_1 * *num_2) as i64)))
+                let result = *num_1 as i64 * *num_2 as i64;
+                if let Ok(value) = i32::try_from(result) {
+                    Ok(Token::Int32(Int32::from(value)))
+                } else {
+                    Ok(Token::Int64(Int64::from(result)))
+                }
             }
             (Integer::Int32(num_1), Integer::Int64(num_2)) => {
                 Ok(Token::Int64(Int64::from(*num_1 as i64 * *num_2)))
@@ -1113,7 +1244,7 @@ impl Operator {
                 Double::new(*num1 as f64) * Double::new(*num2 as f64),
             )),
             (Number::Int32(num1), Number::Double(num2)) => Ok(Token::Double(
-                Double::new(*num1 as f64) * Double::new(*num2 as f64),
+                Double::new(*num1 as f64) * Double::new(*num2),
             )),
 
             (Number::Int64(num1), Number::Int32(num2)) => {
@@ -1126,7 +1257,7 @@ impl Operator {
                 Double::new(*num1 as f64) * Double::new(*num2 as f64),
             )),
             (Number::Int64(num1), Number::Double(num2)) => Ok(Token::Double(
-                Double::new(*num1 as f64) * Double::new(*num2 as f64),
+                Double::new(*num1 as f64) * Double::new(*num2),
             )),
 
             (Number::Float(num1), Number::Int32(num2)) => Ok(Token::Double(
@@ -1181,7 +1312,7 @@ impl Operator {
             _ => Err(ParseError::InvalidOperation {
                 operation: Self::Mul.to_string(),
                 type1: Types::String.to_string(),
-                type2: Types::inferred(&right)?.to_string(),
+                type2: Types::inferred(right)?.to_string(),
             }),
         }
     }
@@ -1216,7 +1347,7 @@ impl Operator {
                 Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
             }
             (Number::Int32(num1), Number::Double(num2)) => {
-                Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
+                Ok(Token::Double(Double::from((*num1 as f64) / *num2)))
             }
 
             (Number::Int64(num1), Number::Int32(num2)) => {
@@ -1229,7 +1360,7 @@ impl Operator {
                 Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
             }
             (Number::Int64(num1), Number::Double(num2)) => {
-                Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
+                Ok(Token::Double(Double::from((*num1 as f64) / *num2)))
             }
 
             (Number::Float(num1), Number::Int32(num2)) => {
@@ -1246,10 +1377,10 @@ impl Operator {
             }
 
             (Number::Double(num1), Number::Int32(num2)) => {
-                Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
+                Ok(Token::Double(Double::from(*num1 / (*num2 as f64))))
             }
             (Number::Double(num1), Number::Int64(num2)) => {
-                Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
+                Ok(Token::Double(Double::from(*num1 / (*num2 as f64))))
             }
             (Number::Double(num1), Number::Float(num2)) => {
                 Self::div_decimal_decimal(Decimal::Double(num1), Decimal::Float(num2))
@@ -1266,12 +1397,14 @@ impl Operator {
                 Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
             }
             (Decimal::Float(num1), Decimal::Double(num2)) => {
-                Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
+                Ok(Token::Double(Double::from((*num1 as f64) / *num2)))
             }
             (Decimal::Double(num1), Decimal::Float(num2)) => {
-                Ok(Token::Double(Double::from((*num1 as f64) / (*num2 as f64))))
+                Ok(Token::Double(Double::from(*num1 / (*num2 as f64))))
+            }
+            (Decimal::Double(num1), Decimal::Double(num2)) => {
+                Ok(Token::Double(Double::from(*num1 / *num2)))
             }
-            (Decimal::Double(num1), Decimal::Double(num2)) => Ok(Token::Double(num1 * num2)),
         }
     }
 
@@ -1284,7 +1417,7 @@ impl Operator {
             _ => Err(ParseError::InvalidOperation {
                 operation: Self::Div.to_string(),
                 type1: Types::String.to_string(),
-                type2: Types::inferred(&right)?.to_string(),
+                type2: Types::inferred(right)?.to_string(),
             }),
         }
     }
@@ -1581,17 +1714,149 @@ impl Operator {
         }
     }
 
+    // MARK: Bitwise
+    /// The bitwise operators only work with integers, the result has the type of the widest
+    /// operand.
+    fn bitwise(&self, left: Token, right: Token) -> Result<Token, ParseError> {
+        let (Some((num1, type1)), Some((num2, type2))) = (Self::bits(&left), Self::bits(&right))
+        else {
+            return Err(ParseError::InvalidOperation {
+                operation: self.to_string(),
+                type1: Types::from(left).to_string(),
+                type2: Types::from(right).to_string(),
+            });
+        };
+        let (width, result_type) = if type1 == Types::HPInt || type2 == Types::HPInt {
+            (128, Types::HPInt)
+        } else if type1 == Types::Int64 || type2 == Types::Int64 {
+            (64, Types::Int64)
+        } else {
+            (32, Types::Int32)
+        };
+
+        let result = match self {
+            Self::BitAnd | Self::BitAndAssign => num1 & num2,
+            Self::BitOr | Self::BitOrAssign => num1 | num2,
+            Self::BitXor | Self::BitXorAssign => num1 ^ num2,
+            _ => {
+                if !(0..width).contains(&num2) {
+                    return Err(ParseError::InvalidShift(format!(
+                        "A <{result_type}> can be shifted from 0 to {} bits but it's {num2}.",
+                        width - 1
+                    )));
+                }
+                // The values are sign-extended, so truncating the result gives the same bits
+                // as shifting with the real size
+                if matches!(self, Self::ShiftLeft | Self::ShiftLeftAssign) {
+                    num1 << num2
+                } else {
+                    num1 >> num2
+                }
+            }
+        };
+
+        Ok(match result_type {
+            Types::Int32 => Token::Int32(Int32::new(result as i32)),
+            Types::Int64 => Token::Int64(Int64::new(result as i64)),
+            _ => Token::HPInt(HPInt::new(result)),
+        })
+    }
+
+    fn bits(value: &Token) -> Option<(i128, Types)> {
+        match value {
+            Token::Int32(num) => Some((**num as i128, Types::Int32)),
+            Token::Int64(num) => Some((**num as i128, Types::Int64)),
+            Token::HPInt(num) => Some((**num, Types::HPInt)),
+            _ => None,
+        }
+    }
+
+    /// Apply the operator to a single operand, like `-10` or `+10`
+    pub fn execute_unary(&self, operand: Token) -> Result<Token, ParseError> {
+        match (self, operand) {
+            (_, Token::None) => Err(self.none_error()),
+            (Self::Add, operand) if operand.is_numeric() => Ok(operand),
+            (Self::Sub, Token::Int32(num)) => Ok(Token::Int32(Int32::from(-*num))),
+            (Self::Sub, Token::Int64(num)) => Ok(Token::Int64(Int64::from(-*num))),
+            (Self::Sub, Token::HPInt(num)) => Ok(Token::HPInt(HPInt::from(-*num))),
+            (Self::Sub, Token::Float(num)) => Ok(Token::Float(Float::from(-*num))),
+            (Self::Sub, Token::Double(num)) => Ok(Token::Double(Double::from(-*num))),
+            (Self::BitNot, Token::Int32(num)) => Ok(Token::Int32(Int32::new(!*num))),
+            (Self::BitNot, Token::Int64(num)) => Ok(Token::Int64(Int64::new(!*num))),
+            (Self::BitNot, Token::HPInt(num)) => Ok(Token::HPInt(HPInt::new(!*num))),
+            (Self::Not, operand) if operand.as_condition().is_ok() => {
+                Ok(Token::Boolean(!operand.as_bool()))
+            }
+            (_, operand) => Err(ParseError::InvalidUnaryOperation {
+                operation: self.to_string(),
+                type1: Types::inferred(&operand)?.to_string(),
+            }),
+        }
+    }
+
+    /// A value that can be `none` is used like a value of its type
+    fn none_error(&self) -> ParseError {
+        ParseError::TypeError(format!(
+            "The operator '{self}' can't use a value that can be 'none', give it a default value with '??'."
+        ))
+    }
+
+    /// Binding power of the unary `+`, `-`, `!` and `~`, `-2 ** 2` is `-(2 ** 2)`
+    pub const PREFIX_BINDING_POWER: u8 = 23;
+
+    /// Binding power `(left, right)` of the binary operators, the higher binds tighter.
+    ///
+    /// The left power is lower than the right one for the left-associative operators, and
+    /// greater for the right-associative ones like `**`.
+    pub fn binding_power(&self) -> Option<(u8, u8)> {
+        let power = match self {
+            Self::Coalesce => (2, 1),
+            Self::Or => (3, 4),
+            Self::And => (5, 6),
+            Self::Equal | Self::StrictEqual | Self::NotEqual | Self::StrictNotEqual => (7, 8),
+            Self::Greater | Self::GreaterOrEqual | Self::Lower | Self::LowerOrEqual => (9, 10),
+            Self::BitOr => (11, 12),
+            Self::BitXor => (13, 14),
+            Self::BitAnd => (15, 16),
+            Self::ShiftLeft | Self::ShiftRight => (17, 18),
+            Self::Add | Self::Sub => (19, 20),
+            Self::Mul | Self::Div | Self::DivInt | Self::Mod => (21, 22),
+            Self::Pow => (25, 24),
+            _ => return None,
+        };
+        Some(power)
+    }
+
+    /// Check if the type of the result with integers depends on their values, like `*` that
+    /// gives an `Int64` when the product doesn't fit in an `Int32`
+    pub fn widens(&self) -> bool {
+        matches!(
+            self,
+            Self::Mul
+                | Self::MulAssign
+                | Self::Pow
+                | Self::PowAssign
+                | Self::DivInt
+                | Self::DivIntAssign
+        )
+    }
+
     pub fn is_assignation(&self) -> bool {
-        match self {
+        matches!(
+            self,
             Self::AddAssign
-            | Self::SubAssign
-            | Self::MulAssign
-            | Self::DivAssign
-            | Self::ModAssign
-            | Self::PowAssign
-            | Self::DivIntAssign => true,
-            _ => false,
-        }
+                | Self::SubAssign
+                | Self::MulAssign
+                | Self::DivAssign
+                | Self::ModAssign
+                | Self::PowAssign
+                | Self::DivIntAssign
+                | Self::BitAndAssign
+                | Self::BitOrAssign
+                | Self::BitXorAssign
+                | Self::ShiftLeftAssign
+                | Self::ShiftRightAssign
+        )
     }
 }
 
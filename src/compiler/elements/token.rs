@@ -1,11 +1,12 @@
 use std::{
     fmt::{Debug, Display},
+    rc::Rc,
     sync::Arc,
 };
 
-use super::{keyword::Keyword, operation::Operation, operator::Operator};
+use super::{keyword::Keyword, operator::Operator};
 use crate::{
-    compiler::{error::parse_error::ParseError, types::Types},
+    compiler::{error::parse_error::ParseError, function_table::Func, span::Span, types::Types},
     types::basic::number::{
         double::Double, float::Float, hpint::HPInt, int32::Int32, int64::Int64,
     },
@@ -19,7 +20,6 @@ pub enum Token {
     /// used to represent variables or functions
     Identifier(Arc<str>),
     Keyword(Keyword),
-    Operation(Operation),
     Operator(Operator),
     Type(Types),
     // Argument(Argument<'a>),
@@ -34,7 +34,23 @@ pub enum Token {
     Str(Arc<str>),
     Boolean(bool),
     List(Vec<Token>),
-    // Function(Arc<str>),
+    /// The entries in the order in which they were inserted
+    Map(Vec<(Token, Token)>),
+    /// A value of a struct, the fields are in the order of the declaration
+    Struct {
+        name: Arc<str>,
+        fields: Vec<(Arc<str>, Token)>,
+    },
+    /// A value of an enum, the variant with the values of its payload
+    Variant {
+        enumeration: Arc<str>,
+        name: Arc<str>,
+        values: Vec<Token>,
+    },
+    /// A function used as a value, like a lambda or `print` stored in a variable
+    Function(Rc<Func>),
+    /// `none`, the value of an optional type like `Int32?` when it doesn't have a value
+    None,
 
     // Others
     StartParenthesis, // (
@@ -44,9 +60,16 @@ pub enum Token {
     StartBrace,       // {
     EndBrace,         // }
     Separator(char),  // ',', ';'
+    Arrow,            // ->
+    FatArrow,         // =>
+    Ellipsis,         // ...
+    Question,         // ?
+    QuestionDot,      // ?.
     NewLine,          // \n
-    EOF,              // EOF
-    Void,             // void
+    /// `# comment` or `#[ comment ]#`, only produced if the lexer keeps the trivia
+    Comment(Arc<str>),
+    EOF,  // EOF
+    Void, // void
 }
 
 impl PartialEq for Token {
@@ -56,13 +79,11 @@ impl PartialEq for Token {
 
             (Token::Keyword(val1), Token::Keyword(val2)) => val1 == val2,
 
-            (Token::Operation(val1), Token::Operation(val2)) => val1 == val2,
-
             (Token::Operator(val1), Token::Operator(val2)) => val1 == val2,
 
             (Token::Type(val1), Token::Type(val2)) => val1 == val2,
 
-            (Token::Int32(val1), Token::Int32(val2)) => **val1 as i32 == **val2,
+            (Token::Int32(val1), Token::Int32(val2)) => **val1 == **val2,
             (Token::Int32(val1), Token::Int64(val2)) => **val1 as i64 == **val2,
             (Token::Int32(val1), Token::HPInt(val2)) => **val1 as i128 == **val2,
             (Token::Int32(val1), Token::Float(val2)) => **val1 as f32 == **val2,
@@ -74,7 +95,7 @@ impl PartialEq for Token {
             }
 
             (Token::Int64(val1), Token::Int32(val2)) => **val1 as i32 == **val2,
-            (Token::Int64(val1), Token::Int64(val2)) => **val1 as i64 == **val2,
+            (Token::Int64(val1), Token::Int64(val2)) => **val1 == **val2,
             (Token::Int64(val1), Token::HPInt(val2)) => **val1 as i128 == **val2,
             (Token::Int64(val1), Token::Float(val2)) => **val1 as f32 == **val2,
             (Token::Int64(val1), Token::Double(val2)) => **val1 == **val2 as i64,
@@ -86,7 +107,7 @@ impl PartialEq for Token {
 
             (Token::HPInt(val1), Token::Int32(val2)) => **val1 as i32 == **val2,
             (Token::HPInt(val1), Token::Int64(val2)) => **val1 as i64 == **val2,
-            (Token::HPInt(val1), Token::HPInt(val2)) => **val1 as i128 == **val2,
+            (Token::HPInt(val1), Token::HPInt(val2)) => **val1 == **val2,
             (Token::HPInt(val1), Token::Float(val2)) => **val1 as f32 == **val2,
             (Token::HPInt(val1), Token::Double(val2)) => **val1 == **val2 as i128,
             (Token::HPInt(val1), Token::String(val2)) => val1.to_string() == *val2,
@@ -98,7 +119,7 @@ impl PartialEq for Token {
             (Token::Float(val1), Token::Int32(val2)) => **val1 as i32 == **val2,
             (Token::Float(val1), Token::Int64(val2)) => **val1 as i64 == **val2,
             (Token::Float(val1), Token::HPInt(val2)) => **val1 as i128 == **val2,
-            (Token::Float(val1), Token::Float(val2)) => **val1 as f32 == **val2,
+            (Token::Float(val1), Token::Float(val2)) => **val1 == **val2,
             (Token::Float(val1), Token::Double(val2)) => **val1 == **val2 as f32,
             (Token::Float(val1), Token::String(val2)) => val1.to_string() == *val2,
             (Token::Float(val1), Token::Str(val2)) => val1.to_string() == val2.to_string(),
@@ -127,6 +148,43 @@ impl PartialEq for Token {
 
             (Token::List(val1), Token::List(val2)) => val1 == val2,
 
+            // The order of the entries doesn't matter to compare two maps
+            (Token::Map(val1), Token::Map(val2)) => {
+                val1.len() == val2.len()
+                    && val1.iter().all(|(key, value)| {
+                        val2.iter()
+                            .any(|(key2, value2)| key.is_same_key(key2) && value == value2)
+                    })
+            }
+
+            (
+                Token::Struct {
+                    name: name1,
+                    fields: fields1,
+                },
+                Token::Struct {
+                    name: name2,
+                    fields: fields2,
+                },
+            ) => name1 == name2 && fields1 == fields2,
+
+            (
+                Token::Variant {
+                    enumeration: enum1,
+                    name: name1,
+                    values: values1,
+                },
+                Token::Variant {
+                    enumeration: enum2,
+                    name: name2,
+                    values: values2,
+                },
+            ) => enum1 == enum2 && name1 == name2 && values1 == values2,
+
+            (Token::Function(func1), Token::Function(func2)) => {
+                Rc::ptr_eq(func1, func2) || func1 == func2
+            }
+
             (Token::StartParenthesis, Token::StartParenthesis) => true,
 
             (Token::EndParenthesis, Token::EndParenthesis) => true,
@@ -141,8 +199,22 @@ impl PartialEq for Token {
 
             (Token::Separator(val1), Token::Separator(val2)) => val1 == val2,
 
+            (Token::Arrow, Token::Arrow) => true,
+
+            (Token::FatArrow, Token::FatArrow) => true,
+
+            (Token::Ellipsis, Token::Ellipsis) => true,
+
+            (Token::Question, Token::Question) => true,
+
+            (Token::QuestionDot, Token::QuestionDot) => true,
+
+            (Token::None, Token::None) => true,
+
             (Token::NewLine, Token::NewLine) => true,
 
+            (Token::Comment(val1), Token::Comment(val2)) => val1 == val2,
+
             (Token::EOF, Token::EOF) => true,
 
             (Token::Void, Token::Void) => true,
@@ -154,18 +226,25 @@ impl PartialEq for Token {
 impl Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Token::Identifier(id) => write!(f, "{}", id.to_string()),
-            Token::Keyword(keyword) => write!(f, "{}", String::from(keyword.to_string())),
+            Token::Identifier(id) => write!(f, "{}", id),
+            Token::Keyword(keyword) => write!(f, "{}", keyword),
             Token::Int32(num) => write!(f, "{num}"),
             Token::Int64(num) => write!(f, "{num}"),
             Token::HPInt(num) => write!(f, "{num}"),
             Token::Float(num) => write!(f, "{num}"),
             Token::Double(num) => write!(f, "{num}"),
             Token::NewLine => write!(f, "{}", String::from("\n")),
+            Token::Comment(comment) => write!(f, "{comment}"),
             Token::EOF => write!(f, "{}", String::from("EOF")),
             Token::Void => write!(f, "{}", String::from("Void")),
-            Token::Operator(op) => write!(f, "{}", String::from(op.to_string())),
+            Token::Operator(op) => write!(f, "{op}"),
             Token::Separator(op) => write!(f, "{op}"),
+            Token::Arrow => write!(f, "->"),
+            Token::FatArrow => write!(f, "=>"),
+            Token::Ellipsis => write!(f, "..."),
+            Token::Question => write!(f, "?"),
+            Token::QuestionDot => write!(f, "?."),
+            Token::None => write!(f, "none"),
             Token::String(string) => {
                 if string.len() <= 1 {
                     write!(f, "{}", string.chars().next().unwrap_or_default())
@@ -174,6 +253,31 @@ impl Display for Token {
                 }
             }
             Token::Str(string) => write!(f, "{string}"),
+            Token::Boolean(boolean) => write!(f, "{boolean}"),
+            Token::List(items) => {
+                let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            Token::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }
+            Token::Struct { name, fields } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(field, value)| format!("{field}: {value}"))
+                    .collect();
+                write!(f, "{name} {{ {} }}", fields.join(", "))
+            }
+            Token::Variant { name, values, .. } if values.is_empty() => write!(f, "{name}"),
+            Token::Variant { name, values, .. } => {
+                let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+                write!(f, "{name}({})", values.join(", "))
+            }
+            Token::Function(func) => write!(f, "<func {}>", func.name()),
             _ => write!(f, "{}", String::from("funcion")),
         }
     }
@@ -190,15 +294,15 @@ impl Token {
 
         match num_type {
             Types::Int32 => match id.parse::<i32>() {
-                Ok(value) => Token::Int32(Int32::new(value as i32)),
+                Ok(value) => Token::Int32(Int32::new(value)),
                 Err(_) => Token::EOF, // Si no se puede parsear, devolvemos un Token::EOF
             },
             Types::Int64 => match id.parse::<i64>() {
-                Ok(value) => Token::Int64(Int64::new(value as i64)),
+                Ok(value) => Token::Int64(Int64::new(value)),
                 Err(_) => Token::EOF, // Si no se puede parsear, devolvemos un Token::EOF
             },
             Types::HPInt => match id.parse::<i64>() {
-                Ok(value) => Token::Int64(Int64::new(value as i64)),
+                Ok(value) => Token::Int64(Int64::new(value)),
                 Err(_) => Token::EOF, // Si no se puede parsear, devolvemos un Token::EOF
             },
             Types::Double => match id.parse::<f64>() {
@@ -206,47 +310,38 @@ impl Token {
                 Err(_) => Token::EOF,
             },
             _ => {
-                return if id.contains('.') {
+                if id.contains('.') {
                     Token::to_number(&id, Types::Double)
                 } else {
                     Token::to_number(&id, Types::Int32)
-                };
+                }
             } // Tipo no soportado
         }
     }
 
-    pub fn resolve(self) -> Result<Token, ParseError> {
-        match self {
-            Token::Operation(mut operation) => operation.resolve(),
-            v => Ok(v),
-        }
-    }
-
     pub fn is_valid_value(&self) -> bool {
-        match self {
+        !matches!(
+            self,
             Self::Operator(_)
-            | Self::EOF
-            | Self::StartBrace
-            | Self::StartBracket
-            | Self::StartParenthesis
-            | Self::EndBrace
-            | Self::EndBracket
-            | Self::EndParenthesis
-            | Self::Separator(_)
-            | Self::Void
-            | Self::NewLine
-            | Self::Keyword(_) => false,
-            _ => true,
-        }
+                | Self::EOF
+                | Self::StartBrace
+                | Self::StartBracket
+                | Self::StartParenthesis
+                | Self::EndBrace
+                | Self::EndBracket
+                | Self::EndParenthesis
+                | Self::Separator(_)
+                | Self::Void
+                | Self::NewLine
+                | Self::Keyword(_)
+        )
     }
 
     pub fn is_numeric(&self) -> bool {
-        match self {
-            Self::Int32(_) | Self::Int64(_) | Self::HPInt(_) | Self::Float(_) | Self::Double(_) => {
-                true
-            }
-            _ => false,
-        }
+        matches!(
+            self,
+            Self::Int32(_) | Self::Int64(_) | Self::HPInt(_) | Self::Float(_) | Self::Double(_)
+        )
     }
     pub fn to(&self, new_type: Types) -> Result<Token, ParseError> {
         match (self, &new_type) {
@@ -375,14 +470,18 @@ impl Token {
         }
     }
 
+    /// Convert the token in a boolean, a number is `true` when it isn't zero and a text
+    /// when it isn't empty.
     pub fn as_bool(&self) -> bool {
         match self {
             Token::Identifier(_) | Token::Keyword(_) => true,
-            Token::Int32(int32) => *int32 > 0,
-            Token::Int64(int64) => *int64 > 0,
-            Token::Double(double) => *double > 0.0,
-            Token::String(s) => s.is_empty(),
-            Token::Str(s) => s.is_empty(),
+            Token::Int32(int32) => **int32 != 0,
+            Token::Int64(int64) => **int64 != 0,
+            Token::HPInt(hpint) => **hpint != 0,
+            Token::Float(float) => **float != 0.0,
+            Token::Double(double) => **double != 0.0,
+            Token::String(s) => !s.is_empty(),
+            Token::Str(s) => !s.is_empty(),
             Token::Boolean(b) => *b,
             _ => false,
         }
@@ -392,9 +491,6 @@ impl Token {
         match self {
             Token::Identifier(v) => v,
             Token::Keyword(keyword) => Box::leak(keyword.to_string().into_boxed_str()),
-            Token::Operation(operation) => {
-                Box::leak((operation.operator).to_string().into_boxed_str())
-            }
             Token::Operator(operator) => Box::leak(operator.to_string().into_boxed_str()),
             Token::Type(types) => Box::leak((*types).to_string().into_boxed_str()),
             Token::Int32(int32) => Box::leak(int32.to_string().into_boxed_str()),
@@ -418,10 +514,54 @@ impl Token {
             Token::StartBrace => "{",
             Token::EndBrace => "}",
             Token::Separator(c) => Box::leak(c.to_string().into_boxed_str()),
+            Token::Arrow => "->",
+            Token::FatArrow => "=>",
+            Token::Ellipsis => "...",
+            Token::Question => "?",
+            Token::QuestionDot => "?.",
             Token::NewLine => "\\n",
+            Token::Comment(comment) => comment,
             Token::EOF => "EOF",
             Token::Void => "Void",
+            Token::None => "none",
             Token::List(_) => "List",
+            Token::Map(_) => "Map",
+            Token::Struct { name, .. } => name,
+            Token::Variant { name, .. } => name,
+            Token::Function(func) => func.name(),
+        }
+    }
+
+    /// Check if two keys of a map are the same, unlike `==` the number `1` and the text `"1"`
+    /// are different keys.
+    pub fn is_same_key(&self, other: &Token) -> bool {
+        let (type1, type2) = (Types::from(self), Types::from(other));
+        let comparable = type1 == type2
+            || (type1.is_integer() && type2.is_integer())
+            || (matches!(type1, Types::String | Types::Str)
+                && matches!(type2, Types::String | Types::Str));
+        comparable && self == other
+    }
+
+    /// Truthiness of the token when it's used as a condition.
+    ///
+    /// Only a `Boolean` or a number can be a condition, see [`Token::as_bool`].
+    pub fn as_condition(&self) -> Result<bool, ParseError> {
+        match self {
+            Token::Boolean(_)
+            | Token::Int32(_)
+            | Token::Int64(_)
+            | Token::HPInt(_)
+            | Token::Float(_)
+            | Token::Double(_) => Ok(self.as_bool()),
+            Token::None => Err(ParseError::TypeError(
+                "A condition can't be a value that can be 'none', give it a default value with '??'."
+                    .into(),
+            )),
+            token => Err(ParseError::TypeError(format!(
+                "A condition must be a <Boolean> or a number but it's <{}>.",
+                Types::from(token)
+            ))),
         }
     }
 
@@ -488,3 +628,23 @@ impl From<String> for Token {
         Token::Identifier(Arc::from(&value[..]))
     }
 }
+
+/// A [`Token`] together with the part of the source code where it was found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+impl SpannedToken {
+    pub fn new(token: Token, span: Span) -> Self {
+        Self { token, span }
+    }
+}
+
+/// Compare only the token, so the position doesn't matter
+impl PartialEq<Token> for SpannedToken {
+    fn eq(&self, other: &Token) -> bool {
+        self.token == *other
+    }
+}
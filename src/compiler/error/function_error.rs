@@ -1,5 +1,12 @@
 use thiserror::Error;
 
+use crate::compiler::span::Span;
+
+use super::{
+    parse_error::ParseError,
+    spanned::{Located, Spanned},
+};
+
 #[derive(Debug, Error)]
 #[allow(dead_code)]
 pub enum FunctionError {
@@ -11,10 +18,60 @@ pub enum FunctionError {
     InvalidNumberOfArgs(String),
 
     #[error("ExecutionError: {0}")]
-    ExecutionError(String),
+    ExecutionError(Box<ParseError>),
 
     #[error("DifferentReturnType: {0}")]
     DifferentReturnType(String),
+
+    /// An argument without a passed value nor a default value
+    #[error("MissingArgument: The argument '{argument}' of '{function}' is missing.")]
+    MissingArgument { function: String, argument: String },
+
+    /// A named argument that the function doesn't declare
+    #[error("UnknownArgument: The function '{function}' doesn't have an argument '{argument}'.")]
+    UnknownArgument { function: String, argument: String },
+
+    /// An argument passed by position and by name, or twice by name
+    #[error("RepeatedArgument: The argument '{argument}' of '{function}' is given twice.")]
+    RepeatedArgument { function: String, argument: String },
+
+    #[error(
+        "ArgumentType: The argument '{argument}' of '{function}' must be <{expected}> but it's <{found}>."
+    )]
+    ArgumentType {
+        function: String,
+        argument: String,
+        expected: String,
+        found: String,
+    },
+
+    #[error(
+        "StackOverflow: The call to '{function}' exceeds the maximum depth of {max_depth} calls."
+    )]
+    StackOverflow { function: String, max_depth: usize },
+
+    #[error("{0}")]
+    At(Spanned<FunctionError>),
+}
+
+impl Located for FunctionError {
+    fn spanned(spanned: Spanned<Self>) -> Self {
+        FunctionError::At(spanned)
+    }
+
+    fn as_spanned(&self) -> Option<&Spanned<Self>> {
+        match self {
+            FunctionError::At(spanned) => Some(spanned),
+            _ => None,
+        }
+    }
+
+    fn own_span(&self) -> Option<Span> {
+        match self {
+            FunctionError::ExecutionError(error) => error.span(),
+            _ => None,
+        }
+    }
 }
 
 // impl fmt::Display for FunctionError {
@@ -1,6 +1,8 @@
 // use std::{error::Error, fmt};
 use thiserror::Error;
 
+use super::spanned::{Located, Spanned};
+
 #[derive(Debug, Error)]
 pub enum LexicError {
     #[error("InvalidIdentifier: {0}")]
@@ -14,6 +16,31 @@ pub enum LexicError {
 
     #[error("UnfinalizedStringError: You didn't finish the string.")]
     UnfinalizedString,
+
+    #[error("UnfinalizedCommentError: You didn't close the comment with ']#'.")]
+    UnfinalizedComment,
+
+    #[error("InvalidEscape: '\\{0}' isn't a valid escape sequence.")]
+    InvalidEscape(char),
+
+    #[error("UnexpectedCharacter: {0:?} isn't valid here.")]
+    UnexpectedCharacter(char),
+
+    #[error("{0}")]
+    At(Spanned<LexicError>),
+}
+
+impl Located for LexicError {
+    fn spanned(spanned: Spanned<Self>) -> Self {
+        LexicError::At(spanned)
+    }
+
+    fn as_spanned(&self) -> Option<&Spanned<Self>> {
+        match self {
+            LexicError::At(spanned) => Some(spanned),
+            _ => None,
+        }
+    }
 }
 
 // impl fmt::Display for LexicError {
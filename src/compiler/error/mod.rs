@@ -1,3 +1,4 @@
+pub mod function_error;
 pub mod lexic_error;
 pub mod parse_error;
-pub mod function_error;
\ No newline at end of file
+pub mod spanned;
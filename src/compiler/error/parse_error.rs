@@ -1,6 +1,11 @@
 use thiserror::Error;
 
-use crate::compiler::elements::token::Token;
+use crate::compiler::{elements::token::Token, span::Span};
+
+use super::{
+    function_error::FunctionError,
+    spanned::{Located, Spanned},
+};
 
 #[derive(Debug, Error)]
 #[allow(dead_code)]
@@ -14,8 +19,8 @@ pub enum ParseError {
     #[error("DivisionByZero: You can't Divide whatever by Zero.")]
     DivisionByZero,
 
-    #[error("Unexpected token at position {position}: {token:?}")]
-    UnexpectedToken { position: usize, token: Token },
+    #[error("Unexpected token: {token:?}")]
+    UnexpectedToken { span: Span, token: Token },
 
     #[error("You can't compare a '{type1}' with a '{type2}'")]
     NotOrd { type1: String, type2: String },
@@ -38,21 +43,86 @@ pub enum ParseError {
     #[error("DefinedVariable: {0}")]
     DefinedVariable(String),
 
+    #[error("ConstReassignment: '{name}' is a constant, its value can't change.")]
+    ConstReassignment {
+        name: String,
+        /// Place of the declaration of the constant
+        declaration: Span,
+        /// Place of the assignment
+        span: Span,
+    },
+
     #[error("DefinedFunction: {0}")]
     DefinedFunction(String),
 
     #[error("FunctionExecution: {0}")]
     FunctionExecution(String),
 
+    #[error("{0}")]
+    Function(#[from] FunctionError),
+
     #[error("InvalidTypeConvertion: {0}")]
     InvalidTypeConvertion(String),
 
+    #[error("InvalidRange: {0}")]
+    InvalidRange(String),
+
+    #[error("IndexOutOfBounds: The index {index} is out of a list of {len} elements.")]
+    IndexOutOfBounds { index: i128, len: usize },
+
+    #[error("DefinedType: {0}")]
+    DefinedType(String),
+
+    #[error("UndefinedField: The struct '{structure}' doesn't have a field '{field}'.")]
+    UndefinedField { structure: String, field: String },
+
+    #[error("NonExhaustiveMatch: The match doesn't cover {0}.")]
+    NonExhaustiveMatch(String),
+
+    #[error("KeyNotFound: The key '{0}' isn't in the map.")]
+    KeyNotFound(String),
+
+    #[error("InvalidShift: {0}")]
+    InvalidShift(String),
+
     #[error("InvalidOperation: You can't '{operation}' a {type1} with a '{type2}'.")]
     InvalidOperation {
         operation: String,
         type1: String,
         type2: String,
     },
+
+    #[error("InvalidOperation: You can't apply '{operation}' to a {type1}.")]
+    InvalidUnaryOperation { operation: String, type1: String },
+
+    /// Code that never runs, found by `atlas check`
+    #[error("UnreachableCode: {0}")]
+    UnreachableCode(String),
+
+    #[error("{0}")]
+    At(Spanned<ParseError>),
+}
+
+impl Located for ParseError {
+    fn spanned(spanned: Spanned<Self>) -> Self {
+        ParseError::At(spanned)
+    }
+
+    fn as_spanned(&self) -> Option<&Spanned<Self>> {
+        match self {
+            ParseError::At(spanned) => Some(spanned),
+            _ => None,
+        }
+    }
+
+    fn own_span(&self) -> Option<Span> {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::ConstReassignment { span, .. } => Some(*span),
+            ParseError::Function(error) => error.span(),
+            _ => None,
+        }
+    }
 }
 
 // impl fmt::Display for ParseError {
@@ -0,0 +1,53 @@
+use thiserror::Error;
+
+use crate::compiler::span::Span;
+
+/// An error with the place of the code where it happened
+#[derive(Debug, Error)]
+#[error("{error}")]
+pub struct Spanned<E> {
+    pub span: Span,
+    pub error: Box<E>,
+}
+
+/// The errors that can carry the place of the code where they happened, in a [`Spanned`]
+pub trait Located: Sized {
+    /// Wrap the error in its variant that has a span
+    fn spanned(spanned: Spanned<Self>) -> Self;
+
+    /// The wrapper with the span, if the error is in it
+    fn as_spanned(&self) -> Option<&Spanned<Self>>;
+
+    /// The span of the errors that keep it in their own fields or in an inner error
+    fn own_span(&self) -> Option<Span> {
+        None
+    }
+
+    /// Attach the place where the error happened.
+    ///
+    /// If the error already has a span it's kept, because the innermost one is the most precise.
+    fn at(self, span: Span) -> Self {
+        if self.span().is_some() {
+            return self;
+        }
+        Self::spanned(Spanned {
+            span,
+            error: Box::new(self),
+        })
+    }
+
+    fn span(&self) -> Option<Span> {
+        match self.as_spanned() {
+            Some(spanned) => Some(spanned.span),
+            None => self.own_span(),
+        }
+    }
+
+    /// The error without the span
+    fn kind(&self) -> &Self {
+        match self.as_spanned() {
+            Some(spanned) => spanned.error.kind(),
+            None => self,
+        }
+    }
+}
@@ -0,0 +1,1406 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use crate::{
+    std::{collections::MUTATING_LIST, debug::DEBUG_LIST},
+    types::basic::number::hpint::HPInt,
+};
+
+use super::{
+    ast::{
+        Arg, EnumDecl, Expr, ExprKind, FunctionDecl, Item, MatchArm, Pattern, Stmt, StmtKind,
+        StructDecl,
+    },
+    elements::{operator::Operator, token::Token},
+    error::{function_error::FunctionError, parse_error::ParseError, spanned::Located},
+    function::{Argument, Function},
+    function_table::{Func, FunctionTable},
+    iteration::{Iteration, Range},
+    span::Span,
+    types::{Bindings, Types},
+    variable::Variable,
+    variable_table::VariableTable,
+};
+
+/// Rust stack that must be left before running a statement or an expression, enough for the
+/// frames until the next check. A call of the program with a nested body uses about 160 KiB
+/// in a debug build, split between several statements and expressions. The parser and the
+/// checker use it too.
+pub(crate) const STACK_RED_ZONE: usize = 256 * 1024;
+
+/// Size of each new piece of stack when the current one runs out
+pub(crate) const STACK_SEGMENT: usize = 4 * 1024 * 1024;
+
+/// The values of the fields of a struct, in the order of the declaration
+type Fields = Vec<(Arc<str>, Token)>;
+
+/// How the execution continues after a statement
+#[derive(Debug, Clone, PartialEq)]
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    /// The value of a `return` and the place of the statement
+    Return(Token, Span),
+}
+
+/// This struct is in charge of execute the tree produced by the [`Parser`](super::parser::Parser)
+#[derive(Debug, Clone)]
+pub struct Evaluator {
+    /// The variables of the innermost scope, linked to the scopes around it
+    variables: Rc<RefCell<VariableTable>>,
+    functions: Rc<RefCell<FunctionTable>>,
+}
+
+impl Evaluator {
+    // MARK: Creation
+    pub fn new(
+        variables: Option<Rc<RefCell<VariableTable>>>,
+        functions: Option<Rc<RefCell<FunctionTable>>>,
+    ) -> Self {
+        Evaluator {
+            variables: variables.unwrap_or_default(),
+            functions: functions.unwrap_or_default(),
+        }
+    }
+
+    // MARK: Get
+    pub fn get_variables(&self) -> Vec<(String, Variable)> {
+        self.variables
+            .borrow()
+            .variables
+            .iter()
+            .map(|(key, var)| (key.clone(), var.clone()))
+            .collect()
+    }
+
+    pub fn get_variable_table(&self) -> VariableTable {
+        (*self.variables.borrow()).clone()
+    }
+
+    pub fn get_function_table(&self) -> FunctionTable {
+        (*self.functions.borrow()).clone()
+    }
+
+    pub fn get_functions(&self) -> Vec<(String, Function)> {
+        self.functions
+            .borrow()
+            .functions
+            .iter()
+            .map(|(key, function)| (key.to_owned(), function.to_owned()))
+            .collect()
+    }
+
+    // MARK: Evaluate
+    /// Execute every item of the program and return the value of each expression
+    /// used as a statement that doesn't return `Void`.
+    pub fn evaluate(&mut self, program: &[Item]) -> Result<Vec<Token>, ParseError> {
+        let mut results = Vec::new();
+
+        // The types can be used before the place where they are written, like in the checker
+        for item in program {
+            match item {
+                Item::Struct(structure) => self
+                    .struct_declaration(structure)
+                    .map_err(|error| error.at(structure.span))?,
+                Item::Enum(enumeration) => self
+                    .enum_declaration(enumeration)
+                    .map_err(|error| error.at(enumeration.span))?,
+                Item::Function(_) | Item::Stmt(_) => {}
+            }
+        }
+        for item in program {
+            match item {
+                Item::Function(function) => self
+                    .function_declaration(function)
+                    .map_err(|error| error.at(function.span))?,
+                Item::Stmt(stmt) => {
+                    self.execute_stmt(stmt, &mut results)?;
+                }
+                Item::Struct(_) | Item::Enum(_) => {}
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Execute a list of statements in the current scope
+    pub fn execute(&mut self, stmts: &[Stmt]) -> Result<Vec<Token>, ParseError> {
+        let mut results = Vec::new();
+        for stmt in stmts {
+            self.execute_stmt(stmt, &mut results)?;
+        }
+        Ok(results)
+    }
+
+    /// Execute the body of a function, returning the value of its `return` and the place of
+    /// the statement. A body without `return` returns `Void`.
+    pub fn execute_function(&mut self, body: &[Stmt]) -> Result<(Token, Option<Span>), ParseError> {
+        let mut results = Vec::new();
+        for stmt in body {
+            if let Flow::Return(value, span) = self.execute_stmt(stmt, &mut results)? {
+                return Ok((value, Some(span)));
+            }
+        }
+        Ok((Token::Void, None))
+    }
+
+    /// Execute a statement. The recursion of the program runs here and in
+    /// [`Evaluator::evaluate_expr`], so both grow the Rust stack when it's running out.
+    fn execute_stmt(&mut self, stmt: &Stmt, results: &mut Vec<Token>) -> Result<Flow, ParseError> {
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+            self.execute_stmt_kind(stmt, results)
+                .map_err(|error| error.at(stmt.span))
+        })
+    }
+
+    fn execute_stmt_kind(
+        &mut self,
+        stmt: &Stmt,
+        results: &mut Vec<Token>,
+    ) -> Result<Flow, ParseError> {
+        match &stmt.kind {
+            StmtKind::VarDecl {
+                name,
+                var_type,
+                value,
+                mutable,
+            } => {
+                self.check_type(var_type, None)
+                    .map_err(|error| error.at(stmt.span))?;
+                let value = self.evaluate_expr(value)?;
+                self.handle_variable_declaration(
+                    name,
+                    value,
+                    var_type.clone(),
+                    *mutable,
+                    stmt.span,
+                )?;
+                Ok(Flow::Normal)
+            }
+
+            StmtKind::Assign {
+                name,
+                operator,
+                value,
+            } => {
+                let value = self.evaluate_expr(value)?;
+                self.handle_variable_reassignment(name, value, operator, stmt.span)?;
+                Ok(Flow::Normal)
+            }
+
+            StmtKind::AssignTo {
+                target,
+                operator,
+                value,
+            } => {
+                let mut value = self.evaluate_expr(value)?;
+                if *operator != Operator::Assign {
+                    value = operator.execute(self.evaluate_expr(target)?, value)?;
+                }
+                self.assign_to(target, value, stmt.span)?;
+                Ok(Flow::Normal)
+            }
+
+            // As a statement the arms can use `break`, `continue` and `return`
+            StmtKind::Expr(Expr {
+                kind: ExprKind::Match { value, arms },
+                span,
+            }) => {
+                let (_, flow) = self
+                    .execute_match(value, arms, results)
+                    .map_err(|error| error.at(*span))?;
+                Ok(flow)
+            }
+
+            StmtKind::Expr(expr) => {
+                let value = self.evaluate_expr(expr)?;
+                if !matches!(value, Token::Void) {
+                    results.push(value);
+                }
+                Ok(Flow::Normal)
+            }
+
+            StmtKind::Block(body) => self.execute_block(body, results),
+
+            StmtKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if self.condition(condition)? {
+                    self.execute_block(then_branch, results)
+                } else if let Some(else_branch) = else_branch {
+                    self.execute_block(else_branch, results)
+                } else {
+                    Ok(Flow::Normal)
+                }
+            }
+
+            StmtKind::While { condition, body } => {
+                while self.condition(condition)? {
+                    // Each iteration has its own scope, so the variables of the body are new each time
+                    match self.execute_block(body, results)? {
+                        Flow::Break => break,
+                        flow @ Flow::Return(..) => return Ok(flow),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                }
+                Ok(Flow::Normal)
+            }
+
+            StmtKind::For {
+                variable,
+                iterable,
+                body,
+            } => {
+                for item in self.iteration(iterable)? {
+                    // The loop variable lives in its own scope around the body
+                    let flow = self.in_scope(|evaluator| {
+                        evaluator.handle_variable_declaration(
+                            variable,
+                            item,
+                            Types::Inferred,
+                            true,
+                            stmt.span,
+                        )?;
+                        evaluator.execute_block(body, results)
+                    });
+
+                    match flow? {
+                        Flow::Break => break,
+                        flow @ Flow::Return(..) => return Ok(flow),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                }
+                Ok(Flow::Normal)
+            }
+
+            StmtKind::Return(value) => {
+                let value = match value {
+                    Some(value) => self.evaluate_expr(value)?,
+                    None => Token::Void,
+                };
+                Ok(Flow::Return(value, stmt.span))
+            }
+
+            StmtKind::Break => Ok(Flow::Break),
+            StmtKind::Continue => Ok(Flow::Continue),
+        }
+    }
+
+    /// Execute the statements in a new scope, its variables disappear at the end
+    fn execute_block(
+        &mut self,
+        body: &[Stmt],
+        results: &mut Vec<Token>,
+    ) -> Result<Flow, ParseError> {
+        self.in_scope(|evaluator| {
+            for stmt in body {
+                let flow = evaluator.execute_stmt(stmt, results)?;
+                if flow != Flow::Normal {
+                    return Ok(flow);
+                }
+            }
+            Ok(Flow::Normal)
+        })
+    }
+
+    /// Run `f` in a new scope inside the current one, the outer scope is restored even if `f`
+    /// fails
+    fn in_scope<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = self.variables.clone();
+        self.variables = Rc::new(RefCell::new(VariableTable::with_parent(outer.clone())));
+        let result = f(self);
+        self.variables = outer;
+        result
+    }
+
+    /// Prepare the values of a `for`, a range is iterated without creating a list
+    fn iteration(&mut self, iterable: &Expr) -> Result<Iteration, ParseError> {
+        match &iterable.kind {
+            ExprKind::Range {
+                start,
+                end,
+                step,
+                inclusive,
+            } => Ok(Iteration::Range(
+                self.range(start, end, step.as_deref(), *inclusive)
+                    .map_err(|error| error.at(iterable.span))?,
+            )),
+            _ => Iteration::from_token(self.evaluate_expr(iterable)?)
+                .map_err(|error| error.at(iterable.span)),
+        }
+    }
+
+    fn range(
+        &mut self,
+        start: &Expr,
+        end: &Expr,
+        step: Option<&Expr>,
+        inclusive: bool,
+    ) -> Result<Range, ParseError> {
+        let start = self.evaluate_expr(start)?;
+        let end = self.evaluate_expr(end)?;
+        let step = match step {
+            Some(step) => Some(self.evaluate_expr(step)?),
+            None => None,
+        };
+        Range::new(start, end, step, inclusive)
+    }
+
+    fn condition(&mut self, condition: &Expr) -> Result<bool, ParseError> {
+        self.evaluate_expr(condition)?
+            .as_condition()
+            .map_err(|error| error.at(condition.span))
+    }
+
+    /// Evaluate an expression, the errors point to the innermost expression that failed
+    pub fn evaluate_expr(&mut self, expr: &Expr) -> Result<Token, ParseError> {
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+            self.evaluate_expr_kind(expr)
+                .map_err(|error| error.at(expr.span))
+        })
+    }
+
+    fn evaluate_expr_kind(&mut self, expr: &Expr) -> Result<Token, ParseError> {
+        match &expr.kind {
+            ExprKind::Literal(value) => Ok(value.clone()),
+
+            ExprKind::Variable(name) => {
+                if let Ok(variable) = self.variables.borrow().get(name) {
+                    return Ok(*variable.value.clone());
+                }
+                // A variant without payload, like `Empty`
+                if self.is_variant(name) {
+                    return self.build_variant(name, &[]);
+                }
+                // A function used as a value, like `var p = print`
+                if let Ok(function) = self.functions.borrow().get(name) {
+                    return Ok(Token::Function(Rc::new(function)));
+                }
+                Err(ParseError::UndefinedVariable(format!(
+                    "The variable '{name}' doesn't exist."
+                )))
+            }
+
+            ExprKind::Unary { operator, operand } => {
+                let operand = self.evaluate_expr(operand)?;
+                operator.execute_unary(operand)
+            }
+
+            // The right side only runs when the left one doesn't decide the result
+            ExprKind::Binary {
+                operator: operator @ (Operator::And | Operator::Or),
+                left,
+                right,
+            } => {
+                let left = self.evaluate_expr(left)?.as_condition()?;
+                if left == (*operator == Operator::Or) {
+                    return Ok(Token::Boolean(left));
+                }
+                Ok(Token::Boolean(self.evaluate_expr(right)?.as_condition()?))
+            }
+
+            // The default value only runs when the value is `none`
+            ExprKind::Binary {
+                operator: Operator::Coalesce,
+                left,
+                right,
+            } => match self.evaluate_expr(left)? {
+                Token::None => self.evaluate_expr(right),
+                value => Ok(value),
+            },
+
+            ExprKind::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let left = self.evaluate_expr(left)?;
+                let right = self.evaluate_expr(right)?;
+                operator.execute(left, right)
+            }
+
+            ExprKind::Call { callee, args } if self.is_variant(callee) => {
+                self.build_variant(callee, args)
+            }
+
+            ExprKind::Call { callee, args } => {
+                let mutating = self.is_mutating(callee);
+                let arguments = self.arguments(args)?;
+                let result = self.process_function_call(callee, arguments)?;
+
+                // The new collection is stored back in the variable or the element passed, and
+                // it's the value of the call too
+                if let Some(
+                    target @ Expr {
+                        kind: ExprKind::Variable(_) | ExprKind::Index { .. },
+                        ..
+                    },
+                ) = args.first().map(|arg| &arg.value)
+                {
+                    if mutating {
+                        self.assign_to(target, result.clone(), expr.span)?;
+                    }
+                }
+                Ok(result)
+            }
+
+            ExprKind::Invoke { callee, args } => {
+                let function = self.evaluate_expr(callee)?;
+                let arguments = self.arguments(args)?;
+                self.call_value(function, arguments)
+            }
+
+            ExprKind::Lambda(function) => Ok(Token::Function(Rc::new(Func::User(
+                self.function_value(function)?,
+            )))),
+
+            // Out of a `for` a range is a list with all its values
+            ExprKind::Range {
+                start,
+                end,
+                step,
+                inclusive,
+            } => Ok(Token::List(
+                self.range(start, end, step.as_deref(), *inclusive)?
+                    .collect(),
+            )),
+
+            ExprKind::List(items) => {
+                let mut values = Vec::with_capacity(items.len());
+                for item in items {
+                    values.push(self.evaluate_expr(item)?);
+                }
+                check_items(&values, "elements of a list")?;
+                Ok(Token::List(values))
+            }
+
+            ExprKind::Map(entries) => {
+                let mut map = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    let key_value = self.evaluate_expr(key)?;
+                    check_key(&key_value).map_err(|error| error.at(key.span))?;
+                    insert_entry(&mut map, key_value, self.evaluate_expr(value)?);
+                }
+                check_items(map.iter().map(|(key, _)| key), "keys of a map")?;
+                check_items(map.iter().map(|(_, value)| value), "values of a map")?;
+                Ok(Token::Map(map))
+            }
+
+            ExprKind::Struct { name, fields } => self.build_struct(name, fields),
+
+            ExprKind::Field { target, name } => {
+                let (structure, mut fields) = struct_fields(self.evaluate_expr(target)?)?;
+                let position = field_position(&structure, &fields, name)?;
+                Ok(fields.swap_remove(position).1)
+            }
+
+            ExprKind::OptionalField { target, name } => match self.evaluate_expr(target)? {
+                Token::None => Ok(Token::None),
+                value => {
+                    let (structure, mut fields) = struct_fields(value)?;
+                    let position = field_position(&structure, &fields, name)?;
+                    Ok(fields.swap_remove(position).1)
+                }
+            },
+
+            ExprKind::Match { value, arms } => {
+                let mut results = vec![];
+                match self.execute_match(value, arms, &mut results)? {
+                    (value, Flow::Normal) => Ok(value),
+                    _ => Err(ParseError::SyntaxError(
+                        "'break', 'continue' and 'return' can't be used in a match inside an expression".into(),
+                    )),
+                }
+            }
+
+            ExprKind::Index { target, index } => {
+                let items = match self.evaluate_expr(target)? {
+                    Token::Map(entries) => {
+                        let key = self.evaluate_expr(index)?;
+                        return entries
+                            .into_iter()
+                            .find(|(old, _)| old.is_same_key(&key))
+                            .map(|(_, value)| value)
+                            .ok_or_else(|| ParseError::KeyNotFound(key.to_string()));
+                    }
+                    target => list_items(target)?,
+                };
+                match &index.kind {
+                    ExprKind::Range {
+                        start,
+                        end,
+                        step,
+                        inclusive,
+                    } => self.slice(items, start, end, step.as_deref(), *inclusive),
+                    _ => {
+                        let index = index_value(&self.evaluate_expr(index)?)?;
+                        let position = list_position(index, items.len())?;
+                        Ok(items[position].clone())
+                    }
+                }
+            }
+        }
+    }
+
+    // MARK: Structs
+    fn struct_declaration(&mut self, structure: &StructDecl) -> Result<(), ParseError> {
+        // A field can have the type of the struct itself, inside a collection
+        for field in &structure.fields {
+            self.check_type(&field.var_type, Some(&structure.name))
+                .map_err(|error| error.at(field.span))?;
+        }
+        self.functions.borrow_mut().insert_struct(structure.clone())
+    }
+
+    /// Check that every struct or enum used in `types` is declared, `own` is the struct or the
+    /// enum being declared
+    fn check_type(&self, types: &Types, own: Option<&str>) -> Result<(), ParseError> {
+        match types {
+            Types::Custom(name, _)
+                if own != Some(name.as_ref()) && !self.functions.borrow().has_type(name) =>
+            {
+                Err(ParseError::UndefinedType(format!(
+                    "The type '{name}' doesn't exist."
+                )))
+            }
+            Types::Custom(_, args) => args.iter().try_for_each(|arg| self.check_type(arg, own)),
+            Types::List(item) | Types::Option(item) => self.check_type(item, own),
+            Types::Map(key, value) => {
+                self.check_type(key, own)?;
+                self.check_type(value, own)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `Name { field: value, ... }`, every field must be given once with its declared type. The
+    /// fields whose type is a type parameter must agree on its type.
+    fn build_struct(
+        &mut self,
+        name: &Arc<str>,
+        fields: &[(Arc<str>, Expr)],
+    ) -> Result<Token, ParseError> {
+        let structure = self.functions.borrow().get_struct(name)?.clone();
+
+        let mut values: Fields = Vec::with_capacity(fields.len());
+        let mut bindings = Bindings::new();
+        for (field, expr) in fields {
+            let Some(field_type) = structure.field_type(field) else {
+                return Err(ParseError::UndefinedField {
+                    structure: name.to_string(),
+                    field: field.to_string(),
+                }
+                .at(expr.span));
+            };
+            if values.iter().any(|(other, _)| other == field) {
+                return Err(ParseError::SyntaxError(format!(
+                    "The field '{field}' is given twice."
+                ))
+                .at(expr.span));
+            }
+            let value = self.evaluate_expr(expr)?;
+            let value_type = self.functions.borrow().type_of(&value);
+            let value = fit_value(
+                value,
+                &value_type,
+                field_type,
+                &mut bindings,
+                &format!("field '{field}' of '{name}'"),
+            )
+            .map_err(|error| error.at(expr.span))?;
+            values.push((field.clone(), value));
+        }
+
+        // The values are stored in the order of the declaration
+        let mut ordered = Vec::with_capacity(structure.fields.len());
+        for field in &structure.fields {
+            let Some(position) = values.iter().position(|(name, _)| *name == field.name) else {
+                return Err(ParseError::TypeError(format!(
+                    "The field '{}' of '{name}' is missing.",
+                    field.name
+                )));
+            };
+            ordered.push(values.swap_remove(position));
+        }
+
+        Ok(Token::Struct {
+            name: name.clone(),
+            fields: ordered,
+        })
+    }
+
+    // MARK: Enums
+    fn enum_declaration(&mut self, enumeration: &EnumDecl) -> Result<(), ParseError> {
+        for variant in &enumeration.variants {
+            for field in &variant.fields {
+                self.check_type(field, Some(&enumeration.name))
+                    .map_err(|error| error.at(variant.span))?;
+            }
+        }
+        self.functions.borrow_mut().insert_enum(enumeration.clone())
+    }
+
+    fn is_variant(&self, name: &str) -> bool {
+        self.functions.borrow().get_variant(name).is_some()
+    }
+
+    /// `Circle(2.0)` or `Empty`, the values must have the types of the payload
+    fn build_variant(&mut self, name: &Arc<str>, args: &[Arg]) -> Result<Token, ParseError> {
+        let (enumeration, variant) = {
+            let table = self.functions.borrow();
+            let (enumeration, variant) = table
+                .get_variant(name)
+                .ok_or_else(|| ParseError::UndefinedType(format!("'{name}' isn't a variant.")))?;
+            (enumeration.name.clone(), variant.clone())
+        };
+        if let Some(arg) = args.iter().find(|arg| arg.name.is_some()) {
+            return Err(ParseError::SyntaxError(format!(
+                "The values of the variant '{name}' can't be named."
+            ))
+            .at(arg.value.span));
+        }
+        if args.len() != variant.fields.len() {
+            return Err(ParseError::TypeError(format!(
+                "The variant '{name}' has {} values but it receives {}.",
+                variant.fields.len(),
+                args.len()
+            )));
+        }
+
+        let mut values = Vec::with_capacity(args.len());
+        let mut bindings = Bindings::new();
+        for (position, (arg, field)) in args.iter().zip(&variant.fields).enumerate() {
+            let what = format!("value {} of '{name}'", position + 1);
+            let value = self.evaluate_expr(&arg.value)?;
+            let value_type = self.functions.borrow().type_of(&value);
+            let value = fit_value(value, &value_type, field, &mut bindings, &what)
+                .map_err(|error| error.at(arg.value.span))?;
+            values.push(value);
+        }
+
+        Ok(Token::Variant {
+            enumeration,
+            name: name.clone(),
+            values,
+        })
+    }
+
+    /// Execute the body of the first arm whose pattern fits the value. The names of the pattern
+    /// live in their own scope around the body, and the value of the arm is the value of its
+    /// last statement if it's an expression.
+    fn execute_match(
+        &mut self,
+        value: &Expr,
+        arms: &[MatchArm],
+        results: &mut Vec<Token>,
+    ) -> Result<(Token, Flow), ParseError> {
+        let value = self.evaluate_expr(value)?;
+        let Some((arm, bindings)) = arms.iter().find_map(|arm| {
+            let mut bindings = vec![];
+            self.pattern_fits(&arm.pattern, &value, &mut bindings)
+                .then_some((arm, bindings))
+        }) else {
+            return Err(ParseError::NonExhaustiveMatch(format!(
+                "the value '{value}'"
+            )));
+        };
+
+        self.in_scope(|evaluator| {
+            for (name, value) in bindings {
+                evaluator.handle_variable_declaration(
+                    &name,
+                    value,
+                    Types::Inferred,
+                    true,
+                    arm.span,
+                )?;
+            }
+
+            let mut result = Token::Void;
+            for (position, stmt) in arm.body.iter().enumerate() {
+                let before = results.len();
+                let flow = evaluator.execute_stmt(stmt, results)?;
+                if flow != Flow::Normal {
+                    return Ok((result, flow));
+                }
+                if position + 1 == arm.body.len()
+                    && matches!(stmt.kind, StmtKind::Expr(_))
+                    && results.len() > before
+                {
+                    result = results[results.len() - 1].clone();
+                }
+            }
+            Ok((result, Flow::Normal))
+        })
+    }
+
+    /// Check if the value fits in the pattern, saving in `bindings` the names it gives values to
+    fn pattern_fits(
+        &self,
+        pattern: &Pattern,
+        value: &Token,
+        bindings: &mut Vec<(Arc<str>, Token)>,
+    ) -> bool {
+        match pattern {
+            Pattern::Wildcard => true,
+            Pattern::Literal(literal) => value == literal,
+            Pattern::Identifier(name) if self.is_variant(name) => {
+                matches!(value, Token::Variant { name: variant, .. } if variant == name)
+            }
+            Pattern::Identifier(name) => {
+                bindings.push((name.clone(), value.clone()));
+                true
+            }
+            Pattern::Variant { name, args } => match value {
+                Token::Variant {
+                    name: variant,
+                    values,
+                    ..
+                } if variant == name => args
+                    .iter()
+                    .zip(values)
+                    .all(|(arg, value)| self.pattern_fits(arg, value, bindings)),
+                _ => false,
+            },
+        }
+    }
+
+    // MARK: Lists
+    /// `list[start..end step n]`, the negative limits count from the end of the list
+    fn slice(
+        &mut self,
+        items: Vec<Token>,
+        start: &Expr,
+        end: &Expr,
+        step: Option<&Expr>,
+        inclusive: bool,
+    ) -> Result<Token, ParseError> {
+        let len = items.len();
+        let from_end = |limit: Token| match index_value(&limit) {
+            Ok(index) if index < 0 => Token::HPInt(HPInt::new(index + len as i128)),
+            _ => limit,
+        };
+        let start = from_end(self.evaluate_expr(start)?);
+        let end = from_end(self.evaluate_expr(end)?);
+        let step = match step {
+            Some(step) => Some(self.evaluate_expr(step)?),
+            None => None,
+        };
+
+        let mut slice = vec![];
+        for index in Range::new(start, end, step, inclusive)? {
+            let index = index_value(&index)?;
+            if index < 0 {
+                return Err(ParseError::IndexOutOfBounds { index, len });
+            }
+            slice.push(items[list_position(index, len)?].clone());
+        }
+        Ok(Token::List(slice))
+    }
+
+    /// Store the value in a variable or in an element of a list, the lists are copied with
+    /// the new element and stored again in their container
+    fn assign_to(&mut self, target: &Expr, value: Token, span: Span) -> Result<(), ParseError> {
+        match &target.kind {
+            ExprKind::Variable(name) => {
+                self.handle_variable_reassignment(name, value, &Operator::Assign, span)
+            }
+            ExprKind::Index {
+                target: container,
+                index,
+            } => {
+                let collection = self.evaluate_expr(container)?;
+                let index = self.evaluate_expr(index)?;
+                let collection = set_item(collection, &index, value, &self.place_type(container))
+                    .map_err(|error| error.at(target.span))?;
+                self.assign_to(container, collection, span)
+            }
+            ExprKind::Field {
+                target: container,
+                name,
+            } => {
+                let (structure, mut fields) = struct_fields(self.evaluate_expr(container)?)?;
+                let position = field_position(&structure, &fields, name)?;
+                let table = self.functions.borrow();
+                let declared = table.get_struct(&structure)?;
+                // The current values decide the types of the type parameters
+                let mut bindings = Bindings::new();
+                for (field, value) in &fields {
+                    if let Some(field_type) = declared.field_type(field) {
+                        field_type.unify(&table.type_of(value), &mut bindings);
+                    }
+                }
+                let field_type = declared.field_type(name).unwrap_or(&Types::Inferred);
+                let what = format!("field '{name}' of '{structure}'");
+                let value_type = table.type_of(&value);
+                fields[position].1 =
+                    fit_value(value, &value_type, field_type, &mut bindings, &what)
+                        .map_err(|error| error.at(target.span))?;
+                drop(table);
+                self.assign_to(
+                    container,
+                    Token::Struct {
+                        name: structure,
+                        fields,
+                    },
+                    span,
+                )
+            }
+            _ => Err(ParseError::SyntaxError(
+                "Only a variable, an element of a collection or a field can be assigned.".into(),
+            )
+            .at(target.span)),
+        }
+    }
+
+    /// Declared type of a variable or an element of a collection, `Inferred` if unknown
+    fn place_type(&self, target: &Expr) -> Types {
+        match &target.kind {
+            ExprKind::Variable(name) => self
+                .variables
+                .borrow()
+                .get(name)
+                .map_or(Types::Inferred, |var| var.var_type.clone()),
+            ExprKind::Index { target, .. } => match self.place_type(target) {
+                Types::List(item) => *item,
+                Types::Map(_, value) => *value,
+                _ => Types::Inferred,
+            },
+            ExprKind::Field { target, name } => match self.place_type(target) {
+                Types::Custom(structure, _) => self
+                    .functions
+                    .borrow()
+                    .get_struct(&structure)
+                    .ok()
+                    .and_then(|structure| structure.field_type(name).cloned())
+                    .map_or(Types::Inferred, |field_type| field_type.without_generics()),
+                _ => Types::Inferred,
+            },
+            _ => Types::Inferred,
+        }
+    }
+
+    // MARK: Variables
+    fn handle_variable_reassignment(
+        &mut self,
+        identifier: &str,
+        value: Token,
+        operator: &Operator,
+        span: Span,
+    ) -> Result<(), ParseError> {
+        let mut table = self.variables.borrow_mut();
+        let Ok(var) = table.get(identifier) else {
+            return Err(ParseError::UndefinedVariable(format!(
+                "Variable {} not defined",
+                identifier
+            )));
+        };
+        if !var.mutable {
+            return Err(ParseError::ConstReassignment {
+                name: identifier.to_string(),
+                declaration: var.span,
+                span,
+            });
+        }
+
+        let new_value = if *operator == Operator::Assign {
+            value
+        } else {
+            operator.execute(*var.value.clone(), value)?
+        };
+
+        let mut var = var.clone();
+        Types::inferred(&new_value)?;
+        let new_type = self.functions.borrow().type_of(&new_value);
+        // A collection or an optional keeps its type, the parts that weren't known, like the
+        // elements of `[]`, take the type of the new value
+        if matches!(
+            var.var_type,
+            Types::List(_) | Types::Map(..) | Types::Option(_)
+        ) {
+            if !new_type.can_convert(&var.var_type) {
+                return Err(ParseError::TypeError(format!(
+                    "The type of '{identifier}' must be <{}> but it's <{}>.",
+                    var.var_type,
+                    new_type.name_for(&new_value)
+                )));
+            }
+            var.var_type = var.var_type.refine(&new_type);
+            *var.value = Types::transform(new_value, var.var_type.clone())?.0;
+        } else {
+            var.var_type = new_type;
+            *var.value = new_value;
+        }
+
+        table.update(identifier, &mut var)?;
+        Ok(())
+    }
+
+    fn handle_variable_declaration(
+        &mut self,
+        identifier: &str,
+        value_token: Token,
+        mut var_type: Types,
+        mutable: bool,
+        span: Span,
+    ) -> Result<(), ParseError> {
+        Types::inferred(&value_token)?;
+        // The type of a struct or a variant of a generic type has the types of its values
+        let inferred_type = self.functions.borrow().type_of(&value_token);
+        // In a generic function a type parameter takes the type of the value
+        var_type = var_type.without_generics();
+        let mut table = self.variables.borrow_mut();
+
+        let new_value = if inferred_type == var_type {
+            value_token
+        } else if var_type == Types::Inferred {
+            var_type = inferred_type;
+            value_token
+        } else if var_type.unify(&inferred_type, &mut Bindings::new()) {
+            // The parts of the declared type that aren't given, like in `Option`, take the type
+            // of the value
+            var_type = var_type.refine(&inferred_type);
+            Types::transform(value_token, var_type.clone())?.0
+        } else {
+            return Err(ParseError::TypeError(format!(
+                "The type of '{identifier}' must be <{var_type}> but it's <{}>.",
+                inferred_type.name_for(&value_token)
+            )));
+        };
+
+        let depth = table.depth();
+        let mut variable = Variable::new(identifier.to_string(), var_type, new_value, depth);
+        variable.mutable = mutable;
+        variable.span = span;
+        table.insert(identifier, variable)?;
+
+        Ok(())
+    }
+
+    // MARK: Functions
+    fn function_declaration(&mut self, function: &FunctionDecl) -> Result<(), ParseError> {
+        let value = self.function_value(function)?;
+        let mut table = self.functions.borrow_mut();
+        if table.get(&function.name).is_ok() {
+            return Err(ParseError::DefinedFunction(function.name.to_string()));
+        }
+        table.insert(&function.name, value)
+    }
+
+    /// The function of a declaration or a lambda, it captures the current scope
+    fn function_value(&mut self, function: &FunctionDecl) -> Result<Function, ParseError> {
+        let mut args = vec![];
+        for param in &function.params {
+            self.check_type(&param.var_type, None)
+                .map_err(|error| error.at(param.span))?;
+            let default = match &param.default {
+                Some(expr) => Some(Box::new(self.evaluate_expr(expr)?)),
+                None => None,
+            };
+            let arg = Argument::new(param.name.clone(), param.var_type.clone(), default, None);
+            args.push(if param.variadic { arg.variadic() } else { arg });
+        }
+        self.check_type(&function.return_type, None)
+            .map_err(|error| error.at(function.span))?;
+
+        Ok(Function::new(
+            function.name.clone(),
+            function.return_type.clone(),
+            args,
+            function.body.clone(),
+            0,
+        )
+        .with_generics(function.generics.clone())
+        .with_closure(self.variables.clone()))
+    }
+
+    fn arguments(&mut self, args: &[Arg]) -> Result<Vec<Argument>, ParseError> {
+        let mut arguments = Vec::with_capacity(args.len());
+        for arg in args {
+            let value = self.evaluate_expr(&arg.value)?;
+            arguments.push(match &arg.name {
+                Some(name) => Argument::named(name.clone(), value),
+                None => Argument::from(value),
+            });
+        }
+        Ok(arguments)
+    }
+
+    /// Check if `callee` is a builtin that changes the collection passed as first argument
+    fn is_mutating(&self, callee: &str) -> bool {
+        MUTATING_LIST.contains(&callee)
+            && self.variables.borrow().get(callee).is_err()
+            && matches!(self.functions.borrow().get(callee), Ok(Func::Std(_)))
+    }
+
+    /// Call the function `var`, a variable that holds a function hides the function with the
+    /// same name
+    fn process_function_call(&self, var: &str, args: Vec<Argument>) -> Result<Token, ParseError> {
+        let variable = self.variables.borrow().get(var);
+        if let Ok(variable) = variable {
+            return self.call_value(*variable.value, args);
+        }
+        let func = if let Ok(function) = self.functions.borrow().get(var) {
+            function
+        } else {
+            return Err(ParseError::UndefinedFunction(
+                "This function doesn't exist.".into(),
+            ));
+        };
+        self.call(&func, args)
+    }
+
+    fn call_value(&self, value: Token, args: Vec<Argument>) -> Result<Token, ParseError> {
+        match value {
+            Token::Function(func) => self.call(&func, args),
+            value => Err(ParseError::TypeError(format!(
+                "Only a function can be called but it's <{}>.",
+                Types::from(&value).name_for(&value)
+            ))),
+        }
+    }
+
+    fn call(&self, func: &Func, args: Vec<Argument>) -> Result<Token, ParseError> {
+        match func {
+            Func::Std(std_func) => {
+                // The functions of the standard library don't declare the names of their arguments
+                if let Some(arg) = args.iter().find(|arg| !arg.is_positional()) {
+                    return Err(FunctionError::UnknownArgument {
+                        function: std_func.name.clone(),
+                        argument: arg.name.to_string(),
+                    }
+                    .into());
+                }
+                let result = if DEBUG_LIST.contains(&std_func.name.as_str()) {
+                    // The outer scopes first, and in each scope the order of declaration
+                    let mut visible = self.variables.borrow().visible();
+                    visible.sort_by_key(|var| (var.scope, var.id));
+                    let vars = visible.iter().map(|var| Token::List(var.row())).collect();
+                    let params = vec![Argument::new(
+                        "variables".into(),
+                        Types::List(Box::new(Types::List(Box::new(Types::Str)))),
+                        None,
+                        Some(Box::new(Token::List(vars))),
+                    )];
+                    std_func.call(params)
+                } else {
+                    std_func.call(args)
+                };
+                result.map_err(ParseError::FunctionExecution)
+            }
+            Func::User(func) => {
+                Ok(func.call(args, self.variables.clone(), self.functions.clone())?)
+            }
+        }
+    }
+}
+
+/// The elements of a collection must have the same type, an empty collection fits in any other
+fn check_items<'a>(
+    values: impl IntoIterator<Item = &'a Token>,
+    what: &str,
+) -> Result<(), ParseError> {
+    let mut values = values.into_iter();
+    let Some(first) = values.next() else {
+        return Ok(());
+    };
+    let item_type = Types::from(first);
+    for value in values {
+        let value_type = Types::from(value);
+        let same = match value_type {
+            Types::List(_) | Types::Map(..) => value_type.can_convert(&item_type),
+            _ => value_type == item_type,
+        };
+        if !same {
+            return Err(ParseError::TypeError(format!(
+                "The {what} must have the same type, <{item_type}> and <{value_type}>."
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Convert the value to the type of the other items of a collection
+fn fit_item(value: Token, item_type: &Types, what: &str) -> Result<Token, ParseError> {
+    let value_type = Types::from(&value);
+    if *item_type == Types::Inferred || value_type == *item_type {
+        Ok(value)
+    } else if value_type.can_convert(item_type) {
+        Ok(Types::transform(value, item_type.clone())?.0)
+    } else {
+        Err(ParseError::TypeError(format!(
+            "The {what} are <{item_type}> but it's <{}>.",
+            value_type.name_for(&value)
+        )))
+    }
+}
+
+/// Convert the value, of the type `value_type`, to a declared type like a field of a struct or a
+/// value of a variant. The type parameters in `declared` take their types from `bindings` or
+/// from the value.
+fn fit_value(
+    value: Token,
+    value_type: &Types,
+    declared: &Types,
+    bindings: &mut Bindings,
+    what: &str,
+) -> Result<Token, ParseError> {
+    if !declared.unify(value_type, bindings) {
+        return Err(ParseError::TypeError(format!(
+            "The {what} must be <{}> but it's <{}>.",
+            declared.substitute(bindings),
+            value_type.name_for(&value)
+        )));
+    }
+
+    let declared = declared.substitute(bindings).without_generics();
+    if *value_type == declared || declared == Types::Inferred {
+        Ok(value)
+    } else {
+        Ok(Types::transform(value, declared)?.0)
+    }
+}
+
+/// The name and the fields of a struct value
+fn struct_fields(value: Token) -> Result<(Arc<str>, Fields), ParseError> {
+    match value {
+        Token::Struct { name, fields } => Ok((name, fields)),
+        Token::None => Err(ParseError::TypeError(
+            "The value is 'none' so it doesn't have fields, use '?.' to get a field of a value that can be 'none'.".into(),
+        )),
+        value => Err(ParseError::TypeError(format!(
+            "Only a struct has fields but it's <{}>.",
+            Types::from(value)
+        ))),
+    }
+}
+
+fn field_position(structure: &str, fields: &Fields, field: &str) -> Result<usize, ParseError> {
+    fields
+        .iter()
+        .position(|(name, _)| &**name == field)
+        .ok_or_else(|| ParseError::UndefinedField {
+            structure: structure.to_string(),
+            field: field.to_string(),
+        })
+}
+
+/// Only the integers, the texts and the booleans can be keys of a map
+fn check_key(key: &Token) -> Result<(), ParseError> {
+    match Types::from(key) {
+        Types::Int32
+        | Types::Int64
+        | Types::HPInt
+        | Types::String
+        | Types::Str
+        | Types::Boolean => Ok(()),
+        key_type => Err(ParseError::TypeError(format!(
+            "The keys of a map can't be <{}>.",
+            key_type.name_for(key)
+        ))),
+    }
+}
+
+/// Replace the value of the key keeping its position, or add the entry at the end
+fn insert_entry(entries: &mut Vec<(Token, Token)>, key: Token, value: Token) {
+    match entries.iter_mut().find(|(old, _)| old.is_same_key(&key)) {
+        Some(entry) => entry.1 = value,
+        None => entries.push((key, value)),
+    }
+}
+
+fn list_items(value: Token) -> Result<Vec<Token>, ParseError> {
+    match value {
+        Token::List(items) => Ok(items),
+        value => Err(ParseError::TypeError(format!(
+            "Only a <List> or a <Map> can be indexed but it's <{}>.",
+            Types::from(&value).name_for(&value)
+        ))),
+    }
+}
+
+fn index_value(index: &Token) -> Result<i128, ParseError> {
+    match index {
+        Token::Int32(num) => Ok(**num as i128),
+        Token::Int64(num) => Ok(**num as i128),
+        Token::HPInt(num) => Ok(**num),
+        index => Err(ParseError::TypeError(format!(
+            "The index of a list must be an integer but it's <{}>.",
+            Types::from(index).name_for(index)
+        ))),
+    }
+}
+
+/// Position in a list of `len` elements, the negative indexes count from the end
+fn list_position(index: i128, len: usize) -> Result<usize, ParseError> {
+    let position = if index < 0 {
+        index + len as i128
+    } else {
+        index
+    };
+    if (0..len as i128).contains(&position) {
+        Ok(position as usize)
+    } else {
+        Err(ParseError::IndexOutOfBounds { index, len })
+    }
+}
+
+/// Copy of the collection with a new value in the position or the key `index`. The value
+/// must have the type of the other elements, `declared` is the type of the place where the
+/// collection is stored, used when the collection is empty.
+fn set_item(
+    container: Token,
+    index: &Token,
+    value: Token,
+    declared: &Types,
+) -> Result<Token, ParseError> {
+    if let Token::Map(mut entries) = container {
+        let (Types::Map(key_type, value_type), declared) = (Types::map_of(&entries), declared)
+        else {
+            unreachable!("map_of always gives a map");
+        };
+        let (key_type, value_type) = match declared {
+            Types::Map(key, value) if **key != Types::Inferred => (key.clone(), value.clone()),
+            _ => (key_type, value_type),
+        };
+        check_key(index)?;
+        let key = fit_item(index.clone(), &key_type, "keys of the map")?;
+        let value = fit_item(value, &value_type, "values of the map")?;
+        insert_entry(&mut entries, key, value);
+        return Ok(Token::Map(entries));
+    }
+
+    let mut items = list_items(container)?;
+    let position = list_position(index_value(index)?, items.len())?;
+    let item_type = match declared {
+        Types::List(item) if **item != Types::Inferred => (**item).clone(),
+        _ => Types::from(&items[position]),
+    };
+    items[position] = fit_item(value, &item_type, "elements of the list")?;
+    Ok(Token::List(items))
+}
+
+#[cfg(test)]
+mod evaluator_test {
+    use crate::compiler::{lexer::Lexer, parser::Parser};
+
+    use super::*;
+
+    fn run(code: &str) -> (Evaluator, Result<Vec<Token>, ParseError>) {
+        let tokens = Lexer::new(code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut evaluator = Evaluator::new(None, None);
+        let result = evaluator.evaluate(&program);
+        (evaluator, result)
+    }
+
+    #[test]
+    fn evaluate_test() {
+        let (_, result) = run("var hola = 10\nhola * 2");
+        assert_eq!(result.unwrap(), vec![Token::Int32(20.into())]);
+    }
+
+    #[test]
+    fn block_scope_test() {
+        let (evaluator, result) = run("{\n var hola = 10\n}");
+        assert_eq!(result.unwrap(), vec![]);
+        assert!(evaluator.get_variables().is_empty());
+    }
+
+    #[test]
+    fn shadowing_test() {
+        let (evaluator, result) =
+            run("var x = 1\nvar y = 1\n{\n var x = 'inner'\n x\n x = 'changed'\n y = 2\n}\nx\ny");
+        assert_eq!(
+            result.unwrap(),
+            vec![
+                Token::Str("inner".into()),
+                Token::Int32(1.into()),
+                Token::Int32(2.into())
+            ]
+        );
+        assert_eq!(evaluator.get_variables().len(), 2);
+    }
+
+    #[test]
+    fn closure_test() {
+        let (_, result) =
+            run("var count = 0\nfunc bump() {\n count += 1\n}\nbump()\nbump()\ncount");
+        assert_eq!(result.unwrap(), vec![Token::Int32(2.into())]);
+    }
+
+    #[test]
+    fn undefined_variable_test() {
+        let (_, result) = run("hola + 1");
+        let error = result.unwrap_err();
+        assert!(matches!(error.kind(), ParseError::UndefinedVariable(_)));
+        assert_eq!(error.span(), Some(Span::new(0, 0, 4)));
+    }
+
+    #[test]
+    fn index_out_of_bounds_test() {
+        let (_, result) = run("var xs = [1, 2]\nxs[5]");
+        let error = result.unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            ParseError::IndexOutOfBounds { index: 5, len: 2 }
+        ));
+        assert_eq!(error.span(), Some(Span::new(0, 16, 21)));
+    }
+
+    #[test]
+    fn map_order_test() {
+        let (_, result) = run("var m = { \"b\": 1, \"a\": 2 }\nm[\"c\"] = 3\nm[\"b\"] = 4\nm");
+        let map = result.unwrap().remove(0);
+        assert_eq!(map.to_string(), "{b: 4, a: 2, c: 3}");
+    }
+
+    #[test]
+    fn condition_type_test() {
+        let (_, result) = run("if 'hola' {\n 1\n}");
+        let error = result.unwrap_err();
+        assert!(matches!(error.kind(), ParseError::TypeError(_)));
+        assert_eq!(error.span(), Some(Span::new(0, 3, 9)));
+    }
+
+    #[test]
+    fn function_error_span_test() {
+        let (_, result) = run("func foo() {\n  1 / hola\n}\nfoo()");
+        assert_eq!(result.unwrap_err().span(), Some(Span::new(0, 19, 23)));
+    }
+
+    #[test]
+    fn const_reassignment_test() {
+        let (evaluator, result) = run("const hola: Int32 = 10\nhola += 1");
+        let error = result.unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            ParseError::ConstReassignment {
+                declaration,
+                ..
+            } if *declaration == Span::new(0, 0, 22)
+        ));
+        assert_eq!(error.span(), Some(Span::new(0, 23, 32)));
+        assert_eq!(
+            *evaluator.get_variable_table().get("hola").unwrap().value,
+            Token::Int32(10.into())
+        );
+    }
+
+    #[test]
+    fn const_redeclaration_test() {
+        let (evaluator, result) = run("const l = 1\nvar l = 3\nl = 4");
+        let error = result.unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            ParseError::ConstReassignment { declaration, .. } if *declaration == Span::new(0, 0, 11)
+        ));
+        assert_eq!(error.span(), Some(Span::new(0, 12, 21)));
+        assert_eq!(
+            *evaluator.get_variable_table().get("l").unwrap().value,
+            Token::Int32(1.into())
+        );
+    }
+}
@@ -1,28 +1,36 @@
-use std::{cell::RefCell, fmt::Debug, rc::Rc, str::FromStr, sync::Arc};
-
-use crate::compiler::error::parse_error::ParseError;
+use std::{cell::RefCell, fmt::Debug, rc::Rc, sync::Arc};
 
 use super::{
-    elements::token::Token, error::function_error::FunctionError, function_table::FunctionTable,
-    parser::Parser, types::Types, variable::Variable, variable_table::VariableTable,
+    ast::Stmt,
+    elements::token::Token,
+    error::{function_error::FunctionError, parse_error::ParseError, spanned::Located},
+    evaluator::Evaluator,
+    function_table::FunctionTable,
+    types::{Bindings, Types},
+    variable::Variable,
+    variable_table::VariableTable,
 };
 
 #[derive(Debug, PartialEq, Clone)]
 #[allow(dead_code)]
 pub struct Argument {
     pub(crate) name: Arc<str>,
+    /// The declared type, it can use the type parameters of the function like `List<T>`
     pub(crate) var_type: Types,
     pub(crate) default_value: Option<Box<Token>>,
     pub(crate) value: Option<Box<Token>>,
+    /// `...name`, it takes the rest of the passed values as a list
+    pub(crate) variadic: bool,
 }
 
-impl<'a> From<Token> for Argument {
+impl From<Token> for Argument {
     fn from(value: Token) -> Self {
         Self {
             name: "".into(),
             var_type: Types::from(&value),
             default_value: Some(Box::new(value.clone())),
             value: Some(Box::new(value)),
+            variadic: false,
         }
     }
 }
@@ -39,6 +47,34 @@ impl Argument {
             var_type,
             default_value,
             value,
+            variadic: false,
+        }
+    }
+
+    /// A value passed by name, like `b` in `f(b: 2)`
+    pub fn named(name: Arc<str>, value: Token) -> Self {
+        Self {
+            name,
+            ..Self::from(value)
+        }
+    }
+
+    /// Make the argument take the rest of the passed values, its type must be a list
+    pub fn variadic(mut self) -> Self {
+        self.variadic = true;
+        self
+    }
+
+    /// If the value was passed by position
+    pub fn is_positional(&self) -> bool {
+        self.name.is_empty()
+    }
+
+    /// The value passed to the argument or its default value if it wasn't passed
+    pub fn get_value(&self) -> Token {
+        match (&self.value, &self.default_value) {
+            (Some(value), _) | (None, Some(value)) => *value.clone(),
+            (None, None) => Token::Void,
         }
     }
 
@@ -46,24 +82,58 @@ impl Argument {
         Variable::new(
             self.name.to_string(),
             self.var_type.clone(),
-            if self.value.is_some() {
-                *self.value.clone().unwrap()
-            } else {
-                *self.default_value.clone().unwrap()
-            },
+            self.get_value(),
             scope,
         )
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone)]
 pub struct Function {
     name: Arc<str>,
+    /// The type parameters, like `T` in `func first<T>(xs: List<T>) -> T`
+    generics: Vec<Arc<str>>,
     return_type: Types,
     args: Vec<Argument>,
-    content: Vec<Token>,
+    body: Vec<Stmt>,
     scope: usize,
     predefined: Option<Arc<str>>, // Usa un identificador único (nombre)
+    /// The scope where the function was declared, the body can use its variables
+    closure: Option<Rc<RefCell<VariableTable>>>,
+}
+
+/// The captured scope can hold the function itself, so it isn't shown
+impl Debug for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Function")
+            .field("name", &self.name)
+            .field("generics", &self.generics)
+            .field("return_type", &self.return_type)
+            .field("args", &self.args)
+            .field("body", &self.body)
+            .field("scope", &self.scope)
+            .field("predefined", &self.predefined)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Two functions capture the same scope only if it's the same table, not an equal one
+impl PartialEq for Function {
+    fn eq(&self, other: &Self) -> bool {
+        let same_closure = match (&self.closure, &other.closure) {
+            (Some(closure1), Some(closure2)) => Rc::ptr_eq(closure1, closure2),
+            (None, None) => true,
+            _ => false,
+        };
+        self.name == other.name
+            && self.generics == other.generics
+            && self.return_type == other.return_type
+            && self.args == other.args
+            && self.body == other.body
+            && self.scope == other.scope
+            && self.predefined == other.predefined
+            && same_closure
+    }
 }
 
 #[allow(dead_code)]
@@ -72,94 +142,247 @@ impl Function {
         name: Arc<str>,
         return_type: Types,
         args: Vec<Argument>,
-        content: Vec<Token>,
+        body: Vec<Stmt>,
         scope: usize,
     ) -> Self {
         Self {
             name,
+            generics: vec![],
             return_type,
             args,
-            content,
+            body,
             scope,
             predefined: None,
+            closure: None,
         }
     }
 
+    /// Make the function generic over the type parameters `generics`
+    pub fn with_generics(mut self, generics: Vec<Arc<str>>) -> Self {
+        self.generics = generics;
+        self
+    }
+
+    /// Capture the scope where the function is declared
+    pub fn with_closure(mut self, closure: Rc<RefCell<VariableTable>>) -> Self {
+        self.closure = Some(closure);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn new_predefined(name: Arc<str>, return_type: Types) -> Self {
         Self {
             name: name.clone(),
+            generics: vec![],
             return_type,
             args: vec![], // Las funciones predefinidas no necesitan argumentos explícitos
-            content: vec![], // Tampoco necesitan un cuerpo
+            body: vec![], // Tampoco necesitan un cuerpo
             scope: 0,     // El scope no importa aquí
             predefined: Some(name),
+            closure: None,
         }
     }
 
-    fn num_args_with_default_value(&self) -> usize {
-        return self
-            .args
-            .iter()
-            .filter(|x| x.default_value.is_some())
-            .count();
-    }
-
-    fn num_args_required(&self) -> usize {
-        self.args.len() - self.num_args_with_default_value()
-    }
-
+    /// Execute the body in a new scope inside the captured one, `variables` is used as the
+    /// outer scope when the function doesn't capture any
     pub fn call(
         &self,
         arguments: Vec<Argument>,
         variables: Rc<RefCell<VariableTable>>,
         functions: Rc<RefCell<FunctionTable>>,
     ) -> Result<Token, FunctionError> {
-        if arguments.len() > self.args.len() {
-            return Err(FunctionError::InvalidNumberOfArgs(format!(
-                "The function accept only {} arguments",
-                self.args.len()
-            )));
+        let outer = self.closure.clone().unwrap_or(variables);
+        let mut var_table = VariableTable::with_parent(outer);
+        let (params, bindings) = self.bind(arguments, &functions.borrow())?;
+        for mut param in params {
+            // A parameter without type, like in `|x| x * 2`, takes the type of the value
+            param.var_type = param.var_type.refine(&Types::from(&param.get_value()));
+            let var = param.as_var(var_table.depth());
+            var_table
+                .insert(&var.name.clone(), var)
+                .map_err(|err| FunctionError::ExecutionError(Box::new(err)))?;
         }
-        if arguments.len() < self.num_args_required() {
+
+        // The stack is shared by every call, so a recursive call sees the calls around it
+        functions.borrow_mut().push_call(self.name.clone())?;
+        let mut evaluator = Evaluator::new(
+            Some(Rc::new(RefCell::new(var_table))),
+            Some(functions.clone()),
+        );
+        let result = evaluator.execute_function(&self.body);
+        functions.borrow_mut().pop_call();
+
+        let (result, span) = result.map_err(|err| {
+            // The overflow is reported once instead of being wrapped by every call of the chain
+            if let ParseError::Function(error) = err.kind() {
+                let FunctionError::StackOverflow {
+                    function,
+                    max_depth,
+                } = error.kind()
+                else {
+                    return FunctionError::ExecutionError(Box::new(err));
+                };
+                let overflow = FunctionError::StackOverflow {
+                    function: function.clone(),
+                    max_depth: *max_depth,
+                };
+                return match err.span() {
+                    Some(span) => overflow.at(span),
+                    None => overflow,
+                };
+            }
+            FunctionError::ExecutionError(Box::new(err))
+        })?;
+
+        self.check_return(result, &functions.borrow(), &bindings)
+            .map_err(|error| match span {
+                Some(span) => error.at(span),
+                None => error,
+            })
+    }
+
+    /// The parameters with the passed values. The values without name go in order to the
+    /// parameters, the ones that are left go to the variadic parameter and the named ones go
+    /// to the parameter with their name. The values give a type to the type parameters, they
+    /// are returned with the parameters, whose types use them.
+    fn bind(
+        &self,
+        arguments: Vec<Argument>,
+        functions: &FunctionTable,
+    ) -> Result<(Vec<Argument>, Bindings), FunctionError> {
+        let mut params = self.args.clone();
+        let mut bindings = Bindings::new();
+        let variadic = params.last().is_some_and(|param| param.variadic);
+        let fixed = params.len() - usize::from(variadic);
+        let positional = arguments.iter().filter(|arg| arg.is_positional()).count();
+        if positional > fixed && !variadic {
             return Err(FunctionError::InvalidNumberOfArgs(format!(
-                "The function required only {} arguments",
-                self.args.len()
+                "The function '{}' accepts {fixed} arguments but it receives {positional}.",
+                self.name
             )));
         }
-        let mut args_to_variables: Vec<Variable> = vec![];
-        for var in &self.args {
-            args_to_variables.push(var.as_var(self.scope));
-        }
-        let mut var_table: VariableTable = (*variables.borrow_mut()).clone();
-        for arg in args_to_variables.iter_mut() {
-            if let Ok(var) = variables.borrow().get(&arg.name) {
-                let _ = var_table.update(&var.name, arg);
+
+        let mut rest = vec![];
+        for (position, arg) in arguments.into_iter().enumerate() {
+            let index = if !arg.is_positional() {
+                params
+                    .iter()
+                    .position(|param| param.name == arg.name)
+                    .ok_or_else(|| FunctionError::UnknownArgument {
+                        function: self.name.to_string(),
+                        argument: arg.name.to_string(),
+                    })?
+            } else if position < fixed {
+                position
             } else {
-                let _ = var_table.insert(&arg.name, arg.clone());
+                rest.push(arg.get_value());
+                continue;
+            };
+
+            let param = &mut params[index];
+            if param.value.is_some() {
+                return Err(FunctionError::RepeatedArgument {
+                    function: self.name.to_string(),
+                    argument: param.name.to_string(),
+                });
             }
+            let value = self.check_argument(param, arg.get_value(), functions, &mut bindings)?;
+            param.value = Some(Box::new(value));
         }
 
-        let mut parser: Parser = Parser::new(
-            self.content.clone(),
-            Some(Rc::new(RefCell::new(var_table))),
-            Some(functions),
-        );
-        let parse: Result<Vec<Token>, ParseError> = parser.parse();
-        if parse.is_err() {
-            return Err(FunctionError::ExecutionError(format!("r")));
-        }
-        let parse = parse.unwrap();
-        if self.return_type == Types::Void {
-            return Ok(Token::Void);
-        }
-        let result = parse[0].clone();
-        let result_type = Types::from_str(&result.to_string());
-        match result_type {
-            Ok(value) if value == self.return_type => Ok(parse[0].clone()),
-            _ => Err(FunctionError::DifferentReturnType(format!(
-                " the return type is {:?} and you are returning {:?}",
-                self.return_type, result_type
-            ))),
+        if let Some(param) = params.last_mut().filter(|param| param.variadic) {
+            if param.value.is_some() && !rest.is_empty() {
+                return Err(FunctionError::RepeatedArgument {
+                    function: self.name.to_string(),
+                    argument: param.name.to_string(),
+                });
+            }
+            if param.value.is_none() {
+                let Types::List(item_type) = &param.var_type else {
+                    unreachable!("the type of a variadic argument is a list");
+                };
+                let item = Argument::new(param.name.clone(), (**item_type).clone(), None, None);
+                let mut values = Vec::with_capacity(rest.len());
+                for value in rest {
+                    values.push(self.check_argument(&item, value, functions, &mut bindings)?);
+                }
+                param.value = Some(Box::new(Token::List(values)));
+            }
+        }
+
+        if let Some(param) = params
+            .iter()
+            .find(|param| param.value.is_none() && param.default_value.is_none())
+        {
+            return Err(FunctionError::MissingArgument {
+                function: self.name.to_string(),
+                argument: param.name.to_string(),
+            });
+        }
+        for param in &mut params {
+            param.var_type = param.var_type.substitute(&bindings).without_generics();
+        }
+        Ok((params, bindings))
+    }
+
+    /// Check that the passed value has the type of the parameter, the integers and the
+    /// decimals are converted to the declared size. A type parameter takes the type of the
+    /// first value passed to it and the next ones must have that type.
+    fn check_argument(
+        &self,
+        param: &Argument,
+        value: Token,
+        functions: &FunctionTable,
+        bindings: &mut Bindings,
+    ) -> Result<Token, FunctionError> {
+        let value_type = functions.type_of(&value);
+        if !param.var_type.unify(&value_type, bindings) {
+            return Err(FunctionError::ArgumentType {
+                function: self.name.to_string(),
+                argument: param.name.to_string(),
+                expected: param.var_type.substitute(bindings).to_string(),
+                found: value_type.name_for(&value),
+            });
+        }
+
+        let expected = param.var_type.substitute(bindings).without_generics();
+        if value_type == expected || expected == Types::Inferred {
+            return Ok(value);
+        }
+        Types::transform(value, expected)
+            .map(|(value, _)| value)
+            .map_err(|err| FunctionError::ExecutionError(Box::new(err)))
+    }
+
+    /// Check that the returned value has the declared return type, the integers and the
+    /// decimals are converted to the declared size. A lambda without return type can return
+    /// any value, and the type parameters have the types given by the arguments.
+    fn check_return(
+        &self,
+        result: Token,
+        functions: &FunctionTable,
+        bindings: &Bindings,
+    ) -> Result<Token, FunctionError> {
+        let result_type = functions.type_of(&result);
+        let return_type = self.return_type.substitute(bindings);
+        if !return_type.unify(&result_type, &mut bindings.clone()) {
+            return Err(FunctionError::DifferentReturnType(format!(
+                "The function '{}' must return <{}> but it returns <{}>.",
+                self.name,
+                return_type,
+                result_type.name_for(&result)
+            )));
+        }
+
+        let return_type = return_type.without_generics();
+        if result_type == return_type || return_type == Types::Inferred {
+            return Ok(result);
         }
+        Types::transform(result, return_type)
+            .map(|(value, _)| value)
+            .map_err(|err| FunctionError::ExecutionError(Box::new(err)))
     }
 }
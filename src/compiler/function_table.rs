@@ -1,26 +1,205 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use super::{error::parse_error::ParseError, function::Function};
+use super::{
+    ast::{EnumDecl, StructDecl, Variant},
+    elements::token::Token,
+    error::{function_error::FunctionError, parse_error::ParseError},
+    function::Function,
+    types::{Bindings, Types},
+};
 use crate::std::{standard_library, StdFunc};
 
 #[derive(Debug, Clone)]
 pub struct FunctionTable {
     pub(crate) functions: HashMap<String, Function>,
     pub(crate) std: HashMap<String, StdFunc>,
+    /// The structs declared in the program, by name
+    pub(crate) structs: HashMap<String, StructDecl>,
+    /// The enums declared in the program, by name
+    pub(crate) enums: HashMap<String, EnumDecl>,
+    /// The user functions that are running, the last one is the innermost call
+    call_stack: Vec<Arc<str>>,
+    /// Number of nested calls allowed before a [`FunctionError::StackOverflow`]
+    max_call_depth: usize,
 }
 
+/// Default of the maximum number of nested calls
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
+/// Largest maximum of nested calls that can be chosen. The evaluator grows its stack on the
+/// heap, and a call with a nested body takes about 160 KiB of it in a debug build.
+pub const MAX_CALL_DEPTH: usize = 10_000;
+
+/// A function of the program or of the standard library, it's also the value of a variable
+/// that holds a function
+#[derive(Debug, Clone)]
 pub enum Func {
     User(Function),
     Std(StdFunc),
 }
 
+impl Func {
+    pub fn name(&self) -> &str {
+        match self {
+            Func::User(function) => function.name(),
+            Func::Std(function) => &function.name,
+        }
+    }
+}
+
+/// The functions of the standard library are the same if they have the same name
+impl PartialEq for Func {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Func::User(function1), Func::User(function2)) => function1 == function2,
+            (Func::Std(function1), Func::Std(function2)) => function1.name == function2.name,
+            _ => false,
+        }
+    }
+}
+
+impl Default for FunctionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[allow(dead_code)]
 impl FunctionTable {
     pub fn new() -> Self {
         FunctionTable {
             functions: HashMap::new(),
             std: standard_library(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            call_stack: vec![],
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+        }
+    }
+
+    pub fn with_max_call_depth(max_call_depth: usize) -> Self {
+        FunctionTable {
+            max_call_depth,
+            ..Self::new()
+        }
+    }
+
+    pub fn call_stack(&self) -> &[Arc<str>] {
+        &self.call_stack
+    }
+
+    /// Register the start of a call, the function can be already in the stack if it's recursive
+    pub fn push_call(&mut self, name: Arc<str>) -> Result<(), FunctionError> {
+        if self.call_stack.len() >= self.max_call_depth {
+            return Err(FunctionError::StackOverflow {
+                function: name.to_string(),
+                max_depth: self.max_call_depth,
+            });
+        }
+        self.call_stack.push(name);
+        Ok(())
+    }
+
+    /// Register the end of the innermost call
+    pub fn pop_call(&mut self) {
+        self.call_stack.pop();
+    }
+
+    pub fn get_struct(&self, name: &str) -> Result<&StructDecl, ParseError> {
+        self.structs
+            .get(name)
+            .ok_or_else(|| ParseError::UndefinedType(format!("The type '{name}' doesn't exist.")))
+    }
+
+    pub fn insert_struct(&mut self, value: StructDecl) -> Result<(), ParseError> {
+        self.check_new_type(&value.name)?;
+        self.structs.insert(value.name.to_string(), value);
+        Ok(())
+    }
+
+    /// The enum with a variant called `name` and the variant
+    pub fn get_variant(&self, name: &str) -> Option<(&EnumDecl, &Variant)> {
+        self.enums.values().find_map(|enumeration| {
+            enumeration
+                .variant(name)
+                .map(|variant| (enumeration, variant))
+        })
+    }
+
+    /// The names of the variants are global, like the functions, so they can't be repeated
+    pub fn insert_enum(&mut self, value: EnumDecl) -> Result<(), ParseError> {
+        self.check_new_type(&value.name)?;
+        for variant in &value.variants {
+            if let Some((other, _)) = self.get_variant(&variant.name) {
+                return Err(ParseError::DefinedType(format!(
+                    "The variant '{}' is already declared in '{}'.",
+                    variant.name, other.name
+                )));
+            }
+        }
+        self.enums.insert(value.name.to_string(), value);
+        Ok(())
+    }
+
+    /// The type of a value, a struct or a variant of a generic type has the types that its
+    /// values give to the type parameters, like `Pair<Int32, Str>`
+    pub fn type_of(&self, value: &Token) -> Types {
+        match value {
+            Token::Struct { name, fields } => match self.structs.get(&**name) {
+                Some(structure) if !structure.generics.is_empty() => {
+                    let mut bindings = Bindings::new();
+                    for (field, value) in fields {
+                        if let Some(field_type) = structure.field_type(field) {
+                            field_type.unify(&self.type_of(value), &mut bindings);
+                        }
+                    }
+                    Types::custom_of(name, &structure.generics, &bindings)
+                }
+                _ => Types::from(value),
+            },
+            Token::Variant {
+                enumeration,
+                name,
+                values,
+            } => match self.enums.get(&**enumeration) {
+                Some(declared) if !declared.generics.is_empty() => {
+                    let mut bindings = Bindings::new();
+                    if let Some(variant) = declared.variant(name) {
+                        for (field_type, value) in variant.fields.iter().zip(values) {
+                            field_type.unify(&self.type_of(value), &mut bindings);
+                        }
+                    }
+                    Types::custom_of(enumeration, &declared.generics, &bindings)
+                }
+                _ => Types::from(value),
+            },
+            Token::List(items) => Types::List(Box::new(
+                items
+                    .first()
+                    .map_or(Types::Inferred, |item| self.type_of(item)),
+            )),
+            Token::Map(entries) => match entries.first() {
+                Some((key, value)) => {
+                    Types::Map(Box::new(self.type_of(key)), Box::new(self.type_of(value)))
+                }
+                None => Types::from(value),
+            },
+            _ => Types::from(value),
+        }
+    }
+
+    /// Check if a struct or an enum is called `name`
+    pub fn has_type(&self, name: &str) -> bool {
+        self.structs.contains_key(name) || self.enums.contains_key(name)
+    }
+
+    fn check_new_type(&self, name: &str) -> Result<(), ParseError> {
+        if self.has_type(name) {
+            return Err(ParseError::DefinedType(format!(
+                "The type '{name}' is already declared."
+            )));
         }
+        Ok(())
     }
 
     pub fn get(&self, key: &str) -> Result<Func, ParseError> {
@@ -51,7 +230,7 @@ impl FunctionTable {
             Ok(())
         } else if let Some(var) = self.functions.get_key_value(key) {
             if *var.1 == value {
-                return Err(ParseError::DefinedVariable(format!("1.name")));
+                return Err(ParseError::DefinedVariable("1.name".to_string()));
             }
             self.functions.insert(key.to_string(), value);
             Ok(())
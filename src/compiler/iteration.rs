@@ -0,0 +1,152 @@
+use std::vec;
+
+use crate::types::basic::number::{hpint::HPInt, int32::Int32, int64::Int64};
+
+use super::{elements::token::Token, error::parse_error::ParseError, types::Types};
+
+/// Values that a `for` loop can go through.
+///
+/// The ranges are produced lazily, so `0..1000000` doesn't need to create a list.
+#[derive(Debug, Clone)]
+pub enum Iteration {
+    Range(Range),
+    List(vec::IntoIter<Token>),
+}
+
+impl Iteration {
+    /// Iterate over a value, a `List` gives its elements and a `Map` its keys in the order in
+    /// which they were inserted
+    pub fn from_token(value: Token) -> Result<Self, ParseError> {
+        match value {
+            Token::List(list) => Ok(Iteration::List(list.into_iter())),
+            Token::Map(entries) => Ok(Iteration::List(
+                entries
+                    .into_iter()
+                    .map(|(key, _)| key)
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            )),
+            value => Err(ParseError::TypeError(format!(
+                "Only a <List>, a <Map> or a range can be iterated but it's <{}>.",
+                Types::from(&value).name_for(&value)
+            ))),
+        }
+    }
+}
+
+impl Iterator for Iteration {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iteration::Range(range) => range.next(),
+            Iteration::List(list) => list.next(),
+        }
+    }
+}
+
+/// `start..end step n` or `start..=end step n`, only with integers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    current: i128,
+    end: i128,
+    step: i128,
+    inclusive: bool,
+    /// Type of the produced numbers, the widest of the limits and the step
+    item_type: Types,
+}
+
+impl Range {
+    pub fn new(
+        start: Token,
+        end: Token,
+        step: Option<Token>,
+        inclusive: bool,
+    ) -> Result<Self, ParseError> {
+        let step = step.unwrap_or(Token::Int32(1.into()));
+        let (start, start_type) = Self::integer(start)?;
+        let (end, end_type) = Self::integer(end)?;
+        let (step, step_type) = Self::integer(step)?;
+
+        if step == 0 {
+            return Err(ParseError::InvalidRange(
+                "The step of a range can't be zero.".into(),
+            ));
+        }
+
+        let item_type = [start_type, end_type, step_type]
+            .into_iter()
+            .max_by_key(|types| match types {
+                Types::Int32 => 0,
+                Types::Int64 => 1,
+                _ => 2,
+            })
+            .unwrap_or(Types::Int32);
+
+        Ok(Range {
+            current: start,
+            end,
+            step,
+            inclusive,
+            item_type,
+        })
+    }
+
+    /// Type of the produced numbers
+    pub fn item_type(&self) -> &Types {
+        &self.item_type
+    }
+
+    fn integer(value: Token) -> Result<(i128, Types), ParseError> {
+        match value {
+            Token::Int32(num) => Ok((*num as i128, Types::Int32)),
+            Token::Int64(num) => Ok((*num as i128, Types::Int64)),
+            Token::HPInt(num) => Ok((*num, Types::HPInt)),
+            value => Err(ParseError::InvalidRange(format!(
+                "The limits and the step of a range must be integers but it's <{}>.",
+                Types::from(&value).name_for(&value)
+            ))),
+        }
+    }
+}
+
+impl Iterator for Range {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let in_range = match (self.step > 0, self.inclusive) {
+            (true, false) => self.current < self.end,
+            (true, true) => self.current <= self.end,
+            (false, false) => self.current > self.end,
+            (false, true) => self.current >= self.end,
+        };
+        if !in_range {
+            return None;
+        }
+
+        let value = self.current;
+        self.current += self.step;
+        Some(match self.item_type {
+            Types::Int32 => Token::Int32(Int32::new(value as i32)),
+            Types::Int64 => Token::Int64(Int64::new(value as i64)),
+            _ => Token::HPInt(HPInt::new(value)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod iteration_test {
+    use super::*;
+
+    #[test]
+    fn range_test() {
+        let range = Range::new(10.into(), 0.into(), Some((-3).into()), true).unwrap();
+        let values: Vec<Token> = range.collect();
+        assert_eq!(values, vec![10.into(), 7.into(), 4.into(), 1.into()]);
+
+        let range = Range::new(0.into(), 3.into(), None, false).unwrap();
+        assert_eq!(range.count(), 3);
+
+        assert!(Range::new(0.into(), 3.into(), Some(0.into()), false).is_err());
+    }
+}
@@ -1,10 +1,10 @@
+use super::span::Span;
 use super::{
     elements::{keyword::Keyword, operator::Operator},
-    error::lexic_error::LexicError,
+    error::{lexic_error::LexicError, spanned::Located},
     types::Types,
 };
-use crate::compiler::elements::token::Token;
-use crate::utils::panic;
+use crate::compiler::elements::token::{SpannedToken, Token};
 use std::{
     iter::Peekable,
     str::{Chars, FromStr},
@@ -19,238 +19,392 @@ pub struct Lexer<'a> {
 
     /// Text to lexing
     data: &'a str,
+
+    /// Byte offset of the next character
+    position: usize,
+
+    /// Id of the file in the [`SourceMap`](super::span::SourceMap)
+    file_id: usize,
+
+    /// Keep the comments as [`Token::Comment`] instead of discarding them
+    trivia: bool,
 }
 
 #[allow(dead_code)]
 impl<'a> Lexer<'a> {
     /// Create new Empty lexer
     pub fn new(content: &'a str) -> Lexer<'a> {
+        Lexer::with_file(content, 0)
+    }
+
+    /// Create a lexer whose spans point to the file `file_id`
+    pub fn with_file(content: &'a str, file_id: usize) -> Lexer<'a> {
         Lexer {
             content: content.chars().peekable(),
             data: content,
+            position: 0,
+            file_id,
+            trivia: false,
         }
     }
 
-    /// Transform each character in a Token
-    pub fn lex(&mut self) -> Vec<Token> {
-        let mut result: Vec<Token> = vec![];
-        while let Some(char) = self.content.peek() {
-            match char {
+    /// Keep the comments in the result, useful for tools like a formatter
+    pub fn with_trivia(mut self) -> Lexer<'a> {
+        self.trivia = true;
+        self
+    }
+
+    /// Transform each character in a Token.
+    ///
+    /// The lexer doesn't stop at the first error, it returns every error found in the text.
+    pub fn lex(&mut self) -> Result<Vec<SpannedToken>, Vec<LexicError>> {
+        let mut result: Vec<SpannedToken> = vec![];
+        let mut errors: Vec<LexicError> = vec![];
+        while let Some(&char) = self.content.peek() {
+            let start = self.position;
+            let token = match char {
                 // Words
-                'a'..='z' | 'A'..='Z' => {
-                    let id = self.cut_identifier();
-                    match id {
-                        Ok(id) => match id.type_id {
-                            IdentifierType::Id => {
-                                result.push(Token::Identifier(Arc::from(id.value.unwrap())))
-                            }
-
-                            IdentifierType::Keyword => {
-                                result.push(Token::Keyword(id.keyword.unwrap()))
-                            }
-                            IdentifierType::Type => {
-                                result.push(Token::Type(id.return_type.unwrap()));
-                            }
-                        },
-                        Err(err) => panic(&format!("{:?}", err)),
+                'a'..='z' | 'A'..='Z' | '_' => match self.cut_identifier() {
+                    Ok(id) => match id.type_id {
+                        IdentifierType::Id => Token::Identifier(Arc::from(id.value.unwrap())),
+                        IdentifierType::Keyword => Token::Keyword(id.keyword.unwrap()),
+                        IdentifierType::Type => Token::Type(id.return_type.unwrap()),
+                    },
+                    Err(err) => {
+                        errors.push(err.at(self.span_from(start)));
+                        continue;
                     }
-                }
+                },
 
                 '"' => {
-                    self.content.next();
-                    let id = self.cut_string();
-                    match id {
-                        Ok(id) => result.push(Token::String(id)),
-                        Err(err) => panic(&format!("{:?}", err)),
+                    self.next_char();
+                    match self.cut_text('"') {
+                        Ok(id) => Token::String(id),
+                        Err(err) => {
+                            errors.push(err.at(self.span_from(start)));
+                            continue;
+                        }
                     }
                 }
 
                 '\'' => {
-                    self.content.next();
-                    let id = self.cut_str();
-                    match id {
-                        Ok(id) => result.push(Token::Str(Arc::from(id))),
-                        Err(err) => panic(&format!("{:?}", err)),
+                    self.next_char();
+                    match self.cut_text('\'') {
+                        Ok(id) => Token::Str(Arc::from(id)),
+                        Err(err) => {
+                            errors.push(err.at(self.span_from(start)));
+                            continue;
+                        }
                     }
                 }
 
-                // Numbers
-                '0'..='9' | '.' => {
-                    let number = self.cut_number();
-                    match number {
-                        Ok(num) => result.push(Token::to_number(num, Types::Void)),
-                        Err(err) => panic(&format!("{:?}", err)),
+                // Variadic arguments, `...rest`
+                '.' if self.data[self.position..].starts_with("...") => {
+                    self.next_char();
+                    self.next_char();
+                    self.next_char();
+                    Token::Ellipsis
+                }
+
+                // Ranges
+                '.' if self.data[self.position..].starts_with("..") => {
+                    self.next_char();
+                    self.next_char();
+
+                    if self.next_if('=') {
+                        Token::Operator(Operator::RangeInclusive)
+                    } else {
+                        Token::Operator(Operator::Range)
                     }
                 }
 
+                // Field access, `point.x`
+                '.' if !self.data[self.position + 1..]
+                    .starts_with(|c: char| c.is_ascii_digit()) =>
+                {
+                    self.next_char();
+                    Token::Separator('.')
+                }
+
+                // Numbers
+                '0'..='9' | '.' => match self.cut_number() {
+                    Ok(num) => num,
+                    Err(err) => {
+                        errors.push(err.at(self.span_from(start)));
+                        continue;
+                    }
+                },
+
+                // Comments
+                '#' => match self.cut_comment() {
+                    Ok(comment) if self.trivia => Token::Comment(Arc::from(comment)),
+                    Ok(_) => continue,
+                    Err(err) => {
+                        errors.push(err.at(self.span_from(start)));
+                        continue;
+                    }
+                },
+
                 // Operators
                 '=' => {
-                    self.content.next();
+                    self.next_char();
+
+                    if self.next_if('=') {
+                        if self.next_if('=') {
+                            Token::Operator(Operator::StrictEqual)
+                        } else {
+                            Token::Operator(Operator::Equal)
+                        }
+                    } else if self.next_if('>') {
+                        Token::FatArrow
+                    } else {
+                        Token::Operator(Operator::Assign)
+                    }
+                }
+
+                '!' => {
+                    self.next_char();
 
-                    if let Some('=') = self.content.peek() {
-                        self.content.next();
-                        if let Some('=') = self.content.peek() {
-                            self.content.next();
-                            result.push(Token::Operator(Operator::StrictEqual));
+                    if self.next_if('=') {
+                        if self.next_if('=') {
+                            Token::Operator(Operator::StrictNotEqual)
                         } else {
-                            result.push(Token::Operator(Operator::Equal));
+                            Token::Operator(Operator::NotEqual)
                         }
                     } else {
-                        result.push(Token::Operator(Operator::Assign));
+                        Token::Operator(Operator::Not)
+                    }
+                }
+
+                '&' => {
+                    self.next_char();
+
+                    if self.next_if('&') {
+                        Token::Operator(Operator::And)
+                    } else if self.next_if('=') {
+                        Token::Operator(Operator::BitAndAssign)
+                    } else {
+                        Token::Operator(Operator::BitAnd)
+                    }
+                }
+
+                '|' => {
+                    self.next_char();
+
+                    if self.next_if('|') {
+                        Token::Operator(Operator::Or)
+                    } else if self.next_if('=') {
+                        Token::Operator(Operator::BitOrAssign)
+                    } else {
+                        Token::Operator(Operator::BitOr)
+                    }
+                }
+
+                '^' => {
+                    self.next_char();
+
+                    if self.next_if('=') {
+                        Token::Operator(Operator::BitXorAssign)
+                    } else {
+                        Token::Operator(Operator::BitXor)
                     }
                 }
 
+                '~' => {
+                    self.next_char();
+                    Token::Operator(Operator::BitNot)
+                }
+
                 '+' => {
-                    self.content.next();
+                    self.next_char();
 
-                    if let Some('=') = self.content.peek() {
-                        self.content.next();
-                        result.push(Token::Operator(Operator::AddAssign));
+                    if self.next_if('=') {
+                        Token::Operator(Operator::AddAssign)
                     } else {
-                        result.push(Token::Operator(Operator::Add));
+                        Token::Operator(Operator::Add)
                     }
                 }
 
                 '-' => {
-                    self.content.next();
+                    self.next_char();
 
-                    if let Some('=') = self.content.peek() {
-                        self.content.next();
-                        result.push(Token::Operator(Operator::SubAssign));
+                    if self.next_if('=') {
+                        Token::Operator(Operator::SubAssign)
+                    } else if self.next_if('>') {
+                        Token::Arrow
                     } else {
-                        result.push(Token::Operator(Operator::Sub));
+                        Token::Operator(Operator::Sub)
                     }
                 }
 
                 '*' => {
-                    self.content.next();
-
-                    if let Some('=') = self.content.peek() {
-                        self.content.next();
-                        result.push(Token::Operator(Operator::MulAssign));
-                    } else if let Some('*') = self.content.peek() {
-                        self.content.next();
-                        if let Some('=') = self.content.peek() {
-                            self.content.next();
-                            result.push(Token::Operator(Operator::PowAssign));
+                    self.next_char();
+
+                    if self.next_if('=') {
+                        Token::Operator(Operator::MulAssign)
+                    } else if self.next_if('*') {
+                        if self.next_if('=') {
+                            Token::Operator(Operator::PowAssign)
                         } else {
-                            result.push(Token::Operator(Operator::Pow));
+                            Token::Operator(Operator::Pow)
                         }
                     } else {
-                        result.push(Token::Operator(Operator::Mul));
+                        Token::Operator(Operator::Mul)
                     }
                 }
 
                 '/' => {
-                    self.content.next();
-
-                    if let Some('=') = self.content.peek() {
-                        self.content.next();
-                        result.push(Token::Operator(Operator::DivAssign));
-                    } else if let Some('/') = self.content.peek() {
-                        self.content.next();
-                        if let Some('=') = self.content.peek() {
-                            self.content.next();
-                            result.push(Token::Operator(Operator::DivIntAssign));
+                    self.next_char();
+
+                    if self.next_if('=') {
+                        Token::Operator(Operator::DivAssign)
+                    } else if self.next_if('/') {
+                        if self.next_if('=') {
+                            Token::Operator(Operator::DivIntAssign)
                         } else {
-                            result.push(Token::Operator(Operator::DivInt));
+                            Token::Operator(Operator::DivInt)
                         }
                     } else {
-                        result.push(Token::Operator(Operator::Div));
+                        Token::Operator(Operator::Div)
                     }
                 }
 
                 '%' => {
-                    self.content.next();
+                    self.next_char();
 
-                    if let Some('=') = self.content.peek() {
-                        self.content.next();
-                        result.push(Token::Operator(Operator::ModAssign));
+                    if self.next_if('=') {
+                        Token::Operator(Operator::ModAssign)
                     } else {
-                        result.push(Token::Operator(Operator::Mod));
+                        Token::Operator(Operator::Mod)
                     }
                 }
 
                 '>' => {
-                    self.content.next();
+                    self.next_char();
 
-                    if let Some('=') = self.content.peek() {
-                        self.content.next();
-                        result.push(Token::Operator(Operator::GreaterOrEqual));
+                    if self.next_if('=') {
+                        Token::Operator(Operator::GreaterOrEqual)
+                    } else if self.next_if('>') {
+                        if self.next_if('=') {
+                            Token::Operator(Operator::ShiftRightAssign)
+                        } else {
+                            Token::Operator(Operator::ShiftRight)
+                        }
                     } else {
-                        result.push(Token::Operator(Operator::Greater));
+                        Token::Operator(Operator::Greater)
                     }
                 }
 
                 '<' => {
-                    self.content.next();
+                    self.next_char();
 
-                    if let Some('=') = self.content.peek() {
-                        self.content.next();
-                        result.push(Token::Operator(Operator::LowerOrEqual));
+                    if self.next_if('=') {
+                        Token::Operator(Operator::LowerOrEqual)
+                    } else if self.next_if('<') {
+                        if self.next_if('=') {
+                            Token::Operator(Operator::ShiftLeftAssign)
+                        } else {
+                            Token::Operator(Operator::ShiftLeft)
+                        }
                     } else {
-                        result.push(Token::Operator(Operator::Lower));
+                        Token::Operator(Operator::Lower)
                     }
                 }
 
-                // Others
-                ' ' | '\t' => {
-                    self.content.next();
+                // Optionals, `a ?? b`, `a?.field` and `Int32?`
+                '?' => {
+                    self.next_char();
+
+                    if self.next_if('?') {
+                        Token::Operator(Operator::Coalesce)
+                    } else if self.next_if('.') {
+                        Token::QuestionDot
+                    } else {
+                        Token::Question
+                    }
                 }
 
-                '\n' => {
-                    self.content.next();
-                    result.push(Token::NewLine);
+                // Others, `\r` is part of the line ends of Windows
+                ' ' | '\t' | '\r' => {
+                    self.next_char();
+                    continue;
                 }
 
-                ',' => {
-                    self.content.next();
-                    result.push(Token::Separator(','));
+                '\n' => {
+                    self.next_char();
+                    Token::NewLine
                 }
 
-                ':' => {
-                    self.content.next();
-                    result.push(Token::Separator(':'));
+                ',' | ':' => {
+                    self.next_char();
+                    Token::Separator(char)
                 }
 
                 '(' => {
-                    self.content.next();
-                    result.push(Token::StartParenthesis);
+                    self.next_char();
+                    Token::StartParenthesis
                 }
 
                 ')' => {
-                    self.content.next();
-                    result.push(Token::EndParenthesis);
+                    self.next_char();
+                    Token::EndParenthesis
                 }
 
                 '[' => {
-                    self.content.next();
-                    result.push(Token::StartBracket);
+                    self.next_char();
+                    Token::StartBracket
                 }
 
                 ']' => {
-                    self.content.next();
-                    result.push(Token::EndBracket);
+                    self.next_char();
+                    Token::EndBracket
                 }
 
                 '{' => {
-                    self.content.next();
-                    result.push(Token::StartBrace);
+                    self.next_char();
+                    Token::StartBrace
                 }
 
                 '}' => {
-                    self.content.next();
-                    result.push(Token::EndBrace);
+                    self.next_char();
+                    Token::EndBrace
                 }
 
                 _ => {
-                    self.content.next();
-                    break;
+                    self.next_char();
+                    errors.push(LexicError::UnexpectedCharacter(char).at(self.span_from(start)));
+                    continue;
                 }
-            }
+            };
+            result.push(SpannedToken::new(token, self.span_from(start)));
+        }
+        result.push(SpannedToken::new(Token::EOF, self.span_from(self.position)));
+
+        if errors.is_empty() {
+            Ok(result)
+        } else {
+            Err(errors)
         }
-        result.push(Token::EOF);
-        return result;
+    }
+
+    /// Consume the next character keeping track of its byte position
+    fn next_char(&mut self) -> Option<char> {
+        let char = self.content.next()?;
+        self.position += char.len_utf8();
+        Some(char)
+    }
+
+    /// Consume the next character only if it's `expected`
+    fn next_if(&mut self, expected: char) -> bool {
+        if self.content.peek() == Some(&expected) {
+            self.next_char();
+            return true;
+        }
+        false
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        Span::new(self.file_id, start, self.position)
     }
 
     fn cut_identifier(&mut self) -> Result<Identifier, LexicError> {
@@ -260,13 +414,13 @@ impl<'a> Lexer<'a> {
             if *char == ' ' || !char.is_alphanumeric() {
                 if char == &'_' {
                     id.push(*char);
-                    self.content.next();
+                    self.next_char();
                 } else {
                     break;
                 }
             } else {
                 id.push(*char);
-                self.content.next();
+                self.next_char();
             }
         }
 
@@ -303,99 +457,87 @@ impl<'a> Lexer<'a> {
         })
     }
 
-    fn cut_str(&mut self) -> Result<String, LexicError> {
+    /// Cut a text until the closing `quote`, which is consumed too
+    fn cut_text(&mut self, quote: char) -> Result<String, LexicError> {
         let mut id = String::new();
+        let mut error = None;
 
-        while let Some(char) = self.content.next() {
-            if char == '\'' {
-                break;
-            }
+        loop {
+            let Some(char) = self.next_char() else {
+                return Err(LexicError::UnfinalizedString);
+            };
             match char {
-                '\\' => match self.content.peek() {
-                    Some(ch) => {
-                        match ch {
-                            'r' => {
-                                self.content.next();
-                                id.push('\r');
-                                id.push('\n');
-                            }
-                            'n' => {
-                                self.content.next();
-                                id.push('\n')
-                            }
-                            't' => {
-                                self.content.next();
-                                id.push('\t')
-                            }
-                            _ => return Err(LexicError::UnfinalizedString),
-                        };
-                    }
+                '\\' => match self.next_char() {
+                    Some('r') => id.push_str("\r\n"),
+                    Some('n') => id.push('\n'),
+                    Some('t') => id.push('\t'),
+                    Some(ch) if ch == '\\' || ch == quote => id.push(ch),
+                    // Keep cutting to find the end of the text
+                    Some(ch) => error = error.or(Some(LexicError::InvalidEscape(ch))),
                     None => return Err(LexicError::UnfinalizedString),
                 },
+                _ if char == quote => break,
                 _ => id.push(char),
             };
         }
 
-        Ok(id)
+        match error {
+            Some(error) => Err(error),
+            None => Ok(id),
+        }
     }
 
-    fn cut_string(&mut self) -> Result<String, LexicError> {
-        let mut id = String::new();
-
-        while let Some(char) = self.content.next() {
-            if char == '"' {
-                break;
+    /// Cut a line comment `# ...` until the end of the line, or a block comment
+    /// `#[ ... ]#` which can contain other block comments.
+    fn cut_comment(&mut self) -> Result<&'a str, LexicError> {
+        let start = self.position;
+        self.next_char();
+
+        if self.next_if('[') {
+            let mut depth = 1;
+            while depth > 0 {
+                match self.next_char() {
+                    Some('#') if self.next_if('[') => depth += 1,
+                    Some(']') if self.next_if('#') => depth -= 1,
+                    Some(_) => {}
+                    None => return Err(LexicError::UnfinalizedComment),
+                }
+            }
+        } else {
+            while self.content.peek().is_some_and(|char| *char != '\n') {
+                self.next_char();
             }
-            match char {
-                '\\' => match self.content.peek() {
-                    Some(ch) => {
-                        match ch {
-                            'r' => {
-                                self.content.next();
-                                id.push('\r');
-                                id.push('\n');
-                            }
-                            'n' => {
-                                self.content.next();
-                                id.push('\n')
-                            }
-                            't' => {
-                                self.content.next();
-                                id.push('\t')
-                            }
-                            _ => return Err(LexicError::UnfinalizedString),
-                        };
-                    }
-                    None => return Err(LexicError::UnfinalizedString),
-                },
-                _ => id.push(char),
-            };
         }
 
-        Ok(id)
+        Ok(self.data[start..self.position].trim_end_matches('\r'))
     }
 
-    fn cut_number(&mut self) -> Result<String, LexicError> {
+    fn cut_number(&mut self) -> Result<Token, LexicError> {
         let mut id = String::new();
 
         while let Some(char) = self.content.peek() {
             match *char {
+                // The start of a range like `0..10`
+                '.' if self.data[self.position..].starts_with("..") => break,
                 '0'..='9' | '.' => {
                     id.push(*char);
-                    self.content.next();
+                    self.next_char();
                 }
-                '\n' | '\t' => break,
                 _ => break,
             }
         }
 
-        if id.chars().all(|x| x.is_digit(10) || x == '.' || x == ',') {
-            return Ok(id);
+        let number = match id.matches('.').count() {
+            0 | 1 if id != "." => Token::to_number(&id, Types::Void),
+            _ => Token::EOF,
+        };
+        if number == Token::EOF {
+            return Err(LexicError::InvalidNumber(format!(
+                "The guess number {id} is an invalid number"
+            )));
         }
 
-        Err(LexicError::InvalidNumber(format!(
-            "The guess number {id} is an invalid number"
-        )))
+        Ok(number)
     }
 }
 
@@ -405,9 +547,22 @@ impl PartialEq for Lexer<'_> {
     }
 }
 
+struct Identifier {
+    value: Option<String>,
+    type_id: IdentifierType,
+    keyword: Option<Keyword>,
+    return_type: Option<Types>,
+}
+
+enum IdentifierType {
+    Id,
+    Keyword,
+    Type,
+}
+
 #[cfg(test)]
 mod lexer_test {
-    use crate::compiler::elements::keyword::Keyword;
+    use crate::{compiler::elements::keyword::Keyword, types::basic::number::double::Double};
 
     use super::*;
 
@@ -419,7 +574,10 @@ mod lexer_test {
             lex,
             Lexer {
                 content: content.chars().peekable(),
-                data: content
+                data: content,
+                position: 0,
+                file_id: 0,
+                trivia: false,
             }
         )
     }
@@ -429,7 +587,7 @@ mod lexer_test {
         let mut lex = Lexer::new("var hola = 10\n");
 
         assert_eq!(
-            lex.lex(),
+            lex.lex().unwrap(),
             vec![
                 Token::Keyword(Keyword::Var),
                 Token::Identifier(Arc::from("hola")),
@@ -440,17 +598,193 @@ mod lexer_test {
             ]
         )
     }
-}
 
-struct Identifier {
-    value: Option<String>,
-    type_id: IdentifierType,
-    keyword: Option<Keyword>,
-    return_type: Option<Types>,
-}
+    #[test]
+    fn span_test() {
+        let tokens = Lexer::with_file("var bé = 10", 2).lex().unwrap();
+        let spans: Vec<Span> = tokens.iter().map(|token| token.span).collect();
 
-enum IdentifierType {
-    Id,
-    Keyword,
-    Type,
+        assert_eq!(
+            spans,
+            vec![
+                Span::new(2, 0, 3),
+                Span::new(2, 4, 7),
+                Span::new(2, 8, 9),
+                Span::new(2, 10, 12),
+                Span::new(2, 12, 12),
+            ]
+        )
+    }
+
+    #[test]
+    fn optional_test() {
+        let tokens = Lexer::new("a ?? b?.c: Int32?").lex().unwrap();
+        let tokens: Vec<Token> = tokens.into_iter().map(|token| token.token).collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier(Arc::from("a")),
+                Token::Operator(Operator::Coalesce),
+                Token::Identifier(Arc::from("b")),
+                Token::QuestionDot,
+                Token::Identifier(Arc::from("c")),
+                Token::Separator(':'),
+                Token::Type(Types::Int32),
+                Token::Question,
+                Token::EOF
+            ]
+        )
+    }
+
+    #[test]
+    fn errors_test() {
+        let errors = Lexer::new("var a = 1.2.3\nvar b = 'hola\\q' ; 2\n\"hola")
+            .lex()
+            .unwrap_err();
+
+        let kinds: Vec<String> = errors
+            .iter()
+            .map(|err| format!("{:?}", err.kind()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "InvalidNumber(\"The guess number 1.2.3 is an invalid number\")",
+                "InvalidEscape('q')",
+                "UnexpectedCharacter(';')",
+                "UnfinalizedString",
+            ]
+        );
+        assert_eq!(errors[2].span(), Some(Span::new(0, 31, 32)));
+    }
+
+    #[test]
+    fn comments_test() {
+        let code = "var a = 1 # one\n#[ out #[ nested ]# ]# a\n#[ open";
+        let errors = Lexer::new(code).lex().unwrap_err();
+        assert!(matches!(errors[0].kind(), LexicError::UnfinalizedComment));
+
+        let code = "var a = 1 # one\n#[ out #[ nested ]# ]# a";
+        assert_eq!(
+            Lexer::new(code).lex().unwrap(),
+            vec![
+                Token::Keyword(Keyword::Var),
+                Token::Identifier(Arc::from("a")),
+                Token::Operator(Operator::Assign),
+                Token::Int32(1.into()),
+                Token::NewLine,
+                Token::Identifier(Arc::from("a")),
+                Token::EOF
+            ]
+        );
+
+        let tokens = Lexer::new(code).with_trivia().lex().unwrap();
+        assert_eq!(tokens[4], Token::Comment(Arc::from("# one")));
+        assert_eq!(
+            tokens[6],
+            Token::Comment(Arc::from("#[ out #[ nested ]# ]#"))
+        );
+    }
+
+    #[test]
+    fn range_test() {
+        assert_eq!(
+            Lexer::new("0..10 1..=2.5").lex().unwrap(),
+            vec![
+                Token::Int32(0.into()),
+                Token::Operator(Operator::Range),
+                Token::Int32(10.into()),
+                Token::Int32(1.into()),
+                Token::Operator(Operator::RangeInclusive),
+                Token::Double(Double::new(2.5)),
+                Token::EOF
+            ]
+        );
+    }
+
+    #[test]
+    fn match_test() {
+        assert_eq!(
+            Lexer::new("Some(_) => a").lex().unwrap(),
+            vec![
+                Token::Identifier("Some".into()),
+                Token::StartParenthesis,
+                Token::Identifier("_".into()),
+                Token::EndParenthesis,
+                Token::FatArrow,
+                Token::Identifier("a".into()),
+                Token::EOF
+            ]
+        );
+    }
+
+    #[test]
+    fn field_test() {
+        assert_eq!(
+            Lexer::new("line.start.x .5").lex().unwrap(),
+            vec![
+                Token::Identifier("line".into()),
+                Token::Separator('.'),
+                Token::Identifier("start".into()),
+                Token::Separator('.'),
+                Token::Identifier("x".into()),
+                Token::Double(Double::new(0.5)),
+                Token::EOF
+            ]
+        );
+    }
+
+    #[test]
+    fn variadic_test() {
+        assert_eq!(
+            Lexer::new("...rest 0..5").lex().unwrap(),
+            vec![
+                Token::Ellipsis,
+                Token::Identifier("rest".into()),
+                Token::Int32(0.into()),
+                Token::Operator(Operator::Range),
+                Token::Int32(5.into()),
+                Token::EOF
+            ]
+        );
+    }
+
+    #[test]
+    fn logical_test() {
+        assert_eq!(
+            Lexer::new("!a && b || c != d !== e").lex().unwrap(),
+            vec![
+                Token::Operator(Operator::Not),
+                Token::Identifier("a".into()),
+                Token::Operator(Operator::And),
+                Token::Identifier("b".into()),
+                Token::Operator(Operator::Or),
+                Token::Identifier("c".into()),
+                Token::Operator(Operator::NotEqual),
+                Token::Identifier("d".into()),
+                Token::Operator(Operator::StrictNotEqual),
+                Token::Identifier("e".into()),
+                Token::EOF
+            ]
+        );
+        assert_eq!(
+            Lexer::new("a & b |= c ^ ~d << e >>= f").lex().unwrap(),
+            vec![
+                Token::Identifier("a".into()),
+                Token::Operator(Operator::BitAnd),
+                Token::Identifier("b".into()),
+                Token::Operator(Operator::BitOrAssign),
+                Token::Identifier("c".into()),
+                Token::Operator(Operator::BitXor),
+                Token::Operator(Operator::BitNot),
+                Token::Identifier("d".into()),
+                Token::Operator(Operator::ShiftLeft),
+                Token::Identifier("e".into()),
+                Token::Operator(Operator::ShiftRightAssign),
+                Token::Identifier("f".into()),
+                Token::EOF
+            ]
+        );
+    }
 }
@@ -1,9 +1,15 @@
+pub mod ast;
+pub mod checker;
+pub mod diagnostics;
 pub mod elements;
 pub mod error;
+pub mod evaluator;
 pub mod function;
 pub mod function_table;
+pub mod iteration;
 pub mod lexer;
 pub mod parser;
+pub mod span;
 pub mod types;
 pub mod variable;
 pub mod variable_table;
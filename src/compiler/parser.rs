@@ -1,895 +1,1509 @@
-use crate::compiler::{
-    function::{Argument, Function},
-    types::Types,
-};
-use crate::std::debug::DEBUG_LIST;
+use std::sync::Arc;
+
+use crate::compiler::types::Types;
 
 use super::{
-    elements::{keyword::Keyword, operation::Operation, operator::Operator, token::Token},
-    error::parse_error::ParseError,
-    function_table::{Func, FunctionTable},
-    variable::Variable,
-    variable_table::VariableTable,
+    ast::{
+        Arg, EnumDecl, Expr, ExprKind, Field, FunctionDecl, Item, MatchArm, Param, Pattern, Stmt,
+        StmtKind, StructDecl, Variant, LAMBDA,
+    },
+    elements::{
+        keyword::Keyword,
+        operator::Operator,
+        token::{SpannedToken, Token},
+    },
+    error::{parse_error::ParseError, spanned::Located},
+    evaluator::{STACK_RED_ZONE, STACK_SEGMENT},
+    span::Span,
 };
-use std::{cell::RefCell, rc::Rc};
 
-/// This struct is in charge of manage the logic and semantic
-#[derive(Debug, Clone)]
+/// This struct is in charge of transform the tokens into a tree of [`Item`]s.
+///
+/// The parser doesn't execute anything, that is the work of the
+/// [`Evaluator`](super::evaluator::Evaluator).
+#[derive(Debug, Clone, PartialEq)]
 pub struct Parser {
     /// List of tokens to parse
-    tokens: Vec<Token>,
+    tokens: Vec<SpannedToken>,
     position: usize,
-    scope: usize,
-    variables: Rc<RefCell<VariableTable>>,
-    functions: Rc<RefCell<FunctionTable>>,
+    /// Span of the end of the file, used when the tokens run out
+    eof: Span,
+    /// Number of loops around the current position, to know if `break` is allowed
+    loop_depth: usize,
+    /// If the current position is in the body of a function, to know if `return` is allowed
+    in_function: bool,
+    /// The type parameters of the declaration around the current position
+    generics: Vec<Arc<str>>,
+    /// The enums declared in the program, to check the patterns of the matches
+    enums: Vec<EnumDecl>,
+    /// The patterns of each `match` and its place, checked when the whole program is parsed
+    matches: Vec<(Vec<Pattern>, Span)>,
+    /// Number of statements, expressions and types around the current position
+    nesting: usize,
 }
 
-#[allow(dead_code)]
+/// Maximum number of statements, expressions and types that can be inside each other, like
+/// the parentheses in `((1))`
+pub const MAX_NESTING: usize = 256;
+
 impl Parser {
     // MARK: Creation
-    pub fn new(
-        tokens: Vec<Token>,
-        variables: Option<Rc<RefCell<VariableTable>>>,
-        functions: Option<Rc<RefCell<FunctionTable>>>,
-    ) -> Self {
+    pub fn new(tokens: Vec<SpannedToken>) -> Self {
+        let eof = tokens.last().map(|token| token.span).unwrap_or_default();
+        let eof = Span::new(eof.file_id, eof.end, eof.end);
         // Using into_iter I can take the ownership and avoid clone
-        let tokens: Vec<Token> = tokens.into_iter().filter(|x| *x != Token::EOF).collect();
         Parser {
-            tokens: tokens.into_iter().filter(|x| *x != Token::EOF).collect(),
+            tokens: tokens
+                .into_iter()
+                .filter(|x| !matches!(x.token, Token::EOF | Token::Comment(_)))
+                .collect(),
             position: 0,
-            scope: 0,
-            variables: variables.unwrap_or(Rc::new(RefCell::new(VariableTable::new()))),
-            functions: functions.unwrap_or(Rc::new(RefCell::new(FunctionTable::new()))),
+            eof,
+            loop_depth: 0,
+            in_function: false,
+            generics: vec![],
+            enums: vec![],
+            matches: vec![],
+            nesting: 0,
         }
     }
 
-    fn internal_new(
-        tokens: Vec<Token>,
-        scope: usize,
-        variables: Rc<RefCell<VariableTable>>,
-        functions: Rc<RefCell<FunctionTable>>,
-    ) -> Self {
-        Parser {
-            tokens: tokens.into_iter().filter(|x| *x != Token::EOF).collect(),
-            position: 0,
-            scope,
-            variables,
-            functions,
+    // MARK: Parse
+    pub fn parse(&mut self) -> Result<Vec<Item>, ParseError> {
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_new_lines();
+            let Some(token) = self.peek() else {
+                break;
+            };
+
+            let item = match token {
+                // A `func` without name is a lambda used as a statement
+                Token::Keyword(Keyword::Function)
+                    if matches!(
+                        self.tokens.get(self.position + 1).map(|token| &token.token),
+                        Some(Token::Identifier(_))
+                    ) =>
+                {
+                    self.function_declaration().map(Item::Function)
+                }
+                Token::Keyword(Keyword::Struct) => self.struct_declaration().map(Item::Struct),
+                Token::Keyword(Keyword::Enum) => self.enum_declaration().map(|enumeration| {
+                    self.enums.push(enumeration.clone());
+                    Item::Enum(enumeration)
+                }),
+                _ => self.statement().map(Item::Stmt),
+            };
+            items.push(item.map_err(|error| error.at(self.current_span()))?);
         }
-    }
 
-    // MARK: Get
-    pub fn get_variables(&self) -> Vec<(String, Variable)> {
-        self.variables
-            .borrow()
-            .variables
-            .iter()
-            .map(|(key, var)| (key.clone(), var.clone()))
-            .collect()
+        // The enums can be declared after the matches that use them
+        for (patterns, span) in &self.matches {
+            self.check_match(patterns)
+                .map_err(|error| error.at(*span))?;
+        }
+
+        Ok(items)
     }
 
-    pub fn get_variable_table(&self) -> VariableTable {
-        (*self.variables.borrow()).clone()
+    // MARK: Statements
+    /// A statement. The nested blocks, expressions and types come back here, to
+    /// [`Parser::binary_expression`] or to [`Parser::parse_type`], so the three are
+    /// [`Parser::nested`].
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        self.nested(Self::statement_kind)
     }
 
-    pub fn get_function_table(&self) -> FunctionTable {
-        (*self.functions.borrow()).clone()
+    fn statement_kind(&mut self) -> Result<Stmt, ParseError> {
+        let stmt = match self.peek() {
+            Some(Token::Keyword(Keyword::Var | Keyword::Const)) => self.variable_declaration()?,
+            Some(Token::Keyword(Keyword::If)) => self.if_statement()?,
+            Some(Token::Keyword(Keyword::While)) => self.while_statement()?,
+            Some(Token::Keyword(Keyword::For)) => self.for_statement()?,
+            Some(Token::Keyword(Keyword::Return)) => self.return_statement()?,
+            Some(Token::Keyword(keyword @ (Keyword::Break | Keyword::Continue))) => {
+                let keyword = keyword.clone();
+                self.loop_control(keyword)?
+            }
+            Some(Token::StartBrace) if !self.is_map_literal() => {
+                let start = self.position;
+                let body = self.block()?;
+                Stmt::new(StmtKind::Block(body), self.span_from(start))
+            }
+            Some(Token::Identifier(_)) if self.is_assignment() => self.assignment()?,
+            _ => {
+                let expr = self.expression()?;
+                let span = expr.span;
+                Stmt::new(StmtKind::Expr(expr), span)
+            }
+        };
+
+        self.end_of_statement()?;
+        Ok(stmt)
     }
 
-    pub fn get_functions(&self) -> Vec<(String, Function)> {
-        let mut result = vec![];
+    /// `var name: Type = value` or `const name: Type = value`
+    fn variable_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.position;
+        let mutable = self.advance() == Some(Token::Keyword(Keyword::Var));
+        let name = self.consume_identifier()?;
+        let var_type = self.consume_type()?.unwrap_or(Types::Inferred);
 
-        for (key, variable) in self.functions.borrow().functions.iter() {
-            result.push((key.to_owned(), variable.to_owned()));
+        match self.peek() {
+            Some(Token::Operator(Operator::Assign)) => self.position += 1,
+            _ => {
+                return Err(ParseError::SyntaxError(format!(
+                    "Expected '=' after the declaration of '{name}'"
+                )))
+            }
         }
 
-        result
+        let value = self.expression()?;
+        Ok(Stmt::new(
+            StmtKind::VarDecl {
+                name,
+                var_type,
+                value,
+                mutable,
+            },
+            self.span_from(start),
+        ))
     }
 
-    // MARK: Parse
-    pub fn parse(&mut self) -> Result<Vec<Token>, ParseError> {
-        let mut results = Vec::new();
-
-        while self.position < self.tokens.len() {
-            let token = &self.tokens[self.position];
-            if *token == Token::EOF {
-                // Si se llega a EOF, salimos.
-                break;
-            }
+    /// `name = value`, `name += value`, `name[index] = value`, `name.field = value`, ...
+    fn assignment(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.position;
+        let target = self.factor()?;
+        let operator = match self.advance() {
+            Some(Token::Operator(op)) => op,
+            _ => unreachable!("is_assignment guarantees an operator"),
+        };
+        let value = self.expression()?;
+
+        let kind = match target.kind {
+            ExprKind::Variable(name) => StmtKind::Assign {
+                name,
+                operator,
+                value,
+            },
+            _ => StmtKind::AssignTo {
+                target,
+                operator,
+                value,
+            },
+        };
+        Ok(Stmt::new(kind, self.span_from(start)))
+    }
 
-            match token {
-                Token::Keyword(Keyword::Var) => {
-                    // Intentamos parsear una asignación
-                    if let Err(err) = self.assignment() {
-                        eprintln!("Parsing error: {:?}", err);
-                        self.recover_from_error(); // --- Recuperación de error
-                    }
-                }
-                Token::Keyword(Keyword::Function) => {
-                    // Intentamos parsear una función
-                    if let Err(err) = self.function_assignment() {
-                        eprintln!("Parsing error in function definition: {:?}", err);
-                        self.recover_from_error(); // --- Recuperación de error
-                    }
-                }
-                _ => {
-                    match self.resolve() {
-                        Ok(result) => {
-                            if result == Token::Void || result == Token::EOF {
-                                continue;
-                            }
-                            match result {
-                                Token::Operation(mut operation) => {
-                                    match operation.resolve() {
-                                        Ok(resolved) => results.push(resolved),
-                                        Err(err) => {
-                                            eprintln!("{:?}", err);
-                                            self.recover_from_error(); // --- Recuperación de error
-                                        }
-                                    }
-                                }
-                                Token::EOF | Token::Void => continue,
-                                _ => results.push(result),
-                            };
-                        }
-                        Err(err) => {
-                            eprintln!("Parsing error: {:?}", err);
-                            self.recover_from_error(); // --- Recuperación de error
-                        }
-                    }
+    /// `if condition { ... } else if condition { ... } else { ... }`
+    fn if_statement(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.position;
+        self.expect(Token::Keyword(Keyword::If))?;
+        let condition = self.expression()?;
+        let then_branch = self.block()?;
+
+        // The `else` can be in the same line of the `}` or in the next one
+        let before_else = self.position;
+        self.skip_new_lines();
+        let else_branch = match self.peek() {
+            Some(Token::Keyword(Keyword::Else)) => {
+                self.position += 1;
+                if let Some(Token::Keyword(Keyword::If)) = self.peek() {
+                    Some(vec![self.nested(Self::if_statement)?])
+                } else {
+                    Some(self.block()?)
                 }
             }
+            _ => {
+                self.position = before_else;
+                None
+            }
+        };
 
-            self.position += 1;
-        }
-
-        Ok(results)
+        Ok(Stmt::new(
+            StmtKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            },
+            self.span_from(start),
+        ))
     }
 
-    fn resolve(&mut self) -> Result<Token, ParseError> {
-        let mut node = self.term()?;
-
-        while self.position < self.tokens.len() {
-            match &self.tokens[self.position] {
-                // Manejo de operadores matemáticos simples
-                Token::Operator(op) if matches!(op, Operator::Add | Operator::Sub) => {
-                    let operator = op.clone();
-                    self.position += 1; // Consume el operador
-                    let right = self.term()?;
-                    node = Token::Operation(Operation::new(operator, node, right));
-                }
+    /// `while condition { ... }`
+    fn while_statement(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.position;
+        self.expect(Token::Keyword(Keyword::While))?;
+        let condition = self.expression()?;
+
+        self.loop_depth += 1;
+        let body = self.block();
+        self.loop_depth -= 1;
+
+        Ok(Stmt::new(
+            StmtKind::While {
+                condition,
+                body: body?,
+            },
+            self.span_from(start),
+        ))
+    }
 
-                // Manejo de operadores de asignación (+=, -=, *=, /=)
-                Token::Operator(op) if op.is_assignation() => {
-                    self.position += 1; // Consume el operador
+    /// `for name in iterable { ... }`
+    fn for_statement(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.position;
+        self.expect(Token::Keyword(Keyword::For))?;
+        let variable = self.consume_identifier()?;
+        self.expect(Token::Keyword(Keyword::In))?;
+        let iterable = self.expression()?;
+
+        self.loop_depth += 1;
+        let body = self.block();
+        self.loop_depth -= 1;
+
+        Ok(Stmt::new(
+            StmtKind::For {
+                variable,
+                iterable,
+                body: body?,
+            },
+            self.span_from(start),
+        ))
+    }
 
-                    // let right = self.term()?;
+    /// `break` or `continue`, only inside a loop
+    fn loop_control(&mut self, keyword: Keyword) -> Result<Stmt, ParseError> {
+        let start = self.position;
+        self.position += 1;
+        if self.loop_depth == 0 {
+            return Err(ParseError::SyntaxError(format!(
+                "'{}' can only be used inside a loop",
+                keyword.to_string().to_lowercase()
+            ))
+            .at(self.span_from(start)));
+        }
 
-                    // Aplicar la asignación a una variable existente
-                    if let Token::Identifier(identifier) = &node {
-                        let mut table = self.variables.borrow_mut();
-                        if let Ok(_) = table.get_mut(identifier) {
-                            node = Token::Identifier(identifier.clone()); // Devuelve la variable actualizada
-                        } else {
-                            return Err(ParseError::UndefinedVariable(identifier.to_string()));
-                        }
-                    } else {
-                        return Err(ParseError::SyntaxError(format!(
-                            "Assignment operator '{}' used incorrectly",
-                            node
-                        )));
-                    }
-                }
+        let kind = match keyword {
+            Keyword::Break => StmtKind::Break,
+            _ => StmtKind::Continue,
+        };
+        Ok(Stmt::new(kind, self.span_from(start)))
+    }
 
-                _ => break,
-            }
+    /// `return value` or `return`, only inside a function
+    fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.position;
+        self.expect(Token::Keyword(Keyword::Return))?;
+        if !self.in_function {
+            return Err(ParseError::SyntaxError(
+                "'return' can only be used inside a function".into(),
+            )
+            .at(self.span_from(start)));
         }
 
-        Ok(node)
+        let value = match self.peek() {
+            None | Some(Token::NewLine | Token::EndBrace) => None,
+            Some(_) => Some(self.expression()?),
+        };
+        Ok(Stmt::new(StmtKind::Return(value), self.span_from(start)))
     }
 
-    fn term(&mut self) -> Result<Token, ParseError> {
-        let mut node = self.factor()?;
+    /// `{ statements }`
+    fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        self.expect(Token::StartBrace)?;
+        let mut body = vec![];
 
-        while self.position < self.tokens.len() {
-            match &self.tokens[self.position] {
-                Token::Operator(op)
-                    if matches!(
-                        op,
-                        Operator::Mul
-                            | Operator::Div
-                            | Operator::Mod
-                            | Operator::DivInt
-                            | Operator::Pow
-                            | Operator::Greater
-                            | Operator::GreaterOrEqual
-                            | Operator::Lower
-                            | Operator::LowerOrEqual
-                            | Operator::Equal
-                            | Operator::StrictEqual
-                    ) =>
-                {
-                    let operator = op.clone();
+        loop {
+            self.skip_new_lines();
+            match self.peek() {
+                Some(Token::EndBrace) => {
                     self.position += 1;
-                    let right = self.factor().map_err(|_| {
-                        ParseError::SyntaxError("Expected an operand after the operator".into())
-                    })?;
-                    node = Token::Operation(Operation::new(operator, node, right));
+                    break;
                 }
-                _ => break,
+                Some(_) => body.push(self.statement()?),
+                None => return Err(ParseError::UndefinedEOF),
             }
         }
 
-        Ok(node)
+        Ok(body)
     }
 
-    fn factor(&mut self) -> Result<Token, ParseError> {
-        if self.position >= self.tokens.len() {
-            return Ok(Token::Void);
-        }
-
-        match &self.tokens[self.position] {
-            Token::Int32(n) => {
-                self.position += 1; // Consume the number
-                Ok(Token::Int32(*n))
+    /// A statement finishes at the end of the line, at the end of the block or at the end of the file
+    fn end_of_statement(&mut self) -> Result<(), ParseError> {
+        match self.peek() {
+            None | Some(Token::EndBrace) => Ok(()),
+            Some(Token::NewLine) => {
+                self.position += 1;
+                Ok(())
             }
+            Some(token) => Err(ParseError::UnexpectedToken {
+                span: self.current_span(),
+                token: token.clone(),
+            }),
+        }
+    }
 
-            Token::Double(n) => {
-                self.position += 1; // Consume the number
-                Ok(Token::Double(*n))
-            }
+    // MARK: Functions
+    /// `func name<T, ...>(arg: Type = default, ...) -> Type { body }`
+    fn function_declaration(&mut self) -> Result<FunctionDecl, ParseError> {
+        let start = self.position;
+        self.expect(Token::Keyword(Keyword::Function))?;
+        let name = self.consume_identifier()?;
+        let generics = self.type_parameters()?;
+        let (params, return_type, body) = self.with_generics(&generics, |parser| {
+            let (params, return_type) = parser.signature(Types::Void)?;
+            let body = parser.function_body(|parser| parser.block())?;
+            Ok((params, return_type, body))
+        })?;
+
+        Ok(FunctionDecl {
+            name,
+            generics,
+            params,
+            return_type,
+            body,
+            span: self.span_from(start),
+        })
+    }
 
-            Token::EOF => {
-                self.position += 1; // Consume the number
-                return self.factor();
-            }
+    /// `<T, U>` after the name of a declaration, they can be used as types inside it
+    fn type_parameters(&mut self) -> Result<Vec<Arc<str>>, ParseError> {
+        let mut generics: Vec<Arc<str>> = vec![];
+        if self.peek() != Some(&Token::Operator(Operator::Lower)) {
+            return Ok(generics);
+        }
+        self.position += 1;
 
-            Token::NewLine => {
-                self.position += 1; // Consume the new line
-                while let Some(Token::NewLine) = self.tokens.get(self.position) {
-                    self.position += 1;
+        loop {
+            if !generics.is_empty() {
+                match self.peek() {
+                    Some(Token::Separator(',')) => self.position += 1,
+                    _ => break,
                 }
-                return self.factor();
             }
-
-            Token::StartBrace => {
-                self.position += 1; // Consume the brace
-                self.scope += 1;
-                while let Some(Token::NewLine) = self.tokens.get(self.position) {
-                    self.position += 1;
-                }
-                return self.factor();
+            let generic = self
+                .consume_identifier()
+                .map_err(|_| ParseError::SyntaxError("Expected a type parameter".into()))?;
+            if generics.contains(&generic) {
+                return Err(ParseError::SyntaxError(format!(
+                    "The type parameter '{generic}' is declared twice"
+                )));
             }
+            generics.push(generic);
+        }
+        self.close_type()?;
+        Ok(generics)
+    }
 
-            Token::EndBrace => {
-                self.position += 1; // Consume the brace
-                self.scope -= 1;
-                self.variables.borrow_mut().pop_scope(self.scope + 1);
-                while let Some(Token::NewLine) = self.tokens.get(self.position) {
-                    self.position += 1;
-                }
-                return self.factor();
-            }
+    /// Parse the inside of a declaration with `inner`, where its type parameters can be used
+    fn with_generics<T>(
+        &mut self,
+        generics: &[Arc<str>],
+        inner: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let outer = self.generics.clone();
+        self.generics.extend_from_slice(generics);
+        let result = inner(self);
+        self.generics = outer;
+        result
+    }
 
-            Token::String(s) => {
-                self.position += 1; // Consume the string
-                Ok(Token::String(s.clone()))
-            }
+    /// `(arg: Type = default, ...) -> Type`, `return_type` is used if there isn't `->`
+    fn signature(&mut self, return_type: Types) -> Result<(Vec<Param>, Types), ParseError> {
+        self.expect(Token::StartParenthesis)
+            .map_err(|_| ParseError::SyntaxError("Bad Function Definition".into()))?;
 
-            Token::Str(s) => {
-                self.position += 1; // Consume the string
-                Ok(Token::Str(s.clone()))
+        let mut params = vec![];
+        loop {
+            self.skip_new_lines();
+            if let Some(Token::EndParenthesis) = self.peek() {
+                self.position += 1;
+                break;
             }
-
-            Token::Keyword(k) => {
-                self.position += 1; // Consume the keyword
-                match k {
-                    Keyword::Var => {
-                        self.assignment()?;
-                        Ok(Token::EOF)
-                    }
-                    Keyword::True => Ok(Token::Boolean(true)),
-                    Keyword::False => Ok(Token::Boolean(false)),
-                    _ => {
-                        let msg = format!(
-                            "Unexpected keyword at position {}: {}",
-                            self.position.clone(),
-                            k
-                        );
-                        Err(ParseError::SyntaxError(msg))
-                    }
-                }
+            if !params.is_empty() {
+                self.expect(Token::Separator(','))?;
+                self.skip_new_lines();
             }
-
-            Token::Identifier(var) => {
-                self.position += 1;
-                let var_name = var.to_string();
-                self.process_identifier(&var_name)
-            }
-
-            // for positive or negative numbers
-            Token::Operator(op) if *op == Operator::Add || *op == Operator::Sub => {
-                let operator = op.clone();
-                self.position += 1; // Consume the unary operator
-                let operand = self.factor()?;
-                // Handle unary operations if necessary
-                Ok(Token::Operation(Operation::new(
-                    operator,
-                    Token::Void,
-                    operand,
-                )))
+            if let Some(Param {
+                name,
+                variadic: true,
+                ..
+            }) = params.last()
+            {
+                return Err(ParseError::SyntaxError(format!(
+                    "The variadic argument '{name}' must be the last one"
+                )));
             }
+            params.push(self.parameter()?);
+        }
 
-            Token::Type(types) => {
-                self.position += 1;
-                Ok(Token::Type(types.clone()))
-            }
+        let return_type = if let Some(Token::Arrow) = self.peek() {
+            self.position += 1;
+            self.parse_type("->")?
+        } else {
+            return_type
+        };
 
-            v => {
-                let msg = format!("Unexpected token in position {}: {:?}", self.position, v);
-                return Err(ParseError::SyntaxError(msg));
-            }
+        if self.peek() != Some(&Token::StartBrace) {
+            return Err(ParseError::SyntaxError(
+                "Expected '{' after function arguments".into(),
+            ));
         }
+        Ok((params, return_type))
     }
 
-    // MARK: Errors
-    fn recover_from_error(&mut self) {
-        let sync_tokens = [
-            Token::NewLine,
-            // Token::Separator(';'),
-            Token::StartBrace,
-            Token::EndBrace,
-        ];
+    /// Parse the body of a function with `body`, where `return` is allowed
+    fn function_body(
+        &mut self,
+        body: impl FnOnce(&mut Self) -> Result<Vec<Stmt>, ParseError>,
+    ) -> Result<Vec<Stmt>, ParseError> {
+        // The loops around the declaration don't continue in the body
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        let body = body(self);
+        self.loop_depth = loop_depth;
+        self.in_function = in_function;
+        body
+    }
+
+    /// `func(arg: Type, ...) -> Type { body }`, a function without name. Without `->` it can
+    /// return any value.
+    fn lambda(&mut self, start: usize) -> Result<ExprKind, ParseError> {
+        let (params, return_type) = self.signature(Types::Inferred)?;
+        let mut body = self.function_body(|parser| parser.block())?;
+        Self::return_last(&mut body);
+
+        Ok(ExprKind::Lambda(Box::new(FunctionDecl {
+            name: LAMBDA.into(),
+            generics: vec![],
+            params,
+            return_type,
+            body,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// `|arg, arg: Type| expression` or `|arg| { body }`, the `|` that opens it is already
+    /// consumed. The arguments can omit the type, `||` is a lambda without arguments.
+    fn pipe_lambda(&mut self, start: usize, with_args: bool) -> Result<ExprKind, ParseError> {
+        let params = if with_args {
+            self.pipe_params()?
+        } else {
+            vec![]
+        };
+
+        let body = self.function_body(|parser| {
+            if parser.peek() == Some(&Token::StartBrace) && !parser.is_map_literal() {
+                let mut body = parser.block()?;
+                Self::return_last(&mut body);
+                return Ok(body);
+            }
+            let value = parser.expression()?;
+            let span = value.span;
+            Ok(vec![Stmt::new(StmtKind::Return(Some(value)), span)])
+        })?;
+
+        Ok(ExprKind::Lambda(Box::new(FunctionDecl {
+            name: LAMBDA.into(),
+            generics: vec![],
+            params,
+            return_type: Types::Inferred,
+            body,
+            span: self.span_from(start),
+        })))
+    }
 
-        while self.position < self.tokens.len() {
-            if sync_tokens.contains(&self.tokens[self.position]) {
+    /// `arg, arg: Type|`, the arguments of a lambda until the `|` that closes them
+    fn pipe_params(&mut self) -> Result<Vec<Param>, ParseError> {
+        let mut params = vec![];
+        loop {
+            if let Some(Token::Operator(Operator::BitOr)) = self.peek() {
                 self.position += 1;
                 break;
             }
-            self.position += 1;
+            if !params.is_empty() {
+                self.expect(Token::Separator(','))?;
+            }
+            let param_start = self.position;
+            let name = self
+                .consume_identifier()
+                .map_err(|_| ParseError::SyntaxError("Invalid argument name".into()))?;
+            let var_type = self.consume_type()?.unwrap_or(Types::Inferred);
+            params.push(Param {
+                name,
+                var_type,
+                default: None,
+                variadic: false,
+                span: self.span_from(param_start),
+            });
         }
-
-        eprintln!("Error recovered at position: {}", self.position);
+        Ok(params)
     }
 
-    // MARK: Assign
-    fn assignment(&mut self) -> Result<(), ParseError> {
-        let new_var = self.consume_keyword_var();
-        let identifier = self.consume_identifier(new_var)?;
-
-        let var_type = self.consume_type().unwrap_or(Types::Inferred);
+    /// The last expression of the body of a lambda is the value that it returns
+    fn return_last(body: &mut [Stmt]) {
+        if let Some(last) = body.last_mut() {
+            if let StmtKind::Expr(value) = &last.kind {
+                last.kind = StmtKind::Return(Some(value.clone()));
+            }
+        }
+    }
 
-        let operator = match self.consume_assignment_operator() {
-            Some(op) => op,
-            None => return Ok(()), // Si no hay operador, la asignación es inválida.
+    /// `name: Type = default` or `...name: Type`, whose value is a `List<Type>` with the rest
+    /// of the passed values. The type can be omitted, like in `func twice(x) { ... }`.
+    fn parameter(&mut self) -> Result<Param, ParseError> {
+        let start = self.position;
+        let variadic = self.peek() == Some(&Token::Ellipsis);
+        if variadic {
+            self.position += 1;
+        }
+        let name = match self.advance() {
+            Some(Token::Identifier(name)) => name,
+            _ => return Err(ParseError::SyntaxError("Invalid argument name".into())),
         };
-        let value_token = self.resolve()?;
-        let inferred_type = Types::inferred(&value_token)?;
-
-        if operator.is_assignation() {
-            self.validate_existing_identifier()?;
-            let mut table = self.variables.borrow_mut();
-            let var = table.get_mut(&identifier)?;
-
-            let existing_value = *var.value.clone();
-            let new_value = match value_token.clone() {
-                Token::Operation(mut expr) => expr.resolve()?,
-                value => operator.execute(existing_value, value)?,
-            };
-
-            let inferred_type = Types::inferred(&new_value)?;
+        // Without type it's inferred from the passed values
+        let mut var_type = self.consume_type()?.unwrap_or(Types::Inferred);
+        if variadic {
+            var_type = Types::List(Box::new(var_type));
+        }
 
-            if inferred_type == var.var_type {
-                var.value = Box::new(new_value);
-            } else {
-                return Err(ParseError::TypeError(format!(
-                    "Cannot assign type '{}' to variable '{}'",
-                    inferred_type, identifier
+        let default = if let Some(Token::Operator(Operator::Assign)) = self.peek() {
+            if variadic {
+                return Err(ParseError::SyntaxError(format!(
+                    "The variadic argument '{name}' can't have a default value"
                 )));
             }
-            let mut var = var.clone();
-            table.update(identifier.as_ref(), &mut var)?;
-        }
+            self.position += 1;
+            Some(self.expression()?)
+        } else {
+            None
+        };
 
-        if operator.is_assignation() {
-            self.validate_existing_identifier()?;
-            let mut table = self.variables.borrow_mut();
-            let var = table.get_mut(&identifier)?;
+        Ok(Param {
+            name,
+            var_type,
+            default,
+            variadic,
+            span: self.span_from(start),
+        })
+    }
 
-            let existing_value = *var.value.clone();
-            let new_value = match value_token.clone() {
-                Token::Operation(mut expr) => expr.resolve()?,
-                value => operator.execute(existing_value, value)?,
-            };
+    // MARK: Structs
+    /// `struct Name<T, ...> { field: Type, ... }`, the fields are separated by commas or new
+    /// lines
+    fn struct_declaration(&mut self) -> Result<StructDecl, ParseError> {
+        let start = self.position;
+        self.expect(Token::Keyword(Keyword::Struct))?;
+        let name = self.consume_identifier()?;
+        let generics = self.type_parameters()?;
+        let fields = self.with_generics(&generics, |parser| parser.struct_members(&name))?;
+
+        Ok(StructDecl {
+            name,
+            generics,
+            fields,
+            span: self.span_from(start),
+        })
+    }
 
-            let inferred_type = Types::inferred(&new_value)?;
+    /// `{ field: Type, ... }` of the struct `name`
+    fn struct_members(&mut self, name: &str) -> Result<Vec<Field>, ParseError> {
+        self.expect(Token::StartBrace)?;
 
-            if inferred_type == var.var_type {
-                var.value = Box::new(new_value);
-            } else {
-                return Err(ParseError::TypeError(format!(
-                    "Cannot assign type '{}' to variable '{}'",
-                    inferred_type, identifier
-                )));
+        let mut fields: Vec<Field> = vec![];
+        loop {
+            self.skip_new_lines();
+            if let Some(Token::EndBrace) = self.peek() {
+                self.position += 1;
+                break;
             }
-            let mut var = var.clone();
-            table.update(identifier.as_ref(), &mut var)?;
+
+            let field_start = self.position;
+            let field = self.consume_identifier()?;
+            if fields.iter().any(|other| other.name == field) {
+                return Err(ParseError::SyntaxError(format!(
+                    "The field '{field}' is declared twice in '{name}'"
+                ))
+                .at(self.span_from(field_start)));
+            }
+            let var_type = self.consume_type()?.ok_or_else(|| {
+                ParseError::SyntaxError(format!("The field '{field}' needs a type"))
+            })?;
+            fields.push(Field {
+                name: field,
+                var_type,
+                span: self.span_from(field_start),
+            });
+            self.member_separator()?;
         }
 
-        if operator.is_assignation() {
-            self.handle_variable_reassignment(identifier, value_token, operator, new_var)
-        } else {
-            self.handle_variable_declaration(identifier, value_token, inferred_type, var_type)
+        if fields.is_empty() {
+            return Err(ParseError::SyntaxError(format!(
+                "The struct '{name}' needs at least one field"
+            )));
         }
+        Ok(fields)
     }
 
-    fn handle_variable_reassignment(
-        &mut self,
-        identifier: String,
-        value_token: Token,
-        operator: Operator,
-        new_var: bool,
-    ) -> Result<(), ParseError> {
-        let mut table = self.variables.borrow_mut();
-        if let Ok(var) = table.get_mut(&identifier) {
-            let new_value = match value_token {
-                Token::Operation(mut expr) => expr.resolve()?,
-                value => operator.execute(*var.value.clone(), value)?,
-            };
-
-            let inferred_type = Types::inferred(&new_value)?;
-            let mut var = var.clone();
-            if inferred_type == var.var_type {
-                var.value = Box::new(new_value);
-            } else {
-                var.var_type = inferred_type;
-                var.value = Box::new(new_value);
+    /// The `,` or the new line after a member of a declaration, the last one can be followed
+    /// directly by the `}`
+    fn member_separator(&mut self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(Token::Separator(',') | Token::NewLine) => {
+                self.position += 1;
+                Ok(())
             }
-
-            table.update(identifier.as_ref(), &mut var)?;
-            Ok(())
-        } else if new_var {
-            Err(ParseError::UndefinedVariable(format!(
-                "Variable {} not defined",
-                identifier
-            )))
-        } else {
-            Err(ParseError::UndefinedVariable(format!(
-                "Variable {} not defined",
-                identifier
-            )))
+            Some(Token::EndBrace) => Ok(()),
+            Some(token) => Err(ParseError::UnexpectedToken {
+                span: self.current_span(),
+                token: token.clone(),
+            }),
+            None => Err(ParseError::UndefinedEOF),
         }
     }
 
-    fn handle_variable_declaration(
-        &mut self,
-        identifier: String,
-        value_token: Token,
-        inferred_type: Types,
-        mut var_type: Types,
-    ) -> Result<(), ParseError> {
-        let mut table = self.variables.borrow_mut();
-
-        let new_value = if inferred_type != var_type {
-            if inferred_type.is_integer() && var_type.is_integer() {
-                Types::transform(value_token, var_type)?.0
-            } else if inferred_type.is_float() && var_type.is_float() {
-                Types::transform(value_token, var_type)?.0
-            } else if var_type == Types::Inferred {
-                var_type = inferred_type;
-                value_token
-            } else {
-                return Err(ParseError::TypeError(format!(
-                    "The type of '{}' must be <{var_type}> but it's <{}>.",
-                    identifier,
-                    Types::from(value_token)
-                )));
-            }
-        } else {
-            value_token
-        };
-
-        let variable = Variable::new(identifier.clone(), var_type, new_value, self.scope);
-        table.insert(identifier.as_ref(), variable)?;
-
-        Ok(())
+    /// `field: value, ...` until the `}` of a struct construction
+    fn struct_fields(&mut self) -> Result<Vec<(Arc<str>, Expr)>, ParseError> {
+        self.map_entries()?
+            .into_iter()
+            .map(|(key, value)| match key.kind {
+                ExprKind::Variable(name) => Ok((name, value)),
+                _ => {
+                    Err(ParseError::SyntaxError("Expected the name of a field".into()).at(key.span))
+                }
+            })
+            .collect()
     }
 
-    // MARK: Consume
-    fn consume_keyword_var(&mut self) -> bool {
-        if let Token::Keyword(Keyword::Var) = self.tokens[self.position] {
-            self.position += 1;
-            true
-        } else {
-            false
-        }
+    // MARK: Enums
+    /// `enum Name<T, ...> { Variant, Variant(Type, ...), ... }`, the variants are separated by
+    /// commas or new lines
+    fn enum_declaration(&mut self) -> Result<EnumDecl, ParseError> {
+        let start = self.position;
+        self.expect(Token::Keyword(Keyword::Enum))?;
+        let name = self.consume_identifier()?;
+        let generics = self.type_parameters()?;
+        let variants = self.with_generics(&generics, |parser| parser.enum_variants(&name))?;
+
+        Ok(EnumDecl {
+            name,
+            generics,
+            variants,
+            span: self.span_from(start),
+        })
     }
 
-    fn consume_identifier(&mut self, new_var: bool) -> Result<String, ParseError> {
-        match self.tokens.get(self.position) {
-            Some(Token::Identifier(name)) => {
+    /// `{ Variant, Variant(Type, ...), ... }` of the enum `name`
+    fn enum_variants(&mut self, name: &str) -> Result<Vec<Variant>, ParseError> {
+        self.expect(Token::StartBrace)?;
+
+        let mut variants: Vec<Variant> = vec![];
+        loop {
+            self.skip_new_lines();
+            if let Some(Token::EndBrace) = self.peek() {
                 self.position += 1;
-                Ok(name.to_string())
+                break;
             }
-            _ => {
-                if new_var {
-                    Err(ParseError::SyntaxError(
-                        "Unexpected an identifier before assignment operator".into(),
-                    ))
-                } else {
-                    Ok("".into())
+
+            let variant_start = self.position;
+            let variant = self.consume_identifier()?;
+            if variants.iter().any(|other| other.name == variant) {
+                return Err(ParseError::SyntaxError(format!(
+                    "The variant '{variant}' is declared twice in '{name}'"
+                ))
+                .at(self.span_from(variant_start)));
+            }
+
+            let mut fields = vec![];
+            if let Some(Token::StartParenthesis) = self.peek() {
+                self.position += 1;
+                loop {
+                    self.skip_new_lines();
+                    if let Some(Token::EndParenthesis) = self.peek() {
+                        self.position += 1;
+                        break;
+                    }
+                    if !fields.is_empty() {
+                        self.expect(Token::Separator(','))?;
+                        self.skip_new_lines();
+                    }
+                    fields.push(self.parse_type("(")?);
                 }
             }
+            variants.push(Variant {
+                name: variant,
+                fields,
+                span: self.span_from(variant_start),
+            });
+            self.member_separator()?;
+        }
+
+        if variants.is_empty() {
+            return Err(ParseError::SyntaxError(format!(
+                "The enum '{name}' needs at least one variant"
+            )));
         }
+        Ok(variants)
     }
 
-    fn consume_type(&mut self) -> Option<Types> {
-        if let Some(Token::Separator(':')) = self.tokens.get(self.position) {
-            self.position += 1;
-            if let Some(Token::Type(tipo)) = self.tokens.get(self.position) {
+    /// `match value { pattern => body, ... }` after the `match`, the arms are separated by
+    /// commas or new lines
+    fn match_expression(&mut self, start: usize) -> Result<ExprKind, ParseError> {
+        let value = self.expression()?;
+        self.expect(Token::StartBrace)?;
+
+        let mut arms = vec![];
+        loop {
+            self.skip_new_lines();
+            if let Some(Token::EndBrace) = self.peek() {
                 self.position += 1;
-                return Some(tipo.clone());
+                break;
             }
+
+            let arm_start = self.position;
+            let pattern = self.pattern()?;
+            self.expect(Token::FatArrow)?;
+            self.skip_new_lines();
+            let body = if self.peek() == Some(&Token::StartBrace) && !self.is_map_literal() {
+                self.block()?
+            } else {
+                let expr = self.expression()?;
+                let span = expr.span;
+                vec![Stmt::new(StmtKind::Expr(expr), span)]
+            };
+            arms.push(MatchArm {
+                pattern,
+                body,
+                span: self.span_from(arm_start),
+            });
+            self.member_separator()?;
+        }
+
+        if arms.is_empty() {
+            return Err(ParseError::SyntaxError(
+                "A match needs at least one arm".into(),
+            ));
         }
-        None
+
+        let patterns = arms.iter().map(|arm| arm.pattern.clone()).collect();
+        self.matches.push((patterns, self.span_from(start)));
+        Ok(ExprKind::Match {
+            value: Box::new(value),
+            arms,
+        })
     }
 
-    fn consume_assignment_operator(&mut self) -> Option<Operator> {
-        match self.tokens.get(self.position) {
-            Some(Token::Operator(op)) => {
-                self.position += 1;
-                Some(op.clone())
+    /// `_`, a literal, a name or a variant with the patterns of its payload like `Rect(w, _)`
+    fn pattern(&mut self) -> Result<Pattern, ParseError> {
+        let start = self.position;
+        let Some(token) = self.advance() else {
+            return Err(ParseError::UndefinedEOF);
+        };
+
+        match token {
+            Token::Identifier(name) if &*name == "_" => Ok(Pattern::Wildcard),
+            Token::Identifier(name) => {
+                if let Some(Token::StartParenthesis) = self.peek() {
+                    self.position += 1;
+                    let mut args = vec![];
+                    loop {
+                        self.skip_new_lines();
+                        if let Some(Token::EndParenthesis) = self.peek() {
+                            self.position += 1;
+                            break;
+                        }
+                        if !args.is_empty() {
+                            self.expect(Token::Separator(','))?;
+                            self.skip_new_lines();
+                        }
+                        args.push(self.pattern()?);
+                    }
+                    Ok(Pattern::Variant { name, args })
+                } else {
+                    Ok(Pattern::Identifier(name))
+                }
             }
-            _ => None,
+            Token::Int32(_)
+            | Token::Int64(_)
+            | Token::HPInt(_)
+            | Token::Float(_)
+            | Token::Double(_)
+            | Token::String(_)
+            | Token::Str(_) => Ok(Pattern::Literal(token)),
+            Token::Keyword(Keyword::True) => Ok(Pattern::Literal(Token::Boolean(true))),
+            Token::Keyword(Keyword::False) => Ok(Pattern::Literal(Token::Boolean(false))),
+            Token::Keyword(Keyword::None) => Ok(Pattern::Literal(Token::None)),
+            // A negative number
+            Token::Operator(Operator::Sub) => match self.advance() {
+                Some(number) if number.is_numeric() => {
+                    Ok(Pattern::Literal(Operator::Sub.execute_unary(number)?))
+                }
+                _ => Err(
+                    ParseError::SyntaxError("Expected a number after '-'".into())
+                        .at(self.span_from(start)),
+                ),
+            },
+            token => Err(ParseError::UnexpectedToken {
+                span: self.tokens[start].span,
+                token,
+            }),
         }
     }
 
-    // MARK: Auxiliary
-    fn validate_existing_identifier(&mut self) -> Result<String, ParseError> {
-        match self.tokens.get(self.position - 2) {
-            Some(Token::Identifier(id)) => Ok(id.to_string()),
-            _ => Err(ParseError::SyntaxError(
-                "Expected an identifier before assignment operator".into(),
-            )),
+    /// Check that the variants in the patterns exist and that every possible value fits in
+    /// one of the patterns
+    fn check_match(&self, patterns: &[Pattern]) -> Result<(), ParseError> {
+        for pattern in patterns {
+            self.check_pattern(pattern)?;
+        }
+        if patterns.iter().any(|pattern| self.is_irrefutable(pattern)) {
+            return Ok(());
         }
-    }
 
-    // MARK: Functions
-    fn function_assignment(&mut self) -> Result<(), ParseError> {
-        self.position += 1;
-        // Esperamos un identificador
-        let identifier = match self.tokens.get(self.position) {
-            Some(Token::Identifier(name)) => {
-                self.position += 1; // Consume el identificador
-                name.to_owned()
-            }
-            _ => {
-                // Si no hay identificador, no podemos seguir
+        let enumeration = patterns.iter().find_map(|pattern| match pattern {
+            Pattern::Variant { name, .. } | Pattern::Identifier(name) => self.enum_of(name),
+            _ => None,
+        });
+        let Some(enumeration) = enumeration else {
+            let booleans = [true, false]
+                .map(|value| patterns.contains(&Pattern::Literal(Token::Boolean(value))));
+            if booleans == [true, true] {
                 return Ok(());
             }
+            return Err(ParseError::NonExhaustiveMatch(
+                "every value, it needs a '_' arm".into(),
+            ));
         };
 
-        match self.tokens.get(self.position) {
-            Some(Token::StartParenthesis) => {
-                self.position += 1; // Consume el identificador
+        // A variant is covered when the patterns of its payload fit any value
+        let mut covered = vec![];
+        for pattern in patterns {
+            let (name, args) = match pattern {
+                Pattern::Variant { name, args } => (name, args.as_slice()),
+                Pattern::Identifier(name) => (name, [].as_slice()),
+                _ => continue,
+            };
+            if enumeration.variant(name).is_none() {
+                return Err(ParseError::TypeError(format!(
+                    "The pattern '{name}' isn't a variant of '{}'.",
+                    enumeration.name
+                )));
             }
-            _ => {
-                // Si no hay identificador, no podemos seguir
-                return Err(ParseError::SyntaxError("Bad Function Definition".into()));
+            if args.iter().all(|arg| self.is_irrefutable(arg)) {
+                covered.push(name.clone());
             }
-        };
+        }
 
-        let mut arguments = vec![];
-        while let Some(tok) = self.tokens.get(self.position) {
-            if *tok == Token::EndParenthesis {
-                self.position += 1; // Avanza más allá del `EndParenthesis`
-                break;
-            }
-            arguments.push(tok.clone());
-            self.position += 1;
+        let missing: Vec<String> = enumeration
+            .variants
+            .iter()
+            .filter(|variant| !covered.contains(&variant.name))
+            .map(|variant| format!("'{}'", variant.name))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ParseError::NonExhaustiveMatch(format!(
+                "the variants {} of '{}'",
+                missing.join(", "),
+                enumeration.name
+            )))
         }
+    }
 
-        // Verifica que `StartBrace` esté después de los argumentos
-        if self.tokens.get(self.position) != Some(&Token::StartBrace) {
-            return Err(ParseError::SyntaxError(
-                "Expected '{' after function arguments".into(),
-            ));
+    /// The variants must exist and the patterns must have the size of their payload
+    fn check_pattern(&self, pattern: &Pattern) -> Result<(), ParseError> {
+        let (name, args) = match pattern {
+            Pattern::Variant { name, args } => (name, args.as_slice()),
+            Pattern::Identifier(name) if self.enum_of(name).is_some() => (name, [].as_slice()),
+            _ => return Ok(()),
+        };
+        let Some(variant) = self
+            .enum_of(name)
+            .and_then(|enumeration| enumeration.variant(name))
+        else {
+            return Err(ParseError::UndefinedType(format!(
+                "The variant '{name}' doesn't exist."
+            )));
+        };
+        if variant.fields.len() != args.len() {
+            return Err(ParseError::SyntaxError(format!(
+                "The variant '{name}' has {} values but the pattern has {}",
+                variant.fields.len(),
+                args.len()
+            )));
         }
-        self.position += 1; // Consume el `StartBrace`
+        args.iter().try_for_each(|arg| self.check_pattern(arg))
+    }
 
-        let mut content = vec![];
-        while let Some(tok) = self.tokens.get(self.position) {
-            if *tok == Token::EndBrace {
-                self.position += 1; // Consume el `EndBrace`
+    /// A pattern that fits any value, `_` or a name that isn't a variant
+    fn is_irrefutable(&self, pattern: &Pattern) -> bool {
+        match pattern {
+            Pattern::Wildcard => true,
+            Pattern::Identifier(name) => self.enum_of(name).is_none(),
+            _ => false,
+        }
+    }
+
+    /// The enum with a variant called `variant`
+    fn enum_of(&self, variant: &str) -> Option<&EnumDecl> {
+        self.enums
+            .iter()
+            .find(|enumeration| enumeration.variant(variant).is_some())
+    }
+
+    // MARK: Expressions
+    /// Any expression, including the ranges `start..end step n` and `start..=end step n`
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        let start = self.binary_expression(0)?;
+
+        let inclusive = match self.peek() {
+            Some(Token::Operator(Operator::Range)) => false,
+            Some(Token::Operator(Operator::RangeInclusive)) => true,
+            _ => return Ok(start),
+        };
+        self.position += 1;
+        let end = self.binary_expression(0)?;
+
+        let step = if let Some(Token::Keyword(Keyword::Step)) = self.peek() {
+            self.position += 1;
+            Some(Box::new(self.binary_expression(0)?))
+        } else {
+            None
+        };
+
+        let span = start
+            .span
+            .to(step.as_ref().map_or(end.span, |step| step.span));
+        Ok(Expr::new(
+            ExprKind::Range {
+                start: Box::new(start),
+                end: Box::new(end),
+                step,
+                inclusive,
+            },
+            span,
+        ))
+    }
+
+    /// Binary operations with precedence climbing, only the operators that bind at least as
+    /// tight as `min_power` are consumed
+    fn binary_expression(&mut self, min_power: u8) -> Result<Expr, ParseError> {
+        self.nested(|parser| parser.binary_operations(min_power))
+    }
+
+    fn binary_operations(&mut self, min_power: u8) -> Result<Expr, ParseError> {
+        let mut node = self.factor()?;
+
+        while let Some(Token::Operator(op)) = self.peek() {
+            let Some((left_power, right_power)) = op.binding_power() else {
+                break;
+            };
+            if left_power < min_power {
                 break;
             }
-            content.push(tok.clone());
+            let operator = op.clone();
             self.position += 1;
+            // Only a missing operand is reported here, the errors inside it keep their own place
+            if matches!(
+                self.peek(),
+                None | Some(
+                    Token::NewLine
+                        | Token::EOF
+                        | Token::EndParenthesis
+                        | Token::EndBracket
+                        | Token::EndBrace
+                        | Token::Separator(_)
+                )
+            ) {
+                let error =
+                    ParseError::SyntaxError("Expected an operand after the operator".into());
+                return Err(error.at(self.span_from(self.position - 1)));
+            }
+            let right = self.binary_expression(right_power)?;
+            node = Self::binary(operator, node, right);
         }
 
-        let mut arg_array: Vec<Argument> = vec![];
-        for x in arguments
-            .split(|x| *x == Token::Separator(','))
-            .map(|x| x.to_owned())
-            .collect::<Vec<Vec<Token>>>()
-            .iter()
-        {
-            if let [name_slice, var_type_slice] = x
-                .split(|x| *x == Token::Separator(':'))
-                .collect::<Vec<&[Token]>>()
-                .as_slice()
-            {
-                // Procesa los elementos de forma segura
-                let name = name_slice.first().ok_or_else(|| {
-                    ParseError::SyntaxError("Argument name missing in function definition".into())
-                })?;
+        Ok(node)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        let start = self.position;
+        let Some(token) = self.advance() else {
+            return Err(ParseError::UndefinedEOF);
+        };
 
-                if let Token::Identifier(_) = name {
+        let kind = match token {
+            Token::Int32(_)
+            | Token::Int64(_)
+            | Token::HPInt(_)
+            | Token::Float(_)
+            | Token::Double(_)
+            | Token::String(_)
+            | Token::Str(_)
+            | Token::Type(_) => ExprKind::Literal(token),
+
+            Token::Keyword(Keyword::True) => ExprKind::Literal(Token::Boolean(true)),
+            Token::Keyword(Keyword::False) => ExprKind::Literal(Token::Boolean(false)),
+            Token::Keyword(Keyword::None) => ExprKind::Literal(Token::None),
+
+            Token::Keyword(Keyword::Match) => self.match_expression(start)?,
+
+            Token::Keyword(Keyword::Function) => self.lambda(start)?,
+
+            Token::Operator(operator @ (Operator::BitOr | Operator::Or)) => {
+                self.pipe_lambda(start, operator == Operator::BitOr)?
+            }
+
+            Token::Identifier(name) => {
+                if let Some(Token::StartParenthesis) = self.peek() {
+                    self.position += 1;
+                    ExprKind::Call {
+                        callee: name,
+                        args: self.call_arguments()?,
+                    }
+                } else if self.is_struct_literal() {
+                    self.position += 1;
+                    ExprKind::Struct {
+                        name,
+                        fields: self.struct_fields()?,
+                    }
                 } else {
-                    return Err(ParseError::SyntaxError("Invalid argument name".into()));
-                };
-
-                if var_type_slice.len() > 1 {
-                    if var_type_slice
-                        .iter()
-                        .find(|x| **x == Token::Operator(Operator::Assign))
-                        .is_some()
-                    {
-                        let var_type = var_type_slice.first().ok_or_else(|| {
-                            ParseError::SyntaxError(
-                                "Argument type missing in function definition".into(),
-                            )
-                        })?;
-
-                        let operator = if let Some(Token::Operator(op)) = var_type_slice.get(1) {
-                            op.clone()
-                        } else {
-                            Operator::Null
-                        };
+                    ExprKind::Variable(name)
+                }
+            }
 
-                        let value = if let Some(token) = var_type_slice.get(2) {
-                            token.clone()
-                        } else {
-                            Token::Void
-                        };
+            // for positive or negative numbers, and the negations
+            Token::Operator(
+                op @ (Operator::Add | Operator::Sub | Operator::Not | Operator::BitNot),
+            ) => ExprKind::Unary {
+                operator: op,
+                operand: Box::new(self.binary_expression(Operator::PREFIX_BINDING_POWER)?),
+            },
 
-                        if value != Token::Void && operator != Operator::Assign {
-                            return Err(ParseError::SyntaxError(
-                                "Argument assignation operator missing in function definition"
-                                    .into(),
-                            ));
-                        }
+            Token::StartBracket => ExprKind::List(self.expression_list(Token::EndBracket)?),
 
-                        let name = if let Token::Identifier(id) = name {
-                            id
-                        } else {
-                            return Err(ParseError::SyntaxError(
-                                "Invalid Name of atribbute".to_string(),
-                            ));
-                        };
+            Token::StartBrace => ExprKind::Map(self.map_entries()?),
 
-                        let var_type = if let Token::Type(my_type) = var_type {
-                            my_type.clone()
-                        } else {
-                            return Err(ParseError::SyntaxError(
-                                "Invalid Type of atribbute".to_string(),
-                            ));
-                        };
+            Token::StartParenthesis => {
+                self.skip_new_lines();
+                let expr = self.expression()?;
+                self.skip_new_lines();
+                self.expect(Token::EndParenthesis)?;
+                expr.kind
+            }
+
+            Token::Keyword(k) => {
+                let msg = format!("Unexpected keyword: {}", k);
+                return Err(ParseError::SyntaxError(msg).at(self.tokens[start].span));
+            }
+
+            token => {
+                return Err(ParseError::UnexpectedToken {
+                    span: self.tokens[start].span,
+                    token,
+                })
+            }
+        };
 
-                        arg_array.push(Argument::new(
-                            name.clone(),
-                            var_type,
-                            Some(Box::new(value)),
-                            None,
-                        ));
+        let mut expr = Expr::new(kind, self.span_from(start));
+        loop {
+            let kind = match self.peek() {
+                // The value of the expression is the function to call, like `adders[0](1)`
+                Some(Token::StartParenthesis) => {
+                    self.position += 1;
+                    ExprKind::Invoke {
+                        callee: Box::new(expr),
+                        args: self.call_arguments()?,
+                    }
+                }
+                Some(Token::StartBracket) => {
+                    self.position += 1;
+                    let index = self.expression()?;
+                    self.expect(Token::EndBracket)?;
+                    ExprKind::Index {
+                        target: Box::new(expr),
+                        index: Box::new(index),
+                    }
+                }
+                Some(Token::Separator('.')) => {
+                    self.position += 1;
+                    let name = self.consume_identifier().map_err(|_| {
+                        ParseError::SyntaxError("Expected the name of a field after '.'".into())
+                    })?;
+                    // `xs.push(1)` is the call `push(xs, 1)`
+                    if let Some(Token::StartParenthesis) = self.peek() {
+                        self.position += 1;
+                        let value = Arg {
+                            name: None,
+                            value: expr,
+                        };
+                        let mut args = self.call_arguments()?;
+                        args.insert(0, value);
+                        ExprKind::Call { callee: name, args }
                     } else {
-                        return Err(ParseError::SyntaxError("Invalid argument type".into()));
+                        ExprKind::Field {
+                            target: Box::new(expr),
+                            name,
+                        }
                     }
-                } else {
-                    let var_type = var_type_slice.first().ok_or_else(|| {
-                        ParseError::SyntaxError(
-                            "Argument type missing in function definition".into(),
-                        )
+                }
+                Some(Token::QuestionDot) => {
+                    self.position += 1;
+                    let name = self.consume_identifier().map_err(|_| {
+                        ParseError::SyntaxError("Expected the name of a field after '?.'".into())
                     })?;
+                    ExprKind::OptionalField {
+                        target: Box::new(expr),
+                        name,
+                    }
+                }
+                _ => break,
+            };
+            expr = Expr::new(kind, self.span_from(start));
+        }
 
-                    let name = if let Token::Identifier(id) = name {
-                        id
-                    } else {
-                        return Err(ParseError::SyntaxError(
-                            "Invalid Name of atribbute".to_string(),
-                        ));
-                    };
+        Ok(expr)
+    }
 
-                    let var_type = if let Token::Type(my_type) = var_type {
-                        my_type.clone()
-                    } else {
-                        return Err(ParseError::SyntaxError(
-                            "Invalid Type of atribbute".to_string(),
-                        ));
-                    };
+    /// `key: value, key: value, ...` until the `}`
+    fn map_entries(&mut self) -> Result<Vec<(Expr, Expr)>, ParseError> {
+        let mut entries = vec![];
 
-                    arg_array.push(Argument::new(name.clone(), var_type, None, None));
-                }
-            } else {
-                return Err(ParseError::SyntaxError("Invalid argument format".into()));
+        loop {
+            self.skip_new_lines();
+            if let Some(Token::EndBrace) = self.peek() {
+                self.position += 1;
+                break;
             }
+            if !entries.is_empty() {
+                self.expect(Token::Separator(','))?;
+                self.skip_new_lines();
+            }
+            let key = self.expression()?;
+            self.expect(Token::Separator(':'))?;
+            self.skip_new_lines();
+            entries.push((key, self.expression()?));
         }
-        let mut table = self.functions.borrow_mut();
-        if let Ok(_) = table.get(identifier.as_ref()) {
-            return Err(ParseError::DefinedFunction(identifier.to_string()));
-        } else {
-            table.insert(
-                identifier.as_ref(),
-                Function::new(identifier.clone(), Types::Void, arg_array, content, 0),
-            )?;
-        }
-        return Ok(());
+
+        Ok(entries)
     }
 
-    fn process_function_call(&self, var: &str, args: Vec<Argument>) -> Result<Token, ParseError> {
-        let func = if let Ok(function) = self.functions.borrow().get(var) {
-            function
-        } else {
-            return Err(ParseError::UndefinedFunction(
-                "This function doesn't exist.".into(),
-            ));
-        };
-        match func {
-            Func::Std(std_func) => {
-                let result = if DEBUG_LIST.contains(&std_func.name.as_str()) {
-                    let mut vars = vec![];
-                    for (_, var) in self.variables.borrow().variables.iter() {
-                        vars.push(var.details());
-                    }
-                    let params = vec![Argument::new(
-                        "variables".into(),
-                        Types::List,
-                        None,
-                        Some(Box::new(Token::List(
-                            vars.iter().map(|x| Token::Str(x.clone().into())).collect(),
-                        ))),
-                    )];
-                    std_func.call(params)
-                } else {
-                    std_func.call(args)
-                };
-                if let Err(err) = result {
-                    return Err(ParseError::FunctionExecution(err));
-                }
-                return Ok(result.unwrap());
+    /// `value, name: value, ...` until the `)`, the named arguments go after the others
+    fn call_arguments(&mut self) -> Result<Vec<Arg>, ParseError> {
+        let mut args: Vec<Arg> = vec![];
+
+        loop {
+            self.skip_new_lines();
+            if let Some(Token::EndParenthesis) = self.peek() {
+                self.position += 1;
+                break;
+            }
+            if !args.is_empty() {
+                self.expect(Token::Separator(','))?;
+                self.skip_new_lines();
             }
-            Func::User(func) => {
-                let result = func.call(args, self.variables.clone(), self.functions.clone());
 
-                if let Err(err) = result {
-                    return Err(ParseError::FunctionExecution(err.to_string()));
+            let next = self.tokens.get(self.position + 1).map(|token| &token.token);
+            let name = match (self.peek(), next) {
+                (Some(Token::Identifier(name)), Some(Token::Separator(':'))) => {
+                    let name = name.clone();
+                    self.position += 2;
+                    self.skip_new_lines();
+                    Some(name)
                 }
-                return Ok(result.unwrap());
+                _ => None,
+            };
+            if name.is_none() && args.last().is_some_and(|arg| arg.name.is_some()) {
+                return Err(ParseError::SyntaxError(
+                    "A positional argument can't go after a named one".into(),
+                )
+                .at(self.current_span()));
             }
+            args.push(Arg {
+                name,
+                value: self.expression()?,
+            });
         }
+
+        Ok(args)
     }
 
-    fn process_identifier(&mut self, var: &str) -> Result<Token, ParseError> {
-        let args = self.is_function_call()?;
-        if args.0 {
-            return self.process_function_call(var, args.1);
+    /// `arg, arg, ...` until `end`, like the elements of a list
+    fn expression_list(&mut self, end: Token) -> Result<Vec<Expr>, ParseError> {
+        let mut items = vec![];
+
+        loop {
+            self.skip_new_lines();
+            if self.peek() == Some(&end) {
+                self.position += 1;
+                break;
+            }
+            if !items.is_empty() {
+                self.expect(Token::Separator(','))?;
+                self.skip_new_lines();
+            }
+            items.push(self.expression()?);
         }
 
-        let (is_assignment, op) = self.is_assignment()?;
-        if is_assignment {
-            let var = self.validate_existing_identifier()?;
-            let right = self.term()?;
+        Ok(items)
+    }
 
-            // Aplicar la asignación a una variable existente
-            let mut table = self.variables.borrow_mut();
-            if let Ok(var) = table.get_mut(&var) {
-                let new_value = op.execute(*var.value.clone(), right)?;
-                let inferred_type = Types::inferred(&new_value)?;
+    fn binary(operator: Operator, left: Expr, right: Expr) -> Expr {
+        let span = left.span.to(right.span);
+        Expr::new(
+            ExprKind::Binary {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+            span,
+        )
+    }
 
-                if inferred_type == var.var_type {
-                    var.value = Box::new(new_value);
-                } else {
-                    return Err(ParseError::TypeError(format!(
-                        "Cannot assign type '{}' to variable '{}'",
-                        inferred_type, var.name
-                    )));
-                }
-                let mut var = var.clone();
-                let id = var.name.clone();
+    // MARK: Consume
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|token| &token.token)
+    }
 
-                table.update(&id, &mut var)?;
-            } else {
-                return Err(ParseError::UndefinedVariable(var));
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(token) if *token == expected => {
+                self.position += 1;
+                Ok(())
             }
-            return Ok(Token::Void);
+            Some(token) => Err(ParseError::UnexpectedToken {
+                span: self.current_span(),
+                token: token.clone(),
+            }),
+            None => Err(ParseError::UndefinedEOF),
         }
+    }
 
-        if let Some(variable) = self.variables.borrow_mut().get(var).ok() {
-            return Ok(*variable.value.clone());
+    fn skip_new_lines(&mut self) {
+        while let Some(Token::NewLine) = self.peek() {
+            self.position += 1;
         }
+    }
 
-        self.handle_undefined_variable_or_type(var)
+    fn consume_identifier(&mut self) -> Result<Arc<str>, ParseError> {
+        match self.peek() {
+            Some(Token::Identifier(name)) => {
+                let name = name.clone();
+                self.position += 1;
+                Ok(name)
+            }
+            _ => Err(ParseError::SyntaxError(
+                "Expected an identifier before assignment operator".into(),
+            )),
+        }
     }
 
-    fn is_function_call(&mut self) -> Result<(bool, Vec<Argument>), ParseError> {
-        if let Some(Token::StartParenthesis) = self.tokens.get(self.position) {
+    fn consume_type(&mut self) -> Result<Option<Types>, ParseError> {
+        if let Some(Token::Separator(':')) = self.peek() {
             self.position += 1;
-            let args = Parser::get_args(
-                &self.tokens,
-                &mut self.position,
-                self.variables.borrow().clone(),
-                self.functions.borrow().clone(),
-            )?;
-
-            Ok((true, args))
-        } else {
-            Ok((false, vec![]))
+            return self.parse_type(":").map(Some);
         }
+        Ok(None)
+    }
+
+    /// The type written after `after`, like `: Int32`, `-> Int32` or `: List<Int32>`
+    fn parse_type(&mut self, after: &str) -> Result<Types, ParseError> {
+        self.nested(|parser| parser.optional_type(after))
     }
 
-    fn is_assignment(&mut self) -> Result<(bool, Operator), ParseError> {
-        if let Some(Token::Operator(op)) = self.tokens.get(self.position) {
-            if op.is_assignation() {
-                return Ok((true, op.clone()));
+    fn optional_type(&mut self, after: &str) -> Result<Types, ParseError> {
+        let types = self.base_type(after)?;
+        // `Int32?` is the same type as `Option<Int32>`
+        if let Some(Token::Question) = self.peek() {
+            self.position += 1;
+            if !matches!(types, Types::Option(_)) {
+                return Ok(Types::Option(Box::new(types)));
             }
         }
-        Ok((false, Operator::Null))
+        Ok(types)
     }
 
-    fn handle_undefined_variable_or_type(&self, var: &str) -> Result<Token, ParseError> {
-        if let Some(Token::Separator(':')) = self.tokens.get(self.position - 2) {
-            Err(ParseError::UndefinedType(format!(
-                "The type '{var}' doesn't exist."
-            )))
-        } else {
-            Err(ParseError::UndefinedVariable(format!(
-                "The variable '{var}' doesn't exist."
-            )))
+    /// A type without the `?` of the optionals
+    fn base_type(&mut self, after: &str) -> Result<Types, ParseError> {
+        match self.advance() {
+            Some(Token::Type(Types::List(_))) => {
+                if let Some(Token::Operator(Operator::Lower)) = self.peek() {
+                    self.position += 1;
+                    let item = self.parse_type("<")?;
+                    self.close_type()?;
+                    Ok(Types::List(Box::new(item)))
+                } else {
+                    Ok(Types::List(Box::new(Types::Inferred)))
+                }
+            }
+            Some(Token::Type(Types::Map(..))) => {
+                if let Some(Token::Operator(Operator::Lower)) = self.peek() {
+                    self.position += 1;
+                    let key = self.parse_type("<")?;
+                    self.expect(Token::Separator(','))?;
+                    let value = self.parse_type(",")?;
+                    self.close_type()?;
+                    Ok(Types::Map(Box::new(key), Box::new(value)))
+                } else {
+                    Ok(Types::Map(
+                        Box::new(Types::Inferred),
+                        Box::new(Types::Inferred),
+                    ))
+                }
+            }
+            Some(Token::Type(Types::Option(_))) => {
+                if let Some(Token::Operator(Operator::Lower)) = self.peek() {
+                    self.position += 1;
+                    let item = self.parse_type("<")?;
+                    self.close_type()?;
+                    Ok(Types::Option(Box::new(item)))
+                } else {
+                    Ok(Types::Option(Box::new(Types::Inferred)))
+                }
+            }
+            Some(Token::Type(tipo)) => Ok(tipo),
+            Some(Token::Identifier(name)) if self.generics.contains(&name) => {
+                Ok(Types::Generic(name.to_string()))
+            }
+            // The struct is checked when the value is used
+            Some(Token::Identifier(name)) => {
+                let mut args = vec![];
+                if let Some(Token::Operator(Operator::Lower)) = self.peek() {
+                    self.position += 1;
+                    args.push(self.parse_type("<")?);
+                    while let Some(Token::Separator(',')) = self.peek() {
+                        self.position += 1;
+                        args.push(self.parse_type(",")?);
+                    }
+                    self.close_type()?;
+                }
+                Ok(Types::Custom(name.to_string(), args))
+            }
+            _ => Err(ParseError::SyntaxError(format!(
+                "Expected a type after '{after}'"
+            ))),
         }
     }
 
-    /// For any transformation you want to make to the arguments, you must change the body of this function.
-    fn get_args(
-        tokens: &[Token],
-        position: &mut usize,
-        variables: VariableTable,
-        functions: FunctionTable,
-    ) -> Result<Vec<Argument>, ParseError> {
-        let mut result = vec![];
-        let mut scopes = 1;
-        while *position < tokens.len() {
-            let token = &tokens[*position];
-            if *token == Token::EndParenthesis && scopes == 1 {
-                *position += 1;
-                break;
+    /// The `>` that closes a generic type, a `>>` is split to close two types
+    fn close_type(&mut self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(Token::Operator(Operator::Greater)) => {
+                self.position += 1;
+                Ok(())
             }
-            if *token == Token::StartParenthesis {
-                scopes += 1;
+            Some(Token::Operator(Operator::ShiftRight)) => {
+                self.tokens[self.position].token = Token::Operator(Operator::Greater);
+                Ok(())
             }
+            _ => Err(ParseError::SyntaxError(
+                "Expected '>' to close the type".into(),
+            )),
+        }
+    }
 
-            result.push(token.clone());
-            *position += 1;
+    // MARK: Auxiliary
+    /// Check if the `{` in the current position starts a map like `{ "a": 1 }` instead of a
+    /// block, a statement never starts with a value followed by `:`. An empty `{}` is a block.
+    fn is_map_literal(&self) -> bool {
+        self.first_entry(self.position).is_some()
+    }
+
+    /// Check if the identifier before the current position starts the construction of a struct
+    /// like `Point { x: 1 }`, a block after a condition like `if a == b { ... }` never starts
+    /// with a field followed by `:`
+    fn is_struct_literal(&self) -> bool {
+        self.peek() == Some(&Token::StartBrace)
+            && matches!(self.first_entry(self.position), Some(Token::Identifier(_)))
+    }
+
+    /// The first token after the `{` in `brace` if it's followed by `:`, like the key of a map
+    fn first_entry(&self, brace: usize) -> Option<&Token> {
+        let mut tokens = self
+            .tokens
+            .iter()
+            .skip(brace + 1)
+            .map(|token| &token.token)
+            .filter(|token| **token != Token::NewLine);
+        match (tokens.next(), tokens.next()) {
+            (Some(key), Some(Token::Separator(':'))) => Some(key),
+            _ => None,
         }
-        let mut data: Vec<Argument> = vec![];
-        for res in result.split(|x| *x.to_string() == Token::Separator(',').to_string()) {
-            let mut parser = Parser::internal_new(
-                res.to_vec(),
-                0,
-                Rc::new(RefCell::new(variables.clone())),
-                Rc::new(RefCell::new(functions.clone())),
-            );
-            let result = parser.parse()?;
-            if !result.is_empty() {
-                data.push(Argument::from(result[0].clone()));
+    }
+
+    /// Check if the identifier in the current position is followed by an assignment operator,
+    /// maybe with indexes or fields between them like `name[0] = value` or `name.x = value`
+    fn is_assignment(&self) -> bool {
+        let mut depth = 0;
+        let mut after_dot = false;
+        for token in self.tokens.iter().skip(self.position + 1) {
+            match &token.token {
+                Token::StartBracket => depth += 1,
+                Token::EndBracket if depth > 0 => depth -= 1,
+                Token::Separator('.') if depth == 0 => {
+                    after_dot = true;
+                    continue;
+                }
+                Token::Identifier(_) if depth == 0 && after_dot => {}
+                Token::Operator(op) if depth == 0 => {
+                    return *op == Operator::Assign || op.is_assignation()
+                }
+                _ if depth == 0 => return false,
+                _ => {}
             }
+            after_dot = false;
         }
-        Ok(data)
+        false
+    }
+
+    /// Span of the token in the current position
+    fn current_span(&self) -> Span {
+        self.tokens
+            .get(self.position)
+            .map(|token| token.span)
+            .unwrap_or(self.eof)
+    }
+
+    /// Parse a piece of code that can contain itself, like an expression in parentheses. The
+    /// Rust stack grows when it's running out, and a code nested more than [`MAX_NESTING`]
+    /// levels is an error, so the checker and the evaluator don't go that deep either.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.nesting >= MAX_NESTING {
+            let error = ParseError::SyntaxError(format!(
+                "The code is nested more than {MAX_NESTING} levels deep."
+            ));
+            return Err(error.at(self.current_span()));
+        }
+        self.nesting += 1;
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || parse(self));
+        self.nesting -= 1;
+        result
     }
-}
 
-impl PartialEq for Parser {
-    fn eq(&self, other: &Self) -> bool {
-        self.tokens == other.tokens
+    /// Span from the token in the position `start` to the last consumed token
+    fn span_from(&self, start: usize) -> Span {
+        let start_span = self.tokens.get(start).map_or(self.eof, |token| token.span);
+        match self
+            .position
+            .checked_sub(1)
+            .and_then(|end| self.tokens.get(end))
+        {
+            Some(end) if self.position > start => start_span.to(end.span),
+            _ => Span::new(start_span.file_id, start_span.start, start_span.start),
+        }
     }
 }
 
@@ -901,15 +1515,19 @@ mod parser_test {
 
     #[test]
     fn new_test() {
-        let parser = Parser::new(vec![], None, None);
+        let parser = Parser::new(vec![SpannedToken::new(Token::EOF, Span::new(0, 4, 4))]);
         assert_eq!(
             parser,
             Parser {
                 tokens: vec![],
                 position: 0,
-                scope: 0,
-                variables: Rc::new(RefCell::new(VariableTable::new())),
-                functions: Rc::new(RefCell::new(FunctionTable::new()))
+                eof: Span::new(0, 4, 4),
+                loop_depth: 0,
+                in_function: false,
+                generics: vec![],
+                enums: vec![],
+                matches: vec![],
+                nesting: 0,
             }
         )
     }
@@ -917,10 +1535,184 @@ mod parser_test {
     #[test]
     fn parse_test() {
         let mut lex: Lexer<'static> = Lexer::new("var hola = 10\n");
-        let tokens = lex.lex();
-        let mut parser: Parser = Parser::new(tokens, None, None);
+        let tokens = lex.lex().unwrap();
+        let mut parser: Parser = Parser::new(tokens);
         let parse = parser.parse().unwrap();
 
-        assert_eq!(parse, vec![])
+        assert_eq!(
+            parse,
+            vec![Item::Stmt(Stmt::new(
+                StmtKind::VarDecl {
+                    name: "hola".into(),
+                    var_type: Types::Inferred,
+                    value: Expr::new(
+                        ExprKind::Literal(Token::Int32(10.into())),
+                        Span::new(0, 11, 13)
+                    ),
+                    mutable: true,
+                },
+                Span::new(0, 0, 13)
+            ))]
+        )
+    }
+
+    #[test]
+    fn precedence_test() {
+        let mut lex = Lexer::new("1 + 2 * 3");
+        let mut parser = Parser::new(lex.lex().unwrap());
+        let parse = parser.parse().unwrap();
+
+        let Item::Stmt(Stmt {
+            kind: StmtKind::Expr(expr),
+            ..
+        }) = &parse[0]
+        else {
+            panic!("Expected an expression, found {parse:?}");
+        };
+        match &expr.kind {
+            ExprKind::Binary {
+                operator, right, ..
+            } => {
+                assert_eq!(*operator, Operator::Add);
+                assert!(matches!(
+                    right.kind,
+                    ExprKind::Binary {
+                        operator: Operator::Mul,
+                        ..
+                    }
+                ));
+            }
+            kind => panic!("Expected a binary expression, found {kind:?}"),
+        }
+    }
+
+    #[test]
+    fn parentheses_test() {
+        let mut lex = Lexer::new("(1 + 2) * 3");
+        let parse = Parser::new(lex.lex().unwrap()).parse().unwrap();
+
+        let Item::Stmt(Stmt {
+            kind: StmtKind::Expr(expr),
+            ..
+        }) = &parse[0]
+        else {
+            panic!("Expected an expression, found {parse:?}");
+        };
+        assert_eq!(expr.span, Span::new(0, 0, 11));
+        let ExprKind::Binary { operator, left, .. } = &expr.kind else {
+            panic!("Expected a binary expression, found {expr:?}");
+        };
+        assert_eq!(*operator, Operator::Mul);
+        assert_eq!(left.span, Span::new(0, 0, 7));
+
+        let mut lex = Lexer::new("(1 + 2");
+        assert!(Parser::new(lex.lex().unwrap()).parse().is_err());
+    }
+
+    #[test]
+    fn operand_error_test() {
+        let mut lex = Lexer::new("1 + (2 * )");
+        let error = Parser::new(lex.lex().unwrap()).parse().unwrap_err();
+        assert_eq!(
+            error.kind().to_string(),
+            "SyntaxError: Expected an operand after the operator"
+        );
+        assert_eq!(error.span(), Some(Span::new(0, 7, 8)));
+
+        // The error of the operand isn't replaced by a generic one
+        let mut lex = Lexer::new("1 + (2 * 3");
+        let error = Parser::new(lex.lex().unwrap()).parse().unwrap_err();
+        assert_ne!(
+            error.kind().to_string(),
+            "SyntaxError: Expected an operand after the operator"
+        );
+    }
+
+    #[test]
+    fn nesting_test() {
+        // The statement and the expression in it are two levels
+        let depth = MAX_NESTING - 2;
+        let code = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        let mut lex = Lexer::new(&code);
+        assert!(Parser::new(lex.lex().unwrap()).parse().is_ok());
+
+        // Too deep is an error instead of a stack overflow, at the code that doesn't fit
+        let code = format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000));
+        let mut lex = Lexer::new(&code);
+        let error = Parser::new(lex.lex().unwrap()).parse().unwrap_err();
+        assert_eq!(
+            error.kind().to_string(),
+            format!("SyntaxError: The code is nested more than {MAX_NESTING} levels deep.")
+        );
+        assert_eq!(error.span(), Some(Span::new(0, depth + 1, depth + 2)));
+    }
+
+    #[test]
+    fn lambda_test() {
+        let mut lex = Lexer::new("var double = |x| x * 2\ndouble(2)(3)");
+        let parse = Parser::new(lex.lex().unwrap()).parse().unwrap();
+
+        let Item::Stmt(Stmt {
+            kind: StmtKind::VarDecl { value, .. },
+            ..
+        }) = &parse[0]
+        else {
+            panic!("Expected a declaration, found {parse:?}");
+        };
+        let ExprKind::Lambda(function) = &value.kind else {
+            panic!("Expected a lambda, found {value:?}");
+        };
+        assert_eq!(&*function.name, LAMBDA);
+        assert_eq!(function.params[0].var_type, Types::Inferred);
+        assert!(matches!(
+            function.body[..],
+            [Stmt {
+                kind: StmtKind::Return(Some(_)),
+                ..
+            }]
+        ));
+
+        let Item::Stmt(Stmt {
+            kind: StmtKind::Expr(expr),
+            ..
+        }) = &parse[1]
+        else {
+            panic!("Expected an expression, found {parse:?}");
+        };
+        let ExprKind::Invoke { callee, .. } = &expr.kind else {
+            panic!("Expected a call to a value, found {expr:?}");
+        };
+        assert!(matches!(callee.kind, ExprKind::Call { .. }));
+    }
+
+    #[test]
+    fn method_call_test() {
+        let mut lex = Lexer::new("xs[0].push(1)\np.x");
+        let parse = Parser::new(lex.lex().unwrap()).parse().unwrap();
+
+        let Item::Stmt(Stmt {
+            kind: StmtKind::Expr(expr),
+            ..
+        }) = &parse[0]
+        else {
+            panic!("Expected an expression, found {parse:?}");
+        };
+        let ExprKind::Call { callee, args } = &expr.kind else {
+            panic!("Expected a call, found {expr:?}");
+        };
+        assert_eq!(&**callee, "push");
+        assert!(matches!(args[0].value.kind, ExprKind::Index { .. }));
+        assert_eq!(args.len(), 2);
+
+        assert!(matches!(
+            &parse[1],
+            Item::Stmt(Stmt {
+                kind: StmtKind::Expr(Expr {
+                    kind: ExprKind::Field { .. },
+                    ..
+                }),
+                ..
+            })
+        ));
     }
 }
@@ -0,0 +1,153 @@
+use std::fmt;
+
+/// Range of bytes `[start, end)` of a source file.
+///
+/// `file_id` is the index of the file in the [`SourceMap`], the files lexed
+/// on their own use the id `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct Span {
+    pub file_id: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(file_id: usize, start: usize, end: usize) -> Self {
+        Self {
+            file_id,
+            start,
+            end,
+        }
+    }
+
+    /// Create a span that goes from the start of `self` to the end of `other`
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.file_id, self.start, other.end)
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Human readable position of a [`Span`], like `main.atlas:12:7`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: String,
+    /// Starting at 1
+    pub line: usize,
+    /// Starting at 1, counted in characters
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SourceFile {
+    name: String,
+    content: String,
+    /// Byte offset where each line starts
+    line_starts: Vec<usize>,
+}
+
+/// Keep every source file by its id to translate a [`Span`] into a [`Location`].
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file and return the id that the lexer must use for it
+    pub fn add(&mut self, name: &str, content: &str) -> usize {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        self.files.push(SourceFile {
+            name: name.to_string(),
+            content: content.to_string(),
+            line_starts,
+        });
+        self.files.len() - 1
+    }
+
+    pub fn name(&self, file_id: usize) -> Option<&str> {
+        self.files.get(file_id).map(|file| file.name.as_str())
+    }
+
+    pub fn content(&self, file_id: usize) -> Option<&str> {
+        self.files.get(file_id).map(|file| file.content.as_str())
+    }
+
+    /// Text of the line `line` (starting at 1) without the line break
+    pub fn line(&self, file_id: usize, line: usize) -> Option<&str> {
+        let file = self.files.get(file_id)?;
+        let start = *file.line_starts.get(line.checked_sub(1)?)?;
+        let end = file
+            .line_starts
+            .get(line)
+            .map(|end| end - 1)
+            .unwrap_or(file.content.len());
+        Some(file.content[start..end].trim_end_matches('\r'))
+    }
+
+    /// Byte offset where the line `line` (starting at 1) starts
+    pub fn line_offset(&self, file_id: usize, line: usize) -> Option<usize> {
+        let file = self.files.get(file_id)?;
+        file.line_starts.get(line.checked_sub(1)?).copied()
+    }
+
+    /// Byte offset of the `column` (counted in characters) of the `line`, both starting at 1
+    pub fn offset(&self, file_id: usize, line: usize, column: usize) -> Option<usize> {
+        let content = self.line(file_id, line)?;
+        let start = self.line_offset(file_id, line)?;
+        let column = column.checked_sub(1)?;
+        content
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(content.len()))
+            .nth(column)
+            .map(|offset| start + offset)
+    }
+
+    pub fn location(&self, span: Span) -> Option<Location> {
+        let file = self.files.get(span.file_id)?;
+        let offset = span.start.min(file.content.len());
+        let line = file.line_starts.partition_point(|start| *start <= offset);
+        let line_start = file.line_starts[line - 1];
+        let column = file.content[line_start..offset].chars().count() + 1;
+
+        Some(Location {
+            file: file.name.clone(),
+            line,
+            column,
+        })
+    }
+}
+
+#[cfg(test)]
+mod span_test {
+    use super::*;
+
+    #[test]
+    fn location_test() {
+        let mut map = SourceMap::new();
+        let id = map.add("main.atlas", "var a = 1\nvar bé = 2\n");
+
+        let location = map.location(Span::new(id, 18, 19)).unwrap();
+        assert_eq!(location.to_string(), "main.atlas:2:8");
+        assert_eq!(map.line(id, 2), Some("var bé = 2"));
+        assert_eq!(map.offset(id, 2, 8), Some(18));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt, str::FromStr};
+use std::{collections::HashMap, fmt, str::FromStr, sync::Arc};
 
 use serde::Serialize;
 
@@ -6,7 +6,7 @@ use crate::types::basic::number::{double::Double, float::Float, int32::Int32, in
 
 use super::{elements::token::Token, error::parse_error::ParseError};
 
-#[derive(Debug, Serialize, PartialEq, Clone, Copy)]
+#[derive(Debug, Serialize, PartialEq, Clone)]
 #[allow(dead_code)]
 pub enum Types {
     Int32,
@@ -20,31 +20,41 @@ pub enum Types {
     Boolean,
     Void,
     Function,
-    List,
+    /// List with the type of its elements, `List<Int32>`
+    List(Box<Types>),
+    /// Map with the type of its keys and its values, `Map<String, Int32>`
+    Map(Box<Types>, Box<Types>),
+    /// A value of the type or `none`, `Option<Int32>` or `Int32?`
+    Option(Box<Types>),
+    /// A struct or an enum declared in the program, identified by its name, with the types of
+    /// its type parameters like `Pair<Int32, Str>`. They are empty when the type isn't generic
+    /// or when they aren't known, like in the type of a value.
+    Custom(String, Vec<Types>),
+    /// A type parameter of a generic function or type, like `T` in
+    /// `func first<T>(xs: List<T>) -> T`
+    Generic(String),
     Inferred,
 }
 
+/// The types given to the type parameters, by their name
+pub type Bindings = HashMap<String, Types>;
+
 #[allow(dead_code)]
 impl Types {
     pub fn is_integer(&self) -> bool {
-        match self {
-            Self::Int32 | Self::Int64 | Self::HPInt => true,
-            _ => false,
-        }
+        matches!(self, Self::Int32 | Self::Int64 | Self::HPInt)
     }
 
     pub fn is_float(&self) -> bool {
-        match self {
-            Self::Double | Self::Float => true,
-            _ => false,
-        }
+        matches!(self, Self::Double | Self::Float)
     }
 
     pub fn is_numeric(&self) -> bool {
         self.is_float() || self.is_integer()
     }
 
-    pub fn cmp<T>(&self, other: T) -> bool
+    /// Check if a value of the `other` type can be used where `self` is expected
+    pub fn is_compatible<T>(&self, other: T) -> bool
     where
         T: Into<Types>,
     {
@@ -54,19 +64,13 @@ impl Types {
         }
 
         match self {
-            Self::Int64 => match other {
-                Self::Int32 => true,
-                _ => false,
-            },
-            Self::Double => match other {
-                Self::Int32 | Self::Int64 => true,
-                _ => false,
-            },
+            Self::Int64 => matches!(other, Self::Int32),
+            Self::Double => matches!(other, Self::Int32 | Self::Int64),
             _ => false,
         }
     }
 
-    pub fn inferred<'a>(value: &Token) -> Result<Self, ParseError> {
+    pub fn inferred(value: &Token) -> Result<Self, ParseError> {
         match value {
             Token::Int32(_) => Ok(Self::Int32),
             Token::Int64(_) => Ok(Self::Int64),
@@ -77,6 +81,12 @@ impl Types {
             Token::Str(_) => Ok(Self::Str),
             Token::Type(types) => Ok(types.clone()),
             Token::Boolean(_) => Ok(Self::Boolean),
+            Token::List(items) => Ok(Self::list_of(items)),
+            Token::Map(entries) => Ok(Self::map_of(entries)),
+            Token::Struct { name, .. } => Ok(Self::Custom(name.to_string(), vec![])),
+            Token::Variant { enumeration, .. } => Ok(Self::Custom(enumeration.to_string(), vec![])),
+            Token::Function(_) => Ok(Self::Function),
+            Token::None => Ok(Self::Option(Box::new(Self::Inferred))),
             _ => Err(ParseError::InvalidType(format!(
                 "the type to '{:?}' doesn't exists.",
                 Self::from(value)
@@ -84,9 +94,218 @@ impl Types {
         }
     }
 
+    /// The name of this type, the type of `value`, in a message. The type of `none` is shown
+    /// as `none`, the type of its value isn't known.
+    pub fn name_for(&self, value: &Token) -> String {
+        match value {
+            Token::None => "none".to_string(),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Type of a list with these elements, the elements have the type of the first one
+    pub fn list_of(items: &[Token]) -> Self {
+        Self::List(Box::new(items.first().map_or(Self::Inferred, Self::from)))
+    }
+
+    /// Type of a map with these entries, like the lists the first entry decides the types
+    pub fn map_of(entries: &[(Token, Token)]) -> Self {
+        let (key, value) = entries
+            .first()
+            .map_or((Self::Inferred, Self::Inferred), |entry| {
+                (Self::from(&entry.0), Self::from(&entry.1))
+            });
+        Self::Map(Box::new(key), Box::new(value))
+    }
+
+    /// Type of a value of the struct or the enum `name` whose type parameters `generics` have
+    /// the types in `bindings`, the ones without type can be any type
+    pub fn custom_of(name: &str, generics: &[Arc<str>], bindings: &Bindings) -> Self {
+        let args = generics
+            .iter()
+            .map(|generic| bindings.get(&**generic).cloned().unwrap_or(Self::Inferred))
+            .collect();
+        Self::Custom(name.to_string(), args)
+    }
+
+    /// Fill the unknown parts of this type with the ones of `known`, like `List<Inferred>` with
+    /// `List<Int32>`. The parts that are already known are kept.
+    pub fn refine(&self, known: &Types) -> Types {
+        match (self, known) {
+            (Self::Inferred, known) => known.clone(),
+            (Self::List(item), Self::List(known)) => Self::List(Box::new(item.refine(known))),
+            (Self::Option(item), Self::Option(known)) => Self::Option(Box::new(item.refine(known))),
+            // A value that isn't `none` tells the type of the optional
+            (Self::Option(item), known) => Self::Option(Box::new(item.refine(known))),
+            (Self::Map(key, value), Self::Map(known_key, known_value)) => Self::Map(
+                Box::new(key.refine(known_key)),
+                Box::new(value.refine(known_value)),
+            ),
+            (Self::Custom(name, args), Self::Custom(known_name, known_args))
+                if name == known_name && args.is_empty() =>
+            {
+                Self::Custom(name.clone(), known_args.clone())
+            }
+            (Self::Custom(name, args), Self::Custom(known_name, known_args))
+                if name == known_name && args.len() == known_args.len() =>
+            {
+                let args = args
+                    .iter()
+                    .zip(known_args)
+                    .map(|(arg, known)| arg.refine(known))
+                    .collect();
+                Self::Custom(name.clone(), args)
+            }
+            (types, _) => types.clone(),
+        }
+    }
+
+    /// Check if some part of the type isn't known yet
+    pub fn has_inferred(&self) -> bool {
+        match self {
+            Self::Inferred => true,
+            Self::List(item) | Self::Option(item) => item.has_inferred(),
+            Self::Map(key, value) => key.has_inferred() || value.has_inferred(),
+            Self::Custom(_, args) => args.iter().any(Self::has_inferred),
+            _ => false,
+        }
+    }
+
+    /// Check if a value of the type `found` fits in this type, giving a type to the type
+    /// parameters that don't have one yet. A type parameter that already has a type only
+    /// accepts values of that type.
+    pub fn unify(&self, found: &Types, bindings: &mut Bindings) -> bool {
+        match (self, found) {
+            (Self::Inferred, _) | (_, Self::Inferred) => true,
+            (Self::Generic(name), found) => match bindings.get(name).cloned() {
+                Some(bound) => {
+                    let fits = bound.unify(found, bindings);
+                    if fits {
+                        bindings.insert(name.clone(), bound.refine(found));
+                    }
+                    fits
+                }
+                None => {
+                    bindings.insert(name.clone(), found.clone());
+                    true
+                }
+            },
+            (Self::List(item), Self::List(found)) => item.unify(found, bindings),
+            (Self::Option(item), Self::Option(found)) => item.unify(found, bindings),
+            (Self::Option(item), found) => item.unify(found, bindings),
+            (Self::Map(key, value), Self::Map(found_key, found_value)) => {
+                key.unify(found_key, bindings) && value.unify(found_value, bindings)
+            }
+            (Self::Custom(name, args), Self::Custom(found_name, found_args)) => {
+                name == found_name
+                    && (args.is_empty()
+                        || found_args.is_empty()
+                        || (args.len() == found_args.len()
+                            && args
+                                .iter()
+                                .zip(found_args)
+                                .all(|(arg, found)| arg.unify(found, bindings))))
+            }
+            (expected, found) => found.can_convert(expected),
+        }
+    }
+
+    /// The type with the type parameters replaced by their types, the ones without type are
+    /// kept
+    pub fn substitute(&self, bindings: &Bindings) -> Types {
+        match self {
+            Self::Generic(name) => bindings.get(name).cloned().unwrap_or(self.clone()),
+            Self::List(item) => Self::List(Box::new(item.substitute(bindings))),
+            Self::Option(item) => Self::Option(Box::new(item.substitute(bindings))),
+            Self::Map(key, value) => Self::Map(
+                Box::new(key.substitute(bindings)),
+                Box::new(value.substitute(bindings)),
+            ),
+            Self::Custom(name, args) => Self::Custom(
+                name.clone(),
+                args.iter().map(|arg| arg.substitute(bindings)).collect(),
+            ),
+            types => types.clone(),
+        }
+    }
+
+    /// The type with `Inferred` in place of the type parameters, when their types aren't known
+    pub fn without_generics(&self) -> Types {
+        match self {
+            Self::Generic(_) => Self::Inferred,
+            Self::List(item) => Self::List(Box::new(item.without_generics())),
+            Self::Option(item) => Self::Option(Box::new(item.without_generics())),
+            Self::Map(key, value) => Self::Map(
+                Box::new(key.without_generics()),
+                Box::new(value.without_generics()),
+            ),
+            Self::Custom(name, args) => Self::Custom(
+                name.clone(),
+                args.iter().map(Self::without_generics).collect(),
+            ),
+            types => types.clone(),
+        }
+    }
+
+    /// Check if a value of this type can be converted to `to` without changing its meaning,
+    /// between integers, between decimals and between collections of those. A value fits in
+    /// an optional of its type, but an optional doesn't fit in a type that can't be `none`.
+    pub fn can_convert(&self, to: &Types) -> bool {
+        // An empty collection has elements of any type
+        let item_can_convert = |from: &Types, to: &Types| {
+            *from == Self::Inferred || *to == Self::Inferred || from.can_convert(to)
+        };
+
+        match (self, to) {
+            (Self::List(from), Self::List(to)) => item_can_convert(from, to),
+            (Self::Option(from), Self::Option(to)) => item_can_convert(from, to),
+            (from, Self::Option(to)) => item_can_convert(from, to),
+            (Self::Map(from_key, from_value), Self::Map(to_key, to_value)) => {
+                item_can_convert(from_key, to_key) && item_can_convert(from_value, to_value)
+            }
+            // A type without type parameters, like the annotation `Pair`, accepts any of them
+            (Self::Custom(from, from_args), Self::Custom(to, to_args)) => {
+                from == to
+                    && (to_args.is_empty()
+                        || (from_args.len() == to_args.len()
+                            && from_args
+                                .iter()
+                                .zip(to_args)
+                                .all(|(from, to)| item_can_convert(from, to))))
+            }
+            (from, to) => {
+                from == to
+                    || (from.is_integer() && to.is_integer())
+                    || (from.is_float() && to.is_float())
+            }
+        }
+    }
+
     pub fn transform(value: Token, to: Self) -> Result<(Token, Self), ParseError> {
         match (to, value) {
-            (Types::Int32, Token::Int32(int32)) => Ok((Token::Int32(int32.clone()), Types::Int32)),
+            (Types::List(item), Token::List(items)) => {
+                let items = items
+                    .into_iter()
+                    .map(|value| Self::transform_item(value, &item))
+                    .collect::<Result<_, _>>()?;
+                Ok((Token::List(items), Types::List(item)))
+            }
+            (Types::Map(key, value), Token::Map(entries)) => {
+                let entries = entries
+                    .into_iter()
+                    .map(|(k, v)| {
+                        Ok((
+                            Self::transform_item(k, &key)?,
+                            Self::transform_item(v, &value)?,
+                        ))
+                    })
+                    .collect::<Result<_, ParseError>>()?;
+                Ok((Token::Map(entries), Types::Map(key, value)))
+            }
+            (Types::Option(item), Token::None) => Ok((Token::None, Types::Option(item))),
+            (Types::Option(item), v) => Ok((Self::transform_item(v, &item)?, Types::Option(item))),
+
+            (Types::Int32, Token::Int32(int32)) => Ok((Token::Int32(int32), Types::Int32)),
             (Types::Int32, Token::Int64(int64)) => {
                 Ok((Token::Int32(Int32::from(*int64 as i32)), Types::Int32))
             }
@@ -108,12 +327,11 @@ impl Types {
                 Token::Int32(if b { Int32::new(1) } else { Int32::new(0) }),
                 Types::Int32,
             )),
-            (Types::Int32, Token::Void) => Ok((Token::Int32(Int32::new(0)), Types::Int32)),
 
             (Types::Int64, Token::Int32(int32)) => {
                 Ok((Token::Int64((*int32 as i64).into()), Types::Int64))
             }
-            (Types::Int64, Token::Int64(int64)) => Ok((Token::Int64(int64.clone()), Types::Int64)),
+            (Types::Int64, Token::Int64(int64)) => Ok((Token::Int64(int64), Types::Int64)),
             (Types::Int64, Token::HPInt(int128)) => {
                 Ok((Token::Int64((*int128 as i64).into()), Types::Int64))
             }
@@ -132,7 +350,6 @@ impl Types {
                 Token::Int64(if b { Int64::new(1) } else { Int64::new(0) }),
                 Types::Int64,
             )),
-            (Types::Int64, Token::Void) => Ok((Token::Int64(Int64::new(0)), Types::Int64)),
 
             (Types::Float, Token::Int32(int32)) => {
                 Ok((Token::Float(Float::new(*int32 as f32)), Types::Float))
@@ -143,9 +360,7 @@ impl Types {
             (Types::Float, Token::HPInt(int128)) => {
                 Ok((Token::Float(Float::new(*int128 as f32)), Types::Float))
             }
-            (Types::Float, Token::Float(double)) => {
-                Ok((Token::Float(double.clone()), Types::Float))
-            }
+            (Types::Float, Token::Float(double)) => Ok((Token::Float(double), Types::Float)),
             (Types::Float, Token::Double(double)) => {
                 Ok((Token::Float(Float::new(*double as f32)), Types::Float))
             }
@@ -165,7 +380,6 @@ impl Types {
                 Token::Float(if b { Float::new(1.0) } else { Float::new(0.0) }),
                 Types::Float,
             )),
-            (Types::Float, Token::Void) => Ok((Token::Float(Float::new(0.0)), Types::Float)),
 
             (Types::Double, Token::Int32(int32)) => {
                 Ok((Token::Double(Double::new(*int32 as f64)), Types::Double))
@@ -176,9 +390,7 @@ impl Types {
             (Types::Double, Token::HPInt(int128)) => {
                 Ok((Token::Double(Double::new(*int128 as f64)), Types::Double))
             }
-            (Types::Double, Token::Double(double)) => {
-                Ok((Token::Double(double.clone()), Types::Double))
-            }
+            (Types::Double, Token::Double(double)) => Ok((Token::Double(double), Types::Double)),
             (Types::Double, Token::Float(double)) => {
                 Ok((Token::Double(Double::new(*double as f64)), Types::Double))
             }
@@ -202,7 +414,6 @@ impl Types {
                 }),
                 Types::Double,
             )),
-            (Types::Double, Token::Void) => Ok((Token::Double(Double::new(0.0)), Types::Double)),
 
             (Types::String, v) => {
                 Ok((Token::String(v.clone().str_value().to_string()), Types::Str))
@@ -211,12 +422,27 @@ impl Types {
             (Types::Boolean, v) => Ok((Token::Boolean(v.as_bool()), Types::Boolean)),
             (Types::Void, _) => Ok((Token::Void, Types::Void)),
             (Types::Function, v) => Ok((v.clone(), Types::Function)),
+            // The values of a struct or a variant are checked by the caller, with the types
+            // of its type parameters
+            (Types::Custom(name, args), v @ (Token::Struct { .. } | Token::Variant { .. })) if matches!(Types::from(&v), Types::Custom(from, _) if from == name) => {
+                Ok((v, Types::Custom(name, args)))
+            }
             (Types::Inferred, v) => Ok((v.clone(), Self::from(v.clone()))),
-            _ => Err(ParseError::InvalidTypeConvertion(format!(""))),
+            _ => Err(ParseError::InvalidTypeConvertion(String::new())),
         }
     }
 }
 
+impl Types {
+    /// Convert an element of a collection, an `Inferred` type keeps the value
+    fn transform_item(value: Token, to: &Types) -> Result<Token, ParseError> {
+        if *to == Types::Inferred {
+            return Ok(value);
+        }
+        Self::transform(value, to.clone()).map(|(value, _)| value)
+    }
+}
+
 impl fmt::Display for Types {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -230,8 +456,17 @@ impl fmt::Display for Types {
             Types::Boolean => write!(f, "Boolean"),
             Types::Void => write!(f, "Void"),
             Types::Function => write!(f, "Function"),
-            Types::Inferred => write!(f, "Inferred"),
-            Types::List => write!(f, "List"),
+            // A type that isn't known yet, like the elements of `[]`
+            Types::Inferred => write!(f, "_"),
+            Types::List(item) => write!(f, "List<{item}>"),
+            Types::Map(key, value) => write!(f, "Map<{key}, {value}>"),
+            Types::Option(item) => write!(f, "Option<{item}>"),
+            Types::Custom(name, args) if args.is_empty() => write!(f, "{name}"),
+            Types::Custom(name, args) => {
+                let args: Vec<String> = args.iter().map(Types::to_string).collect();
+                write!(f, "{name}<{}>", args.join(", "))
+            }
+            Types::Generic(name) => write!(f, "{name}"),
         }
     }
 }
@@ -250,6 +485,12 @@ impl FromStr for Types {
             "Str" => Ok(Self::Str),
             "Void" => Ok(Self::Void),
             "Function" => Ok(Self::Function),
+            "List" => Ok(Self::List(Box::new(Self::Inferred))),
+            "Option" => Ok(Self::Option(Box::new(Self::Inferred))),
+            "Map" => Ok(Self::Map(
+                Box::new(Self::Inferred),
+                Box::new(Self::Inferred),
+            )),
             _ => Err(ParseError::InvalidType(format!("Invalid type: {s}"))),
         }
     }
@@ -266,8 +507,13 @@ impl From<&Token> for Types {
             Token::Double(_) => Self::Double,
             Token::String(_) => Self::String,
             Token::Str(_) => Self::Str,
+            Token::List(items) => Self::list_of(items),
+            Token::Map(entries) => Self::map_of(entries),
+            Token::Struct { name, .. } => Self::Custom(name.to_string(), vec![]),
+            Token::Variant { enumeration, .. } => Self::Custom(enumeration.to_string(), vec![]),
             Token::Void => Self::Void,
-            // Token::Function => Self::Function,
+            Token::None => Self::Option(Box::new(Self::Inferred)),
+            Token::Function(_) => Self::Function,
             _ => Self::Void,
         }
     }
@@ -284,8 +530,13 @@ impl From<Token> for Types {
             Token::Double(_) => Self::Double,
             Token::String(_) => Self::String,
             Token::Str(_) => Self::Str,
+            Token::List(items) => Self::list_of(&items),
+            Token::Map(entries) => Self::map_of(&entries),
+            Token::Struct { name, .. } => Self::Custom(name.to_string(), vec![]),
+            Token::Variant { enumeration, .. } => Self::Custom(enumeration.to_string(), vec![]),
             Token::Void => Self::Void,
-            // Token::Function => Self::Function,
+            Token::None => Self::Option(Box::new(Self::Inferred)),
+            Token::Function(_) => Self::Function,
             _ => Self::Void,
         }
     }
@@ -1,12 +1,28 @@
-use super::{elements::token::Token, types::Types};
+use super::{elements::token::Token, span::Span, types::Types};
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub struct Variable {
     pub id: usize,
     pub name: String,
     pub var_type: Types,
     pub value: Box<Token>,
     pub scope: usize,
+    /// `false` for the constants declared with `const`
+    pub mutable: bool,
+    /// Place of the declaration
+    pub span: Span,
+}
+
+/// The place of the declaration doesn't change the variable
+impl PartialEq for Variable {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.var_type == other.var_type
+            && self.value == other.value
+            && self.scope == other.scope
+            && self.mutable == other.mutable
+    }
 }
 
 #[allow(dead_code)]
@@ -18,11 +34,13 @@ impl Variable {
             var_type,
             value: Box::new(value),
             scope,
+            mutable: true,
+            span: Span::default(),
         }
     }
 
     pub fn to_token(&self) -> &Token {
-        &*self.value
+        &self.value
     }
 
     pub fn details(&self) -> String {
@@ -32,11 +50,26 @@ impl Variable {
             format!("{} - ", self.id)
         };
         format!(
-            "{}{} - {:?} - {} - {}",
+            "{}{} - {} - {} - {}",
             &id, self.name, self.var_type, self.value, self.scope
         )
     }
 
+    /// The id, the name, the type, the value and the scope of the variable, the columns shown
+    /// by `showVars`
+    pub fn row(&self) -> Vec<Token> {
+        [
+            self.id.to_string(),
+            self.name.clone(),
+            self.var_type.to_string(),
+            self.value.to_string(),
+            self.scope.to_string(),
+        ]
+        .into_iter()
+        .map(|cell| Token::Str(cell.into()))
+        .collect()
+    }
+
     pub(crate) fn set_id(&mut self, id: usize) {
         self.id = id;
     }
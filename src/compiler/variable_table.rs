@@ -1,11 +1,26 @@
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use super::{error::parse_error::ParseError, variable::Variable};
 
+/// The variables of one scope, linked to the scope around it.
+///
+/// A block, a loop iteration, an arm of a `match` and a function call create a new table whose
+/// parent is the table where they are, so an inner `var x` shadows the outer `x` until the end
+/// of the block. The lookups go from the innermost scope to the outermost one.
 #[derive(Debug, Clone)]
 pub struct VariableTable {
     pub(crate) variables: HashMap<String, Variable>,
     pub(crate) length: usize,
+    /// The scope around this one, `None` for the global scope
+    parent: Option<Rc<RefCell<VariableTable>>>,
+    /// Number of scopes around this one
+    depth: usize,
+}
+
+impl Default for VariableTable {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[allow(dead_code)]
@@ -14,12 +29,35 @@ impl VariableTable {
         VariableTable {
             variables: HashMap::new(),
             length: 0,
+            parent: None,
+            depth: 0,
         }
     }
 
-    pub fn get(&self, key: &str) -> Result<&Variable, ParseError> {
+    /// A new scope inside `parent`
+    pub fn with_parent(parent: Rc<RefCell<VariableTable>>) -> Self {
+        let depth = parent.borrow().depth + 1;
+        VariableTable {
+            parent: Some(parent),
+            depth,
+            ..Self::new()
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn parent(&self) -> Option<Rc<RefCell<VariableTable>>> {
+        self.parent.clone()
+    }
+
+    /// The variable visible with this name, from this scope or one around it
+    pub fn get(&self, key: &str) -> Result<Variable, ParseError> {
         if let Some(var) = self.variables.get(key) {
-            Ok(var)
+            Ok(var.clone())
+        } else if let Some(parent) = &self.parent {
+            parent.borrow().get(key)
         } else {
             Err(ParseError::UndefinedVariable(format!(
                 "The variable {key} doesn't exists."
@@ -27,6 +65,7 @@ impl VariableTable {
         }
     }
 
+    /// The variable with this name declared in this scope
     pub fn get_mut(&mut self, key: &str) -> Result<&mut Variable, ParseError> {
         if let Some(var) = self.variables.get_mut(key) {
             Ok(var)
@@ -37,7 +76,16 @@ impl VariableTable {
         }
     }
 
+    /// Declare a variable in this scope, it can replace a variable of the same scope but not a
+    /// constant
     pub fn insert(&mut self, key: &str, value: Variable) -> Result<(), ParseError> {
+        if let Some(var) = self.variables.get(key).filter(|var| !var.mutable) {
+            return Err(ParseError::ConstReassignment {
+                name: key.to_string(),
+                declaration: var.span,
+                span: value.span,
+            });
+        }
         if !self.variables.contains_key(key) {
             let mut var = value.clone();
             var.set_id(self.length + 1);
@@ -47,14 +95,14 @@ impl VariableTable {
             Ok(())
         } else if let Some((name, var)) = self.variables.get_key_value(key) {
             if *var == value {
-                return Err(ParseError::DefinedVariable(format!("{name}")));
+                return Err(ParseError::DefinedVariable(name.to_string()));
             }
             if var.id == 0 {
                 let mut var = value.clone();
                 var.set_id(self.length + 1);
                 self.variables.insert(key.to_string(), var);
                 self.length += 1;
-                return Ok(());
+                Ok(())
             } else {
                 self.variables.insert(key.to_string(), value);
                 self.length += 1;
@@ -66,7 +114,7 @@ impl VariableTable {
                 var.set_id(self.length + 1);
                 self.variables.insert(key.to_string(), value);
                 self.length += 1;
-                return Ok(());
+                Ok(())
             } else {
                 self.variables.insert(key.to_string(), value);
                 self.length += 1;
@@ -75,12 +123,15 @@ impl VariableTable {
         }
     }
 
+    /// Change the variable visible with this name, in the scope where it was declared
     pub fn update(&mut self, key: &str, value: &mut Variable) -> Result<Variable, ParseError> {
         if let Some(mut var) = self.variables.get_mut(key) {
             let aux = var.clone();
             var = value;
             self.variables.insert(key.to_string(), var.clone());
             Ok(aux)
+        } else if let Some(parent) = &self.parent {
+            parent.borrow_mut().update(key, value)
         } else {
             Err(ParseError::UndefinedVariable(format!(
                 "The variable {key} doesn't exists."
@@ -120,19 +171,19 @@ impl VariableTable {
         (result, not_deleted)
     }
 
-    pub fn pop_scope(&mut self, scope: usize) -> Vec<Variable> {
-        let mut result: Vec<Variable> = vec![];
-        let mut keys: Vec<String> = vec![];
-        for (key, var) in self.variables.iter() {
-            if var.scope >= scope {
-                result.push(var.clone());
-                keys.push(key.to_string());
-            }
-        }
-        for key in keys {
-            self.variables.remove(&key);
-        }
-        result
+    /// Every variable visible from this scope, from the outermost scope to this one. The
+    /// variables shadowed by an inner one aren't visible.
+    pub fn visible(&self) -> Vec<Variable> {
+        let mut visible = match &self.parent {
+            Some(parent) => parent.borrow().visible(),
+            None => vec![],
+        };
+        visible.retain(|var| !self.variables.contains_key(&var.name));
+
+        let mut own: Vec<Variable> = self.variables.values().cloned().collect();
+        own.sort_by_key(|var| var.id);
+        visible.extend(own);
+        visible
     }
 
     pub fn clear(&mut self) {
@@ -150,6 +201,10 @@ impl VariableTable {
 
     pub fn contains_key(&self, key: &str) -> bool {
         self.variables.contains_key(key)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.borrow().contains_key(key))
     }
 
     pub fn show_vars(&self) -> String {
@@ -187,3 +242,61 @@ impl VariableTable {
         txt
     }
 }
+
+#[cfg(test)]
+mod variable_table_test {
+    use crate::compiler::{elements::token::Token, types::Types};
+
+    use super::*;
+
+    fn variable(name: &str, value: i32, scope: usize) -> Variable {
+        Variable::new(name.to_string(), Types::Int32, Token::from(value), scope)
+    }
+
+    #[test]
+    fn shadowing_test() {
+        let global = Rc::new(RefCell::new(VariableTable::new()));
+        global
+            .borrow_mut()
+            .insert("x", variable("x", 1, 0))
+            .unwrap();
+        global
+            .borrow_mut()
+            .insert("y", variable("y", 2, 0))
+            .unwrap();
+
+        let mut inner = VariableTable::with_parent(global.clone());
+        inner.insert("x", variable("x", 10, 1)).unwrap();
+        inner.update("y", &mut variable("y", 20, 0)).unwrap();
+
+        assert_eq!(inner.depth(), 1);
+        assert_eq!(*inner.get("x").unwrap().value, Token::from(10));
+        assert_eq!(*global.borrow().get("x").unwrap().value, Token::from(1));
+        assert_eq!(*global.borrow().get("y").unwrap().value, Token::from(20));
+
+        let visible: Vec<(String, usize)> = inner
+            .visible()
+            .into_iter()
+            .map(|var| (var.name, var.scope))
+            .collect();
+        assert_eq!(visible, vec![("y".into(), 0), ("x".into(), 1)]);
+    }
+
+    #[test]
+    fn row_test() {
+        let mut table = VariableTable::new();
+        let text = Token::String("a - b".into());
+        let var = Variable::new("s".to_string(), Types::String, text, 0);
+        table.insert("s", var).unwrap();
+
+        // The value is one cell even when it has the separator of the old rows
+        let row: Vec<String> = table
+            .get("s")
+            .unwrap()
+            .row()
+            .iter()
+            .map(Token::to_string)
+            .collect();
+        assert_eq!(row, ["1", "s", "String", "a - b", "0"]);
+    }
+}
@@ -1,14 +1,13 @@
-mod cli;
-mod compiler;
-mod std;
-mod types;
-mod utils;
-
-use ::std::process;
-use atlas_lang::compiler::{lexer::Lexer, parser};
+use atlas_lang::{
+    cli::{project::Project, Args, Command},
+    compiler::{
+        checker::Checker, diagnostics::Diagnostic, evaluator::Evaluator,
+        function_table::FunctionTable, lexer::Lexer, parser, span::SourceMap,
+    },
+    utils::panic,
+};
 use clap::Parser;
-use cli::Args;
-use utils::panic;
+use std::{cell::RefCell, process, rc::Rc};
 
 fn main() {
     let args = Args::parse();
@@ -27,19 +26,124 @@ fn main() {
 
     let project = verify.unwrap();
 
+    match args.command {
+        Some(Command::Check { .. }) => return check(project),
+        Some(Command::Hover { line, column, .. }) => return hover(project, line, column),
+        None => {}
+    }
+
+    run(project, args.max_depth);
+
+    // println!("{:#?}", args)
+}
+
+/// Execute every file of the project
+fn run(project: Project, max_depth: usize) {
+    let mut sources = SourceMap::new();
     for file in project.files {
-        let mut lex = Lexer::new(&file.content);
-        let tokens = lex.lex();
-        println!("Tokens: {:?}", tokens);
-
-        let mut parser = parser::Parser::new(tokens, None, None);
-        // println!("\n\nParser: {:?}", parser);
-        let parse = parser.parse();
-        // println!("\n\nParser: {:?}", parse);
-        if parse.is_err() {
-            panic(&format!("{:?}", parse.unwrap_err()));
+        let file_id = sources.add(&file.filename, &file.content);
+        let mut lex = Lexer::with_file(&file.content, file_id);
+        let tokens = match lex.lex() {
+            Ok(tokens) => tokens,
+            Err(errors) => {
+                for error in &errors {
+                    Diagnostic::from(error).emit(&sources);
+                }
+                process::exit(1);
+            }
+        };
+        println!(
+            "Tokens: {:?}",
+            tokens.iter().map(|token| &token.token).collect::<Vec<_>>()
+        );
+
+        let mut parser = parser::Parser::new(tokens);
+        let program = match parser.parse() {
+            Ok(program) => program,
+            Err(error) => return report(&sources, Diagnostic::from(&error)),
+        };
+
+        let functions = FunctionTable::with_max_call_depth(max_depth);
+        let mut evaluator = Evaluator::new(None, Some(Rc::new(RefCell::new(functions))));
+        if let Err(error) = evaluator.evaluate(&program) {
+            report(&sources, Diagnostic::from(&error));
         }
     }
+}
 
-    // println!("{:#?}", args)
+/// Check the types of every file of the project without running it, showing all the errors
+fn check(project: Project) {
+    let mut sources = SourceMap::new();
+    let mut failed = false;
+    for file in project.files {
+        let file_id = sources.add(&file.filename, &file.content);
+        let mut lex = Lexer::with_file(&file.content, file_id);
+        let tokens = match lex.lex() {
+            Ok(tokens) => tokens,
+            Err(errors) => {
+                for error in &errors {
+                    Diagnostic::from(error).emit(&sources);
+                }
+                failed = true;
+                continue;
+            }
+        };
+
+        let mut parser = parser::Parser::new(tokens);
+        let program = match parser.parse() {
+            Ok(program) => program,
+            Err(error) => {
+                Diagnostic::from(&error).emit(&sources);
+                failed = true;
+                continue;
+            }
+        };
+
+        if let Err(errors) = Checker::new().check(&program) {
+            for error in &errors {
+                Diagnostic::from(error).emit(&sources);
+            }
+            failed = true;
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+    println!("No errors found.");
+}
+
+/// Show the type of the code at the line and the column of the file
+fn hover(project: Project, line: usize, column: usize) {
+    let mut sources = SourceMap::new();
+    for file in project.files {
+        let file_id = sources.add(&file.filename, &file.content);
+        let Ok(tokens) = Lexer::with_file(&file.content, file_id).lex() else {
+            process::exit(1);
+        };
+        let program = match parser::Parser::new(tokens).parse() {
+            Ok(program) => program,
+            Err(error) => return report(&sources, Diagnostic::from(&error)),
+        };
+
+        // The types are known even if the program has errors
+        let mut checker = Checker::new();
+        let _ = checker.check(&program);
+        let hover = sources
+            .offset(file_id, line, column)
+            .and_then(|offset| checker.hover(file_id, offset));
+        match hover {
+            Some(hover) => println!("{hover}"),
+            None => {
+                eprintln!("There isn't any code at {}:{line}:{column}.", file.filename);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Show the diagnostic and finish the execution
+fn report(sources: &SourceMap, diagnostic: Diagnostic) {
+    diagnostic.emit(sources);
+    process::exit(1);
 }
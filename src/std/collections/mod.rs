@@ -0,0 +1,101 @@
+use crate::compiler::{elements::token::Token, function::Argument, types::Types};
+
+use super::StdFunc;
+use std::collections::HashMap;
+
+/// Functions that change the collection passed as first argument. They return the new
+/// collection, and the evaluator also stores it back in the argument when it's a variable or
+/// an element.
+pub const MUTATING_LIST: &[&str] = &["remove", "push"];
+
+/// The entries of the map passed as first argument
+fn map_argument(name: &str, args: &[Argument], len: usize) -> Result<Vec<(Token, Token)>, String> {
+    if args.len() != len {
+        return Err(format!("The {name} function needs {len} arguments."));
+    }
+    match args[0].get_value() {
+        Token::Map(entries) => Ok(entries),
+        value => Err(format!(
+            "The first argument of {name} must be a <Map> but it's <{}>.",
+            Types::from(&value).name_for(&value)
+        )),
+    }
+}
+
+pub fn collections_functions() -> HashMap<String, StdFunc> {
+    let mut functions = HashMap::new();
+
+    functions.insert(
+        "has".to_string(),
+        StdFunc::new("has".to_string(), |args| {
+            let entries = map_argument("has", &args, 2)?;
+            let key = args[1].get_value();
+            Ok(Token::Boolean(
+                entries.iter().any(|(old, _)| old.is_same_key(&key)),
+            ))
+        }),
+    );
+
+    functions.insert(
+        "keys".to_string(),
+        StdFunc::new("keys".to_string(), |args| {
+            let entries = map_argument("keys", &args, 1)?;
+            Ok(Token::List(
+                entries.into_iter().map(|(key, _)| key).collect(),
+            ))
+        }),
+    );
+
+    functions.insert(
+        "values".to_string(),
+        StdFunc::new("values".to_string(), |args| {
+            let entries = map_argument("values", &args, 1)?;
+            Ok(Token::List(
+                entries.into_iter().map(|(_, value)| value).collect(),
+            ))
+        }),
+    );
+
+    functions.insert(
+        "remove".to_string(),
+        StdFunc::new("remove".to_string(), |args| {
+            let mut entries = map_argument("remove", &args, 2)?;
+            let key = args[1].get_value();
+            entries.retain(|(old, _)| !old.is_same_key(&key));
+            Ok(Token::Map(entries))
+        }),
+    );
+
+    functions.insert(
+        "push".to_string(),
+        StdFunc::new("push".to_string(), |args| {
+            if args.len() != 2 {
+                return Err("The push function needs 2 arguments.".to_string());
+            }
+            let mut items = match args[0].get_value() {
+                Token::List(items) => items,
+                value => {
+                    return Err(format!(
+                        "The first argument of push must be a <List> but it's <{}>.",
+                        Types::from(&value).name_for(&value)
+                    ))
+                }
+            };
+            let value = args[1].get_value();
+            let item_type = match Types::list_of(&items) {
+                Types::List(item_type) => *item_type,
+                _ => Types::Inferred,
+            };
+            let value_type = Types::from(&value);
+            if item_type != Types::Inferred && !value_type.can_convert(&item_type) {
+                return Err(format!(
+                    "The push function can't add a <{value_type}> to a <List<{item_type}>>."
+                ));
+            }
+            items.push(value);
+            Ok(Token::List(items))
+        }),
+    );
+
+    functions
+}
@@ -6,18 +6,14 @@ use std::collections::HashMap;
 
 pub const DEBUG_LIST: &[&str] = &["showVars"];
 
-fn lengths_of_vars(vars: Vec<String>) -> [usize; 5] {
+fn lengths_of_vars(vars: &[Vec<String>]) -> [usize; 5] {
     let mut largest_id: usize = 0;
     let mut largest_name: usize = 0;
     let mut largest_type: usize = 0;
     let mut largest_value: usize = 0;
     let mut largest_scope: usize = 0;
 
-    let values = vars
-        .iter()
-        .map(|x| x.split(" - ").collect::<Vec<&str>>())
-        .collect::<Vec<Vec<&str>>>();
-    for key in values {
+    for key in vars {
         if key[0].to_string().len() > largest_id {
             largest_id = key[0].len();
         }
@@ -55,19 +51,20 @@ pub fn debug_functions() -> HashMap<String, StdFunc> {
             }
             let vars = args.iter().find(|x| *x.name == *"variables").unwrap();
             if let Token::List(vars) = *vars.value.clone().unwrap() {
-                let mut orders_vars = vars
+                // A row for each variable with its id, name, type, value and scope, they come
+                // already in order
+                let orders_vars = vars
                     .iter()
-                    .map(|x| x.to_string().replace("\"", ""))
-                    .collect::<Vec<String>>();
+                    .map(|row| match row {
+                        Token::List(cells) => cells
+                            .iter()
+                            .map(|cell| cell.to_string().replace("\"", ""))
+                            .collect(),
+                        _ => vec![String::new(); 5],
+                    })
+                    .collect::<Vec<Vec<String>>>();
 
-                orders_vars.sort_by_key(|s| {
-                    s.split(" - ")
-                        .next() // Tomamos la parte antes del guion
-                        .and_then(|num| num.parse::<u32>().ok()) // Convertimos a número
-                        .unwrap_or(u32::MAX) // En caso de error, asignamos un valor grande para evitar fallos
-                });
-
-                let lenghts = lengths_of_vars(orders_vars.clone());
+                let lenghts = lengths_of_vars(&orders_vars);
                 let string = format!("| {:width_id$} | {:width_name$} | {:width_type$} | {:width_value$} | {} |",
                         center("ID", lenghts[0]),
                         center("NAME", lenghts[1]/2),
@@ -83,8 +80,7 @@ pub fn debug_functions() -> HashMap<String, StdFunc> {
                         "{}", string.blue()
                     );
                 println!("{}", "-".repeat(string.len()).blue());
-                for s in &orders_vars {
-                    let string = s.split(" - ").collect::<Vec<&str>>();
+                for string in &orders_vars {
                     let txt = format!(
                         "| {:width_id$} | {:width_name$} | {:width_type$} | {:width_value$} | scope: {:width_scope$} |",
                         string[0],
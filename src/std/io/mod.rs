@@ -13,16 +13,15 @@ pub fn io_functions() -> HashMap<String, StdFunc> {
         StdFunc::new("print".to_string(), |args| {
             let mut txt = String::new();
             for arg in args {
+                let value = arg.get_value().to_string();
                 txt.push_str(&format!(
                     "{}",
-                    if arg.value.is_some() {
-                        if arg.var_type.is_numeric() {
-                            arg.value.unwrap().to_string().blue()
-                        } else {
-                            arg.value.unwrap().to_string().white()
-                        }
+                    if arg.value.is_none() {
+                        value.red()
+                    } else if arg.var_type.is_numeric() {
+                        value.blue()
                     } else {
-                        arg.default_value.unwrap().to_string().red()
+                        value.white()
                     }
                 ));
             }
@@ -37,16 +36,9 @@ pub fn io_functions() -> HashMap<String, StdFunc> {
             let mut txt = String::new();
             let len = args.len();
             for arg in args {
-                txt.push_str(&format!(
-                    "{:?}",
-                    if arg.value.is_some() {
-                        arg.value.unwrap()
-                    } else {
-                        arg.default_value.unwrap()
-                    }
-                ));
+                txt.push_str(&format!("{:?}", arg.get_value()));
                 if len > 1 {
-                    txt.push_str(" ");
+                    txt.push(' ');
                 }
             }
             println!("{txt}");
@@ -61,19 +53,14 @@ pub fn io_functions() -> HashMap<String, StdFunc> {
             let len = args.len();
             for arg in args {
                 if arg.var_type.is_numeric() {
-                    let value = if arg.value.is_some() {
-                        *(arg.value).clone().unwrap()
-                    } else {
-                        *(arg.default_value).clone().unwrap()
-                    };
+                    let value = arg.get_value();
 
                     let divide: &dyn Fn(String, bool) -> String = &|num, double| {
                         let mut result = String::new();
-                        let mut count = 0;
 
                         // Recorremos el string al revés, agregando puntos cada 3 caracteres
                         let chars = num.chars().rev();
-                        for c in chars {
+                        for (count, c) in chars.enumerate() {
                             if count > 0 && count % 3 == 0 {
                                 if count == 3 && double {
                                     result.push(',');
@@ -82,7 +69,6 @@ pub fn io_functions() -> HashMap<String, StdFunc> {
                                 }
                             }
                             result.push(c);
-                            count += 1;
                         }
                         let txt: String = result.chars().rev().collect(); // Revertimos el resultado final
                         txt.blue().to_string()
@@ -95,17 +81,10 @@ pub fn io_functions() -> HashMap<String, StdFunc> {
                         _ => (),
                     }
                 } else {
-                    txt.push_str(&format!(
-                        "{:?}",
-                        if arg.value.is_some() {
-                            arg.value.unwrap()
-                        } else {
-                            arg.default_value.unwrap()
-                        }
-                    ));
+                    txt.push_str(&format!("{:?}", arg.get_value()));
                 }
                 if len > 1 {
-                    txt.push_str(" ");
+                    txt.push(' ');
                 }
             }
             println!("{txt}");
@@ -1,6 +1,7 @@
 use crate::compiler::{elements::token::Token, function::Argument};
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
+pub(crate) mod collections;
 pub(crate) mod debug;
 pub(crate) mod io;
 pub(crate) mod math;
@@ -16,6 +17,7 @@ pub fn standard_library() -> HashMap<String, StdFunc> {
     functions.extend(string::string_functions());
     functions.extend(types::types_functions());
     functions.extend(debug::debug_functions());
+    functions.extend(collections::collections_functions());
 
     functions
 }
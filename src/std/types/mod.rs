@@ -1,4 +1,4 @@
-use crate::compiler::elements::token::Token;
+use crate::compiler::{elements::token::Token, types::Types};
 
 use super::StdFunc;
 use colored::Colorize;
@@ -16,13 +16,12 @@ pub fn types_functions() -> HashMap<String, StdFunc> {
             }
             let value = args[0].value.as_ref().unwrap();
 
-            match **value {
-                Token::Int32(_) => println!("{}", "<type: Int32>".blue()),
-                Token::Int64(_) => println!("{}", "<type: Int64>".blue()),
-                Token::String(_) => println!("{}", "<type: String>".blue()),
-                Token::Str(_) => println!("{}", "<type: Str>".blue()),
+            match &**value {
                 Token::Type(types) => println!("<type: {}>", types.to_string().blue()),
-                _ => todo!("implement std::types::types_functions::typeof"),
+                value => println!(
+                    "{}",
+                    format!("<type: {}>", Types::from(value).name_for(value)).blue()
+                ),
             }
             Ok(Token::Void)
         }),
@@ -43,9 +43,9 @@ impl fmt::Display for Double {
     }
 }
 
-impl Into<f64> for Double {
-    fn into(self) -> f64 {
-        *self
+impl From<Double> for f64 {
+    fn from(val: Double) -> Self {
+        *val
     }
 }
 
@@ -180,7 +180,7 @@ impl Mul<Int64> for Double {
     type Output = Double;
 
     fn mul(self, rhs: Int64) -> Self::Output {
-        let result = *self as f64 * *rhs as f64;
+        let result = *self * *rhs as f64;
         Self::Output::new(result)
     }
 }
@@ -189,7 +189,7 @@ impl Div for Double {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
-        let result = *self as f64 / *rhs as f64;
+        let result = *self / *rhs;
         Self { data: result }
     }
 }
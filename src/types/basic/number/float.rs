@@ -42,9 +42,9 @@ impl fmt::Display for Float {
     }
 }
 
-impl Into<f32> for Float {
-    fn into(self) -> f32 {
-        *self
+impl From<Float> for f32 {
+    fn from(val: Float) -> Self {
+        *val
     }
 }
 
@@ -152,7 +152,7 @@ impl Mul<Int64> for Float {
     type Output = Float;
 
     fn mul(self, rhs: Int64) -> Self::Output {
-        let result = *self as f32 * *rhs as f32;
+        let result = *self * *rhs as f32;
         Self::Output::new(result)
     }
 }
@@ -161,7 +161,7 @@ impl Div for Float {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
-        let result = *self as f32 / *rhs as f32;
+        let result = *self / *rhs;
         Self { data: result }
     }
 }
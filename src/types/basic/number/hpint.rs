@@ -115,8 +115,7 @@ where
     type Output = Self;
 
     fn add(self, rhs: T) -> Self::Output {
-        let result = Number::add(&self, rhs);
-        result
+        Number::add(&self, rhs)
     }
 }
 
@@ -130,8 +129,7 @@ impl<T: Number> Sub<T> for HPInt {
     type Output = Self;
 
     fn sub(self, rhs: T) -> Self::Output {
-        let result = Number::sub(&self, rhs);
-        result
+        Number::sub(&self, rhs)
     }
 }
 
@@ -145,8 +143,7 @@ impl<T: Number> Mul<T> for HPInt {
     type Output = Self;
 
     fn mul(self, rhs: T) -> Self::Output {
-        let result = Number::mul(&self, rhs);
-        result
+        Number::mul(&self, rhs)
     }
 }
 
@@ -160,8 +157,7 @@ impl<T: Number> Div<T> for HPInt {
     type Output = Double;
 
     fn div(self, rhs: T) -> Self::Output {
-        let result = Number::div(&self, rhs);
-        result
+        Number::div(&self, rhs)
     }
 }
 
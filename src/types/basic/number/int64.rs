@@ -37,9 +37,9 @@ impl fmt::Display for Int64 {
     }
 }
 
-impl Into<f64> for Int64 {
-    fn into(self) -> f64 {
-        self.data as f64
+impl From<Int64> for f64 {
+    fn from(val: Int64) -> Self {
+        val.data as f64
     }
 }
 
@@ -3,7 +3,7 @@
 pub fn pad_end(string: &str, length: usize) -> String {
     let mut txt = string.to_string();
     while txt.len() < length {
-        txt.push_str(" ");
+        txt.push(' ');
     }
 
     txt
@@ -14,7 +14,7 @@ pub fn pad_end(string: &str, length: usize) -> String {
 pub fn pad_start(string: &str, length: usize) -> String {
     let mut txt = String::new();
     while (string.len() + txt.len()) < length {
-        txt.push_str(" ");
+        txt.push(' ');
     }
     txt + string
 }
@@ -24,7 +24,7 @@ pub fn pad_start(string: &str, length: usize) -> String {
 pub fn center(string: &str, length: usize) -> String {
     let mut txt = String::new();
     while txt.len() < length {
-        txt.push_str(" ");
+        txt.push(' ');
     }
     txt.clone() + string + &txt
 }
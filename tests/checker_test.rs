@@ -0,0 +1,510 @@
+use atlas_lang::compiler::{
+    checker::Checker,
+    error::{function_error::FunctionError, parse_error::ParseError, spanned::Located},
+    lexer::Lexer,
+    parser::{Parser, MAX_NESTING},
+    span::Span,
+    types::Types,
+};
+
+/// The errors found by the checker, without their spans
+fn check(code: &str) -> Vec<String> {
+    let tokens = Lexer::new(code.trim()).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    match Checker::new().check(&program) {
+        Ok(()) => vec![],
+        Err(errors) => errors
+            .iter()
+            .map(|error| error.kind().to_string())
+            .collect(),
+    }
+}
+
+#[test]
+fn valid_program_test() {
+    let code = "
+    struct Point { x: Int32, y: Int32 }
+    enum Shape { Circle(Double), Empty }
+
+    func area(shape: Shape) -> Double {
+        return match shape {
+            Circle(r) => r * r * 3.14,
+            Empty => 0.0,
+        }
+    }
+
+    func sum(...values: Int32) -> Int32 {
+        var total = 0
+        for value in values {
+            total += value
+        }
+        return total
+    }
+
+    var point = Point { x: 1, y: 2 }
+    point.x = sum(1, 2, 3)
+    var names: List<Str> = []
+    var double = |x| x * 2
+    print(area(Circle(1.5)), double(point.y), names)
+    ";
+
+    assert_eq!(check(code), Vec::<String>::new());
+}
+
+#[test]
+fn collected_errors_test() {
+    let code = "
+    var x: Int32 = 'a'
+    const c = 1
+    c = 2
+    var s = 'a' - 1
+    print(nope)
+    ";
+
+    let errors = check(code);
+    assert_eq!(
+        errors,
+        vec![
+            "TypeError: The type of 'x' must be <Int32> but it's <Str>.".to_string(),
+            "ConstReassignment: 'c' is a constant, its value can't change.".to_string(),
+            "InvalidOperation: You can't '-' a Str with a 'Int32'.".to_string(),
+            "UndefinedVariable: The variable 'nope' doesn't exist.".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn const_redeclaration_test() {
+    let code = "const l = 1\nvar l = 3\nl = 4\nif true {\n  var l = 5\n}";
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let errors = Checker::new().check(&program).unwrap_err();
+
+    // Only the declaration in the same scope is an error, the block can shadow the constant
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(matches!(
+        errors[0].kind(),
+        ParseError::ConstReassignment { declaration, span, .. }
+            if *declaration == Span::new(0, 0, 11) && *span == Span::new(0, 12, 21)
+    ));
+}
+
+#[test]
+fn nesting_test() {
+    // The deepest code that the parser accepts is checked without overflowing the stack
+    let depth = MAX_NESTING - 2;
+    let code = format!(
+        "var x = {}1{}\nvar xs = {}{}",
+        "(".repeat(depth),
+        ")".repeat(depth),
+        "[".repeat(depth),
+        "]".repeat(depth)
+    );
+    assert_eq!(check(&code), Vec::<String>::new());
+}
+
+#[test]
+fn operator_test() {
+    let code = "
+    var a = 1 > 'a'
+    var b = [1] + 2.5
+    var c = 1 + 2
+    var d: Str = c - 2
+    var e = ~1.5
+    if 'text' {
+    }
+    ";
+
+    let errors = check(code);
+    assert_eq!(errors.len(), 5, "{errors:?}");
+    assert_eq!(errors[0], "You can't compare a 'Int32' with a 'Str'");
+    assert_eq!(
+        errors[2],
+        "TypeError: The type of 'd' must be <Str> but it's <Int32>."
+    );
+    assert_eq!(
+        errors[3],
+        "InvalidOperation: You can't apply '~' to a Double."
+    );
+}
+
+#[test]
+fn integer_widening_test() {
+    // `*` gives an `Int64` when the product doesn't fit in an `Int32`, the checker can't know it
+    let code = "var b = 2147483647 * 2\nvar s = 1 + 2\nvar p = 2 ** 40\nvar w: Int64 = 3 * 4\n";
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut checker = Checker::new();
+    assert!(checker.check(&program).is_ok());
+
+    let type_of = |name: &str| {
+        checker
+            .symbols()
+            .iter()
+            .find(|symbol| &*symbol.name == name)
+            .map(|symbol| symbol.var_type.clone())
+            .unwrap()
+    };
+    assert_eq!(type_of("b"), Types::Inferred);
+    assert_eq!(type_of("p"), Types::Inferred);
+    assert_eq!(type_of("s"), Types::Int32);
+}
+
+#[test]
+fn argument_test() {
+    let code = "
+    func f(a: Int32, b: Int32 = 2) -> Int32 {
+        return a + b
+    }
+    f('x')
+    f(1, c: 3)
+    f(b: 1)
+    f(1, 2, 3)
+    f(1, a: 2)
+    print(1, end: 2)
+    ";
+
+    let tokens = Lexer::new(code.trim()).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let errors = Checker::new().check(&program).unwrap_err();
+    let kinds: Vec<&FunctionError> = errors
+        .iter()
+        .map(|error| match error.kind() {
+            ParseError::Function(error) => error.kind(),
+            error => panic!("unexpected error {error}"),
+        })
+        .collect();
+
+    assert!(
+        matches!(kinds[0], FunctionError::ArgumentType { argument, found, .. } if argument == "a" && found == "Str")
+    );
+    assert!(matches!(kinds[1], FunctionError::UnknownArgument { argument, .. } if argument == "c"));
+    assert!(matches!(kinds[2], FunctionError::MissingArgument { argument, .. } if argument == "a"));
+    assert!(matches!(kinds[3], FunctionError::InvalidNumberOfArgs(_)));
+    assert!(
+        matches!(kinds[4], FunctionError::RepeatedArgument { argument, .. } if argument == "a")
+    );
+    assert!(
+        matches!(kinds[5], FunctionError::UnknownArgument { function, .. } if function == "print")
+    );
+    assert_eq!(kinds.len(), 6);
+}
+
+#[test]
+fn return_test() {
+    let code = "
+    func name() -> Int32 {
+        return 'name'
+    }
+    func maybe(a: Int32) -> Int32 {
+        if a > 1 {
+            return a
+        }
+    }
+    func always(a: Int32) -> Int32 {
+        if a > 1 {
+            return a
+        } else {
+            return 0
+        }
+    }
+    ";
+
+    assert_eq!(
+        check(code),
+        vec![
+            "DifferentReturnType: The function 'name' must return <Int32> but it returns <Str>.",
+            "DifferentReturnType: The function 'maybe' must return <Int32> but it can finish without a 'return'.",
+        ]
+    );
+}
+
+#[test]
+fn unreachable_code_test() {
+    let code = "
+    func f() -> Int32 {
+        return 1
+        print('never')
+    }
+    if false {
+        print(1)
+    }
+    while true {
+        break
+        print(2)
+    }
+    var m = match 1 {
+        n => n,
+        2 => 3,
+    }
+    ";
+
+    let errors = check(code);
+    assert_eq!(errors.len(), 4, "{errors:?}");
+    assert!(errors
+        .iter()
+        .all(|error| error.starts_with("UnreachableCode:")));
+}
+
+#[test]
+fn inference_test() {
+    let code = "
+    var xs = []
+    xs.push(1)
+    var m = {}
+    m['a'] = 1.5
+    func twice(x) -> Int32 {
+        return x * 2
+    }
+    func fill(list, value: Str) {
+        list.push(value)
+    }
+    var ys = []
+    fill(ys, 'b')
+    twice(3)
+    xs.push('a')
+    xs = ['b']
+    ";
+
+    let tokens = Lexer::new(code.trim()).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut checker = Checker::new();
+    let errors = checker.check(&program).unwrap_err();
+    let type_of = |name: &str| {
+        checker
+            .symbols()
+            .iter()
+            .find(|symbol| &*symbol.name == name)
+            .map(|symbol| symbol.var_type.to_string())
+            .unwrap()
+    };
+
+    assert_eq!(type_of("xs"), "List<Int32>");
+    assert_eq!(type_of("m"), "Map<Str, Double>");
+    assert_eq!(type_of("x"), "Int32");
+    assert_eq!(type_of("list"), "List<Str>");
+    assert_eq!(type_of("ys"), "List<Str>");
+
+    // Once inferred the type is checked like a declared one
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].kind().to_string(),
+        "TypeError: The push function can't add a <Str> to a <List<Int32>>."
+    );
+    assert_eq!(
+        errors[1].kind().to_string(),
+        "TypeError: The type of 'xs' must be <List<Int32>> but it's <List<Str>>."
+    );
+}
+
+#[test]
+fn collection_functions_test() {
+    // `push` and `remove` return the changed collection, with a variable or with a value
+    let code = "
+    var xs = [1]
+    var ys = push(xs, 2)
+    var zs = push([1.5], 2.5)
+    var m = { 'a': 1 }
+    var n = remove(m, 'a')
+    var s: Str = remove({ 'b': 2 }, 'b')
+    ";
+
+    let tokens = Lexer::new(code.trim()).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut checker = Checker::new();
+    let errors = checker.check(&program).unwrap_err();
+    let type_of = |name: &str| {
+        checker
+            .symbols()
+            .iter()
+            .find(|symbol| &*symbol.name == name)
+            .map(|symbol| symbol.var_type.to_string())
+            .unwrap()
+    };
+
+    assert_eq!(type_of("ys"), "List<Int32>");
+    assert_eq!(type_of("zs"), "List<Double>");
+    assert_eq!(type_of("n"), "Map<Str, Int32>");
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert_eq!(
+        errors[0].kind().to_string(),
+        "TypeError: The type of 's' must be <Str> but it's <Map<Str, Int32>>."
+    );
+}
+
+#[test]
+fn inference_order_test() {
+    // `a` gives a type to `xs` before the body of `b` does, the declarations go in order
+    let code = "
+    func a(list) {
+        list.push(1)
+        b(list)
+    }
+    func b(xs) {
+        xs.push('s')
+    }
+    ";
+
+    for _ in 0..20 {
+        assert_eq!(
+            check(code),
+            vec!["TypeError: The push function can't add a <Str> to a <List<Int32>>."]
+        );
+    }
+}
+
+#[test]
+fn branch_assignment_test() {
+    // The branch may not run, so after it `x` can be a number or a text
+    let code = "
+    var x = 1
+    var c = 3
+    if c > 5 {
+        x = 's'
+    }
+    print(x - 2)
+    var y = 1
+    y = 's'
+    print(y - 2)
+    ";
+
+    assert_eq!(
+        check(code),
+        vec!["InvalidOperation: You can't '-' a Str with a 'Int32'."]
+    );
+}
+
+#[test]
+fn hover_test() {
+    let code = "var xs = []\nxs.push(1)\nvar n = xs[0] + 1\n";
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut checker = Checker::new();
+    assert!(checker.check(&program).is_ok());
+
+    // The declaration shows the type inferred with the uses that come after it
+    let hover = checker.hover(0, 4).unwrap();
+    assert_eq!(hover.to_string(), "xs: List<Int32>");
+    let hover = checker.hover(0, code.find("xs[0]").unwrap()).unwrap();
+    assert_eq!(hover.to_string(), "xs: List<Int32>");
+    let hover = checker.hover(0, code.find("1\n").unwrap()).unwrap();
+    assert_eq!((hover.name, hover.var_type), (None, Types::Int32));
+    assert_eq!(checker.hover(0, code.len()), None);
+}
+
+#[test]
+fn generics_test() {
+    let code = "
+    struct Pair<A, B> { first: A, second: B }
+    enum Maybe<T> { Just(T), Nothing }
+    func first<T>(xs: List<T>) -> T {
+        return xs[0]
+    }
+    func or<T>(m: Maybe<T>, default: T) -> T {
+        return match m {
+            Just(value) => value,
+            Nothing => default,
+        }
+    }
+    func wrong<T>(x: T) -> T {
+        return 1
+    }
+    var n = first([1, 2])
+    var p = Pair { first: n, second: 'a' }
+    var s = p.second
+    var o = or(Just(2.5), 1.0)
+    var e = or(Just(1), 'zero')
+    var q: Pair<Int32> = p
+    var m: Maybe<Str> = Just(1)
+    ";
+
+    let tokens = Lexer::new(code.trim()).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut checker = Checker::new();
+    let errors: Vec<String> = checker
+        .check(&program)
+        .unwrap_err()
+        .iter()
+        .map(|error| error.kind().to_string())
+        .collect();
+    let type_of = |name: &str| {
+        checker
+            .symbols()
+            .iter()
+            .find(|symbol| &*symbol.name == name)
+            .map(|symbol| symbol.var_type.to_string())
+            .unwrap()
+    };
+
+    assert_eq!(type_of("n"), "Int32");
+    assert_eq!(type_of("p"), "Pair<Int32, Str>");
+    assert_eq!(type_of("s"), "Str");
+    assert_eq!(type_of("o"), "Double");
+    assert_eq!(type_of("value"), "T");
+    assert_eq!(
+        errors[..3],
+        [
+            "DifferentReturnType: The function 'wrong' must return <T> but it returns <Int32>.",
+            "ArgumentType: The argument 'default' of 'or' must be <Int32> but it's <Str>.",
+            "TypeError: The type 'Pair' has 2 type parameters but it receives 1.",
+        ]
+    );
+    assert_eq!(
+        errors.last().unwrap(),
+        "TypeError: The type of 'm' must be <Maybe<Str>> but it's <Maybe<Int32>>."
+    );
+}
+
+#[test]
+fn optional_test() {
+    let code = "
+    struct Node { value: Int32, next: Node? }
+    func find(x: Int32) -> Int32? {
+        if x > 0 {
+            return x
+        }
+        return none
+    }
+    var found = find(1) ?? 0
+    var list = Node { value: 1, next: none }
+    var next = list.next?.value
+    var maybe: Str? = none
+    maybe = 'a'
+    var sum = find(1) + 1
+    var n: Int32 = find(2)
+    var value = list.next.value
+    var wrong = find(3) ?? 'zero'
+    ";
+
+    let tokens = Lexer::new(code.trim()).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut checker = Checker::new();
+    let errors: Vec<String> = checker
+        .check(&program)
+        .unwrap_err()
+        .iter()
+        .map(|error| error.kind().to_string())
+        .collect();
+    let type_of = |name: &str| {
+        checker
+            .symbols()
+            .iter()
+            .find(|symbol| &*symbol.name == name)
+            .map(|symbol| symbol.var_type.to_string())
+            .unwrap()
+    };
+
+    assert_eq!(type_of("found"), "Int32");
+    assert_eq!(type_of("next"), "Option<Int32>");
+    assert_eq!(type_of("maybe"), "Option<Str>");
+    assert_eq!(
+        errors,
+        vec![
+            "TypeError: The operator '+' can't use a value that can be 'none', give it a default value with '??'.",
+            "TypeError: The type of 'n' must be <Int32> but it's <Option<Int32>>.",
+            "TypeError: The value can be 'none', use '?.value' to get the field only when it isn't 'none'.",
+            "TypeError: The default value of '??' must be <Int32> but it's <Str>.",
+        ]
+    );
+}
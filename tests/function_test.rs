@@ -1,6 +1,13 @@
 use std::{cell::RefCell, rc::Rc};
 
-use atlas_lang::compiler::{elements::token::Token, lexer::Lexer, parser::Parser};
+use atlas_lang::compiler::{
+    elements::token::Token,
+    error::{function_error::FunctionError, parse_error::ParseError, spanned::Located},
+    evaluator::Evaluator,
+    function_table::{FunctionTable, DEFAULT_MAX_CALL_DEPTH},
+    lexer::Lexer,
+    parser::Parser,
+};
 
 #[test]
 fn create_function_test() {
@@ -12,19 +19,20 @@ fn create_function_test() {
     .trim();
 
     let mut lex = Lexer::new(code);
-    let tokens = lex.lex();
+    let tokens = lex.lex().unwrap();
     println!("{tokens:?}");
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program);
 
     println!("\nparse: {parse:?}");
 
-    for (name, func) in parser.get_functions() {
+    for (name, func) in evaluator.get_functions() {
         if name == "hello" {
             let result = func.call(
                 vec![],
-                Rc::new(RefCell::new(parser.get_variable_table())),
-                Rc::new(RefCell::new(parser.get_function_table())),
+                Rc::new(RefCell::new(evaluator.get_variable_table())),
+                Rc::new(RefCell::new(evaluator.get_function_table())),
             );
             println!("result: {result:?}");
             assert_eq!(result.unwrap(), Token::Void);
@@ -42,19 +50,430 @@ fn std_function_test() {
     .trim();
 
     let mut lex = Lexer::new(code);
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let _ = parser.parse();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let _ = evaluator.evaluate(&program);
 
-    for (name, func) in parser.get_functions() {
+    for (name, func) in evaluator.get_functions() {
         if name == "hello" {
             let result = func.call(
                 vec![],
-                Rc::new(RefCell::new(parser.get_variable_table())),
-                Rc::new(RefCell::new(parser.get_function_table())),
+                Rc::new(RefCell::new(evaluator.get_variable_table())),
+                Rc::new(RefCell::new(evaluator.get_function_table())),
             );
             println!("result: {result:?}");
             assert_eq!(result.unwrap(), Token::Void);
         }
     }
 }
+
+#[test]
+fn return_test() {
+    let code = "
+    func add(a: Int32 = 2, b: Int32 = 3) -> Int32 {
+        return a + b
+    }
+    func first_over(limit: Int32 = 3) -> Int64 {
+        for i in 0..10 {
+            if i > limit {
+                return i
+            }
+        }
+        return 0
+    }
+    add()
+    first_over()
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let result = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(result, vec![Token::Int32(5.into()), Token::Int64(4.into())]);
+}
+
+#[test]
+fn return_type_error_test() {
+    let code = "
+    func name() -> Int32 {
+        return \"atlas\"
+    }
+    func nothing() -> Int32 {
+        var x = 1
+    }
+    func void() {
+        return 1
+    }
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    evaluator.evaluate(&program).unwrap();
+
+    for (name, func) in evaluator.get_functions() {
+        if name == "print" {
+            continue;
+        }
+        let result = func.call(
+            vec![],
+            Rc::new(RefCell::new(evaluator.get_variable_table())),
+            Rc::new(RefCell::new(evaluator.get_function_table())),
+        );
+        assert!(result.is_err(), "{name} should fail");
+    }
+}
+
+#[test]
+fn return_outside_function_test() {
+    let tokens = Lexer::new("var x = 1\nreturn x\n").lex().unwrap();
+
+    assert!(Parser::new(tokens).parse().is_err())
+}
+
+#[test]
+fn recursion_test() {
+    let code = "
+    func fact(n: Int64) -> Int64 {
+        if n <= 1 {
+            return 1
+        }
+        return n * fact(n - 1)
+    }
+    func is_even(n: Int32) -> Boolean {
+        if n == 0 {
+            return true
+        }
+        return is_odd(n - 1)
+    }
+    func is_odd(n: Int32) -> Boolean {
+        if n == 0 {
+            return false
+        }
+        return is_even(n - 1)
+    }
+    fact(20)
+    is_even(10)
+    is_odd(10)
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let result = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            Token::Int64(2432902008176640000i64.into()),
+            Token::Boolean(true),
+            Token::Boolean(false)
+        ]
+    );
+    assert!(evaluator.get_function_table().call_stack().is_empty());
+}
+
+#[test]
+fn stack_overflow_test() {
+    let code = "
+    func forever(n: Int32) -> Int32 {
+        return forever(n + 1)
+    }
+    forever(0)
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let functions = Rc::new(RefCell::new(FunctionTable::with_max_call_depth(50)));
+    let mut evaluator = Evaluator::new(None, Some(functions.clone()));
+    let error = evaluator.evaluate(&program).unwrap_err();
+
+    assert!(matches!(
+        error.kind(),
+        ParseError::Function(error) if matches!(
+            error.kind(),
+            FunctionError::StackOverflow { max_depth: 50, function } if function == "forever"
+        )
+    ));
+    assert!(functions.borrow().call_stack().is_empty());
+}
+
+#[test]
+fn nested_stack_overflow_test() {
+    // Each call goes through several loops, blocks and expressions before the next one
+    let code = "
+    func nested(n: Int32) -> Int32 {
+        for i in 0..1 {
+            while true {
+                if n > 0 {
+                    var r = match n {
+                        _ => ((((nested(n - 1) + 1))))
+                    }
+                    return r
+                }
+                return 0
+            }
+        }
+        return 0
+    }
+    nested(5000)
+    "
+    .trim();
+
+    // The test thread has a small stack, the evaluator must not overflow it
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let error = evaluator.evaluate(&program).unwrap_err();
+
+    assert!(matches!(
+        error.kind(),
+        ParseError::Function(error) if matches!(
+            error.kind(),
+            FunctionError::StackOverflow { max_depth: DEFAULT_MAX_CALL_DEPTH, .. }
+        )
+    ));
+}
+
+#[test]
+fn first_class_function_test() {
+    let code = "
+    func apply(f: Function, x: Int32) -> Int32 {
+        return f(x)
+    }
+    func make_adder(n: Int32) -> Function {
+        return |x| x + n
+    }
+    func make_counter() -> Function {
+        var count = 0
+        return func() -> Int32 {
+            count += 1
+            count
+        }
+    }
+    var double = func(x: Int32) -> Int32 { x * 2 }
+    double(4)
+    apply(|x| x * 3, 5)
+    make_adder(5)(1)
+    var counter = make_counter()
+    counter()
+    counter()
+    var fact = |n: Int32| {
+        if n <= 1 {
+            return 1
+        }
+        n * fact(n - 1)
+    }
+    fact(5)
+    var names = keys
+    names({1: 2})
+    [double, make_adder(2)][1](1)
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let result = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            Token::Int32(8.into()),
+            Token::Int32(15.into()),
+            Token::Int32(6.into()),
+            Token::Int32(1.into()),
+            Token::Int32(2.into()),
+            Token::Int32(120.into()),
+            Token::List(vec![Token::Int32(1.into())]),
+            Token::Int32(3.into()),
+        ]
+    );
+}
+
+#[test]
+fn call_value_error_test() {
+    let code = "
+    var x = 1
+    x(2)
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let error = evaluator.evaluate(&program).unwrap_err();
+    assert!(matches!(error.kind(), ParseError::TypeError(_)));
+
+    let code = "
+    var triple = |x: Int32| x * 3
+    triple('hola')
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let error = evaluator.evaluate(&program).unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        ParseError::Function(error) if matches!(
+            error.kind(),
+            FunctionError::ArgumentType { argument, found, .. } if argument == "x" && found == "Str"
+        )
+    ));
+}
+
+#[test]
+fn named_variadic_test() {
+    let code = "
+    func sub(a: Int32, b: Int32 = 1) -> Int32 {
+        return a - b
+    }
+    func sum(first: Int32, ...rest: Int64) -> Int64 {
+        var total: Int64 = first
+        for n in rest {
+            total += n
+        }
+        return total
+    }
+    sub(b: 2, a: 10)
+    sub(5, b: 3)
+    sub(5)
+    sum(1)
+    sum(1, 2, 3, 4)
+    sum(first: 2, rest: [5])
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let result = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            Token::Int32(8.into()),
+            Token::Int32(2.into()),
+            Token::Int32(4.into()),
+            Token::Int64(1i64.into()),
+            Token::Int64(10i64.into()),
+            Token::Int64(7i64.into()),
+        ]
+    );
+}
+
+#[test]
+fn argument_error_test() {
+    let declarations = "
+    func sub(a: Int32, b: Int32 = 1) -> Int32 {
+        return a - b
+    }
+    func sum(...values: Int32) -> Int32 {
+        return 0
+    }
+    ";
+    let run = |call: &str| {
+        let tokens = Lexer::new(&format!("{declarations}\n{call}"))
+            .lex()
+            .unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut evaluator = Evaluator::new(None, None);
+        match evaluator.evaluate(&program).unwrap_err().kind() {
+            ParseError::Function(error) => error.kind().to_string(),
+            error => panic!("Expected an error of the call, found {error:?}"),
+        }
+    };
+
+    assert_eq!(
+        run("sub(1, 2, 3)"),
+        "InvalidNumberOfArgs: The function 'sub' accepts 2 arguments but it receives 3."
+    );
+    assert_eq!(
+        run("sub(b: 1)"),
+        "MissingArgument: The argument 'a' of 'sub' is missing."
+    );
+    assert_eq!(
+        run("sub(1, c: 2)"),
+        "UnknownArgument: The function 'sub' doesn't have an argument 'c'."
+    );
+    assert_eq!(
+        run("sub(1, a: 2)"),
+        "RepeatedArgument: The argument 'a' of 'sub' is given twice."
+    );
+    assert_eq!(
+        run("sum(1, 'x')"),
+        "ArgumentType: The argument 'values' of 'sum' must be <Int32> but it's <Str>."
+    );
+    assert_eq!(
+        run("print(end: 1)"),
+        "UnknownArgument: The function 'print' doesn't have an argument 'end'."
+    );
+
+    for code in [
+        "sub(a: 1, 2)",
+        "func f(...a: Int32, b: Int32) {\n}",
+        "func f(...a: Int32 = 1) {\n}",
+    ] {
+        let tokens = Lexer::new(code).lex().unwrap();
+        let error = Parser::new(tokens).parse().unwrap_err();
+        assert!(matches!(error.kind(), ParseError::SyntaxError(_)), "{code}");
+    }
+}
+
+#[test]
+fn generic_function_test() {
+    let code = "
+    func first<T>(xs: List<T>) -> T {
+        return xs[0]
+    }
+    func pick<T>(a: T, b: T, left: Boolean) -> T {
+        var chosen: T = b
+        if left {
+            chosen = a
+        }
+        return chosen
+    }
+    first([3, 4])
+    first([\"a\"])
+    pick(1, 2, true)
+    pick([1], [], false)
+    "
+    .trim();
+
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let result = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            Token::Int32(3.into()),
+            Token::String("a".into()),
+            Token::Int32(1.into()),
+            Token::List(vec![]),
+        ]
+    );
+
+    // Every value of a type parameter must have the type of the first one
+    let code = format!("{code}\npick(1, \"b\", true)");
+    let tokens = Lexer::new(&code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let error = Evaluator::new(None, None).evaluate(&program).unwrap_err();
+    let ParseError::Function(error) = error.kind() else {
+        panic!("unexpected error {error}");
+    };
+    assert!(matches!(
+        error.kind(),
+        FunctionError::ArgumentType { argument, expected, found, .. }
+            if argument == "b" && expected == "Int32" && found == "String"
+    ));
+}
@@ -10,7 +10,7 @@ fn parethesis_test() {
 
 hola + (10+10)",
     );
-    let tokens = lex.lex();
+    let tokens = lex.lex().unwrap();
 
     assert_eq!(
         tokens,
@@ -36,7 +36,7 @@ hola + (10+10)",
 #[test]
 fn brackets_test() {
     let mut lex = Lexer::new("[10+10]");
-    let tokens = lex.lex();
+    let tokens = lex.lex().unwrap();
 
     assert_eq!(
         tokens,
@@ -50,3 +50,32 @@ fn brackets_test() {
         ]
     );
 }
+
+#[test]
+fn crlf_test() {
+    let mut lex = Lexer::new("var hola = 100 # cien\r\n\r\nhola\r\n").with_trivia();
+    let tokens = lex.lex().unwrap();
+
+    assert_eq!(
+        tokens,
+        vec![
+            Token::Keyword(Keyword::Var),
+            Token::Identifier("hola".into()),
+            Token::Operator(Operator::Assign),
+            Token::Int32(100.into()),
+            Token::Comment("# cien".into()),
+            Token::NewLine,
+            Token::NewLine,
+            Token::Identifier("hola".into()),
+            Token::NewLine,
+            Token::EOF
+        ]
+    );
+
+    // The characters that can't be seen are shown escaped
+    let errors = Lexer::new("var a = 1\u{7}").lex().unwrap_err();
+    assert_eq!(
+        errors[0].to_string(),
+        "UnexpectedCharacter: '\\u{7}' isn't valid here."
+    );
+}
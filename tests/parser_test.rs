@@ -1,6 +1,7 @@
 use atlas_lang::{
     compiler::{
-        elements::token::Token, lexer::Lexer, parser::Parser, types::Types, variable::Variable,
+        ast::Item, elements::token::Token, error::spanned::Located, evaluator::Evaluator,
+        lexer::Lexer, parser::Parser, types::Types, variable::Variable,
     },
     types::basic::number::double::Double,
 };
@@ -12,15 +13,19 @@ fn assignation_test() {
     var hola = 10
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let _ = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let _ = evaluator.evaluate(&program).unwrap();
 
-    let result = vec![(
+    let result = [(
         "hola",
-        Variable::new("hola".to_string(), Types::Int32, Token::Int32(10.into()), 0),
+        Variable {
+            id: 1,
+            ..Variable::new("hola".to_string(), Types::Int32, Token::Int32(10.into()), 0)
+        },
     )];
-    let tuple = parser.get_variables();
+    let tuple = evaluator.get_variables();
 
     for (key, val) in tuple {
         assert_eq!(key, result[0].0);
@@ -36,9 +41,10 @@ fn add_test() {
     hola + 10
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
 
     assert_eq!(parse, vec![Token::Int32(20.into())])
 }
@@ -51,13 +57,14 @@ fn add_assign_test() {
     hola += 10
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
 
     assert_eq!(parse, vec![]);
 
-    let result = vec![(
+    let result = [(
         "hola",
         Variable {
             id: 1,
@@ -65,9 +72,11 @@ fn add_assign_test() {
             var_type: Types::Int32,
             value: Box::new(Token::Int32(20.into())),
             scope: 0,
+            mutable: true,
+            span: Default::default(),
         },
     )];
-    let tuple = parser.get_variables();
+    let tuple = evaluator.get_variables();
 
     for (key, val) in tuple {
         assert_eq!(key, result[0].0);
@@ -83,9 +92,10 @@ fn sub_test() {
     hola - 10
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
 
     assert_eq!(parse, vec![Token::Int32(0.into())])
 }
@@ -98,16 +108,20 @@ fn sub_assign_test() {
     hola -= 10
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
 
     assert_eq!(parse, vec![]);
-    let result = vec![(
+    let result = [(
         "hola",
-        Variable::new("hola".to_string(), Types::Int32, Token::Int32(0.into()), 0),
+        Variable {
+            id: 1,
+            ..Variable::new("hola".to_string(), Types::Int32, Token::Int32(0.into()), 0)
+        },
     )];
-    let tuple = parser.get_variables();
+    let tuple = evaluator.get_variables();
 
     for (key, val) in tuple {
         assert_eq!(key, result[0].0);
@@ -123,9 +137,10 @@ fn mul_test() {
     hola * 5
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
 
     assert_eq!(parse, vec![Token::Int32(50.into())])
 }
@@ -138,16 +153,20 @@ fn mul_assign_test() {
     hola *= 5
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
 
     assert_eq!(parse, vec![]);
-    let result = vec![(
+    let result = [(
         "hola",
-        Variable::new("hola".to_string(), Types::Int32, Token::Int32(50.into()), 0),
+        Variable {
+            id: 1,
+            ..Variable::new("hola".to_string(), Types::Int32, Token::Int32(50.into()), 0)
+        },
     )];
-    let tuple = parser.get_variables();
+    let tuple = evaluator.get_variables();
 
     for (key, val) in tuple {
         assert_eq!(key, result[0].0);
@@ -163,9 +182,10 @@ fn div_test() {
     hola / 2
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
 
     assert_eq!(parse, vec![Token::Double(Double::from(5))])
 }
@@ -178,21 +198,25 @@ fn div_assign_test() {
     hola /= 2
     ",
     );
-    let tokens = lex.lex();
-    let mut parser = Parser::new(tokens, None, None);
-    let parse = parser.parse().unwrap();
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
 
     assert_eq!(parse, vec![]);
-    let result = vec![(
+    let result = [(
         "hola",
-        Variable::new(
-            "hola".to_string(),
-            Types::Double,
-            Token::Double(Double::from(5)),
-            0,
-        ),
+        Variable {
+            id: 1,
+            ..Variable::new(
+                "hola".to_string(),
+                Types::Double,
+                Token::Double(Double::from(5)),
+                0,
+            )
+        },
     )];
-    let tuple = parser.get_variables();
+    let tuple = evaluator.get_variables();
 
     for (key, val) in tuple {
         assert_eq!(key, result[0].0);
@@ -208,9 +232,10 @@ fn div_assign_test() {
 //     hola // 3
 //     ",
 //     );
-//     let tokens = lex.lex();
-//     let mut parser = Parser::new(tokens, None, None);
-//     let parse = parser.parse().unwrap();
+//     let tokens = lex.lex().unwrap();
+//     let program = Parser::new(tokens).parse().unwrap();
+//     let mut evaluator = Evaluator::new(None, None);
+//     let parse = evaluator.evaluate(&program).unwrap();
 
 //     assert_eq!(parse, vec![Token::Int32((3).into())])
 // }
@@ -223,16 +248,17 @@ fn div_assign_test() {
 //     hola //= 3
 //     ",
 //     );
-//     let tokens = lex.lex();
-//     let mut parser = Parser::new(tokens, None, None);
-//     let parse = parser.parse().unwrap();
+//     let tokens = lex.lex().unwrap();
+//     let program = Parser::new(tokens).parse().unwrap();
+//     let mut evaluator = Evaluator::new(None, None);
+//     let parse = evaluator.evaluate(&program).unwrap();
 
 //     assert_eq!(parse, vec![]);
 //     let result = vec![(
 //         "hola",
 //         Variable::new("hola".to_string(), Types::Int32, Token::Int32(3.into()), 0),
 //     )];
-//     let tuple = parser.get_variables();
+//     let tuple = evaluator.get_variables();
 
 //     for (key, val) in tuple {
 //         assert_eq!(key, result[0].0);
@@ -248,9 +274,10 @@ fn div_assign_test() {
 //     hola % 3
 //     ",
 //     );
-//     let tokens = lex.lex();
-//     let mut parser = Parser::new(tokens, None, None);
-//     let parse = parser.parse().unwrap();
+//     let tokens = lex.lex().unwrap();
+//     let program = Parser::new(tokens).parse().unwrap();
+//     let mut evaluator = Evaluator::new(None, None);
+//     let parse = evaluator.evaluate(&program).unwrap();
 
 //     assert_eq!(parse, vec![Token::Int32(1.into())])
 // }
@@ -263,16 +290,17 @@ fn div_assign_test() {
 //     hola %= 3
 //     ",
 //     );
-//     let tokens = lex.lex();
-//     let mut parser = Parser::new(tokens, None, None);
-//     let parse = parser.parse().unwrap();
+//     let tokens = lex.lex().unwrap();
+//     let program = Parser::new(tokens).parse().unwrap();
+//     let mut evaluator = Evaluator::new(None, None);
+//     let parse = evaluator.evaluate(&program).unwrap();
 
 //     assert_eq!(parse, vec![]);
 //     let result = vec![(
 //         "hola",
 //         Variable::new("hola".to_string(), Types::Int32, Token::Int32(1.into()), 0),
 //     )];
-//     let tuple = parser.get_variables();
+//     let tuple = evaluator.get_variables();
 
 //     for (key, val) in tuple {
 //         assert_eq!(key, result[0].0);
@@ -288,15 +316,16 @@ fn div_assign_test() {
 //     hola ** 3
 //     ",
 //     );
-//     let tokens = lex.lex();
+//     let tokens = lex.lex().unwrap();
 //     for token in tokens
 //         .split(|x| *x == Token::NewLine)
 //         .filter(|x| !x.is_empty() && *x != [Token::EOF])
 //     {
 //         println!("tokens: {token:?}",);
 //     }
-//     let mut parser = Parser::new(tokens, None, None);
-//     let parse = parser.parse().unwrap();
+//     let program = Parser::new(tokens).parse().unwrap();
+//     let mut evaluator = Evaluator::new(None, None);
+//     let parse = evaluator.evaluate(&program).unwrap();
 
 //     assert_eq!(parse, vec![Token::Int32(8.into())])
 // }
@@ -309,25 +338,1005 @@ fn div_assign_test() {
 //     hola **= 3
 //     ",
 //     );
-//     let tokens = lex.lex();
+//     let tokens = lex.lex().unwrap();
 //     for token in tokens
 //         .split(|x| *x == Token::NewLine)
 //         .filter(|x| !x.is_empty() && *x != [Token::EOF])
 //     {
 //         println!("tokens: {token:?}",);
 //     }
-//     let mut parser = Parser::new(tokens, None, None);
-//     let parse = parser.parse().unwrap();
+//     let program = Parser::new(tokens).parse().unwrap();
+//     let mut evaluator = Evaluator::new(None, None);
+//     let parse = evaluator.evaluate(&program).unwrap();
 
 //     assert_eq!(parse, vec![]);
 //     let result = vec![(
 //         "hola",
 //         Variable::new("hola".to_string(), Types::Int32, Token::Int32(8.into()), 0),
 //     )];
-//     let tuple = parser.get_variables();
+//     let tuple = evaluator.get_variables();
 
 //     for (key, val) in tuple {
 //         assert_eq!(key, result[0].0);
 //         assert_eq!(val, result[0].1);
 //     }
 // }
+
+#[test]
+fn if_else_test() {
+    let mut lex = Lexer::new(
+        "
+    var hola = 10
+    if hola > 20 {
+        1
+    } else if hola > 5 {
+        2
+    }
+    else {
+        3
+    }
+    if false { 4 }
+    if 0.0 { 5 } else { 6 }
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(parse, vec![Token::Int32(2.into()), Token::Int32(6.into())])
+}
+
+#[test]
+fn if_scope_test() {
+    let mut lex = Lexer::new(
+        "
+    if true {
+        var hola = 10
+    }
+    hola
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+
+    assert!(evaluator.evaluate(&program).is_err())
+}
+
+#[test]
+fn while_test() {
+    let mut lex = Lexer::new(
+        "
+    var i = 0
+    var total = 0
+    while i < 5 {
+        i += 1
+        total += i
+    }
+    total
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(parse, vec![Token::Int32(15.into())])
+}
+
+#[test]
+fn while_break_continue_test() {
+    let mut lex = Lexer::new(
+        "
+    var i = 0
+    var total = 0
+    while true {
+        i += 1
+        if i % 2 == 0 {
+            continue
+        }
+        if i > 7 {
+            break
+        }
+        total += i
+    }
+    total
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    // 1 + 3 + 5 + 7
+    assert_eq!(parse, vec![Token::Int32(16.into())])
+}
+
+#[test]
+fn nested_while_test() {
+    let mut lex = Lexer::new(
+        "
+    var i = 0
+    var pairs = 0
+    while i < 4 {
+        var j = 0
+        while true {
+            if j >= i {
+                break
+            }
+            j += 1
+            pairs += 1
+        }
+        i += 1
+    }
+    pairs
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    // 0 + 1 + 2 + 3
+    assert_eq!(parse, vec![Token::Int32(6.into())]);
+    // `j` only lives in the body of the loop
+    let names: Vec<String> = evaluator
+        .get_variables()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert!(!names.contains(&"j".to_string()));
+}
+
+#[test]
+fn break_outside_loop_test() {
+    let mut lex = Lexer::new("var i = 0\nbreak\n");
+    let tokens = lex.lex().unwrap();
+
+    assert!(Parser::new(tokens).parse().is_err())
+}
+
+#[test]
+fn for_range_test() {
+    let mut lex = Lexer::new(
+        "
+    var total = 0
+    for i in 0..5 {
+        total += i
+    }
+    for i in 10..=0 step -5 {
+        total += i
+    }
+    total
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    // 0 + 1 + 2 + 3 + 4 + 10 + 5 + 0
+    assert_eq!(parse, vec![Token::Int32(25.into())]);
+    // `i` only lives in the body of the loop
+    assert_eq!(evaluator.get_variables().len(), 1);
+}
+
+#[test]
+fn for_list_test() {
+    let mut lex = Lexer::new(
+        "
+    var numbers = 1..4
+    var total = 1
+    for number in numbers {
+        if number == 3 {
+            continue
+        }
+        total *= number
+    }
+    total
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(parse, vec![Token::Int32(2.into())])
+}
+
+#[test]
+fn for_invalid_range_test() {
+    let mut lex = Lexer::new("for i in 0..1.5 {\n}");
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+
+    assert!(evaluator.evaluate(&program).is_err())
+}
+
+#[test]
+fn operator_precedence_test() {
+    let mut lex = Lexer::new(
+        "
+    1 + 2 > 2
+    (1 + 2) * 3
+    2 ** 3 ** 2
+    -2 ** 2
+    2 * 3 ** 2
+    10 - 4 - 3
+    1 + 1 == 2
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        parse,
+        vec![
+            Token::Boolean(true),
+            Token::Int32(9.into()),
+            Token::Int32(512.into()),
+            Token::Int32((-4).into()),
+            Token::Int32(18.into()),
+            Token::Int32(3.into()),
+            Token::Boolean(true),
+        ]
+    )
+}
+
+#[test]
+fn logical_test() {
+    let mut lex = Lexer::new(
+        "
+    1 < 2 && 3 > 2 || false
+    !true || 1 != 1
+    false && undefined
+    true || undefined
+    2 !== 2
+    !0
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        parse,
+        vec![
+            Token::Boolean(true),
+            Token::Boolean(false),
+            Token::Boolean(false),
+            Token::Boolean(true),
+            Token::Boolean(false),
+            Token::Boolean(true),
+        ]
+    )
+}
+
+#[test]
+fn bitwise_test() {
+    let mut lex = Lexer::new(
+        "
+    var flags = 0
+    flags |= 1 << 3
+    flags |= 1
+    flags ^= 8
+    flags <<= 2
+    flags
+    6 & 3
+    6 | 3 == 7
+    ~5
+    -16 >> 2
+    1 << 31
+    var big: Int64 = 3
+    big << 40
+    1 + 1 << 1
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        parse,
+        vec![
+            Token::Int32(4.into()),
+            Token::Int32(2.into()),
+            Token::Boolean(true),
+            Token::Int32((-6).into()),
+            Token::Int32((-4).into()),
+            Token::Int32(i32::MIN.into()),
+            Token::Int64(3298534883328i64.into()),
+            Token::Int32(4.into()),
+        ]
+    )
+}
+
+#[test]
+fn bitwise_error_test() {
+    for code in ["1.5 & 1", "\"a\" | 1", "~1.5", "1 << 32", "1 >> -1"] {
+        let tokens = Lexer::new(code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut evaluator = Evaluator::new(None, None);
+
+        assert!(evaluator.evaluate(&program).is_err(), "{code}");
+    }
+}
+
+#[test]
+fn unary_error_test() {
+    for (code, message) in [
+        ("~1.5", "InvalidOperation: You can't apply '~' to a Double."),
+        (
+            "-\"a\"",
+            "InvalidOperation: You can't apply '-' to a String.",
+        ),
+    ] {
+        let tokens = Lexer::new(code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut evaluator = Evaluator::new(None, None);
+
+        let error = evaluator.evaluate(&program).unwrap_err();
+        assert_eq!(error.to_string(), message);
+    }
+}
+
+#[test]
+fn list_test() {
+    let mut lex = Lexer::new(
+        "
+    var xs: List<Int64> = [1, 2, 3, 4, 5]
+    xs[0]
+    xs[-1]
+    xs[1..3]
+    xs[-2..=-1]
+    xs[4..=0 step -2]
+    xs[0] = 10
+    xs[1] += 5
+    xs[0] + xs[1]
+    var grid: List<List<Int32>> = [[1, 2], []]
+    grid[0][1] = 7
+    grid
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    let list = |items: &[i64]| Token::List(items.iter().map(|&n| Token::Int64(n.into())).collect());
+    assert_eq!(
+        parse,
+        vec![
+            Token::Int64(1.into()),
+            Token::Int64(5.into()),
+            list(&[2, 3]),
+            list(&[4, 5]),
+            list(&[5, 3, 1]),
+            Token::Int64(17.into()),
+            Token::List(vec![
+                Token::List(vec![Token::Int32(1.into()), Token::Int32(7.into())]),
+                Token::List(vec![]),
+            ]),
+        ]
+    )
+}
+
+#[test]
+fn list_error_test() {
+    for code in [
+        "var xs = [1, 2]\nxs[2]",
+        "var xs = [1, 2]\nxs[-3]",
+        "var xs = [1, 2]\nxs[0..3]",
+        "var xs = [1, 2]\nxs[0] = \"a\"",
+        "var xs = [1, 2]\nxs[\"a\"]",
+        "const xs = [1, 2]\nxs[0] = 3",
+        "var x = 1\nx[0]",
+        "[1, \"a\"]",
+        "var xs: List<String> = [1]",
+        // The declared type of the elements is kept when the list is empty
+        "var xs: List<Int32> = []\nxs.push(\"a\")",
+        "var xs = [1]\nxs = [\"a\"]",
+        "var m: Map<String, Int32> = {}\nm = { \"a\": \"b\" }",
+    ] {
+        let tokens = Lexer::new(code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut evaluator = Evaluator::new(None, None);
+
+        assert!(evaluator.evaluate(&program).is_err(), "{code}");
+    }
+}
+
+#[test]
+fn map_test() {
+    let mut lex = Lexer::new(
+        "
+    var ages: Map<String, Int64> = { \"ana\": 30, \"luis\": 25 }
+    ages[\"ana\"]
+    ages[\"eva\"] = 41
+    ages[\"luis\"] += 1
+    has(ages, \"eva\")
+    remove(ages, \"ana\")
+    has(ages, \"ana\")
+    keys(ages)
+    values(ages)
+    var names = \"\"
+    for name in { 'b': 1, 'a': 2 } {
+        names += name
+    }
+    names
+    {
+        var inner = 1
+    }
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        parse,
+        vec![
+            Token::Int64(30.into()),
+            Token::Boolean(true),
+            Token::Map(vec![
+                (Token::String("luis".into()), Token::Int64(26.into())),
+                (Token::String("eva".into()), Token::Int64(41.into())),
+            ]),
+            Token::Boolean(false),
+            Token::List(vec![
+                Token::String("luis".into()),
+                Token::String("eva".into())
+            ]),
+            Token::List(vec![Token::Int64(26.into()), Token::Int64(41.into())]),
+            Token::String("ba".into()),
+        ]
+    )
+}
+
+#[test]
+fn map_error_test() {
+    for code in [
+        "var m = { \"a\": 1 }\nm[\"b\"]",
+        "var m = { \"a\": 1 }\nm[1]",
+        "var m = { \"a\": 1 }\nm[\"b\"] = \"x\"",
+        "var m: Map<String, Int32> = {}\nm[\"a\"] = \"x\"",
+        "{ \"a\": 1, \"b\": \"x\" }",
+        "{ 1.5: 1 }",
+        "has(1, 2)",
+    ] {
+        let tokens = Lexer::new(code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut evaluator = Evaluator::new(None, None);
+
+        assert!(evaluator.evaluate(&program).is_err(), "{code}");
+    }
+}
+
+#[test]
+fn shadowed_builtin_test() {
+    let mut lex = Lexer::new(
+        "
+    var list = [1]
+    var push = |xs, x| 0
+    push(list, 2)
+    list
+    var m = { 'a': 1 }
+    var remove = |map, key| 'kept'
+    remove(m, 'a')
+    m
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    // A variable with the name of a builtin doesn't store its result in the first argument
+    assert_eq!(parse[0], Token::Int32(0.into()));
+    assert_eq!(parse[1].to_string(), "[1]");
+    assert_eq!(parse[2], Token::Str("kept".into()));
+    assert_eq!(parse[3].to_string(), "{a: 1}");
+}
+
+#[test]
+fn push_test() {
+    let mut lex = Lexer::new(
+        "
+    var xs = []
+    xs.push(1)
+    push(xs, 2)
+    var m = {}
+    m[\"a\"] = 1.5
+    func twice(x) -> Int32 {
+        return x * 2
+    }
+    xs
+    twice(xs[1])
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        parse,
+        vec![
+            Token::List(vec![Token::Int32(1.into())]),
+            Token::List(vec![Token::Int32(1.into()), Token::Int32(2.into())]),
+            Token::List(vec![Token::Int32(1.into()), Token::Int32(2.into())]),
+            Token::Int32(4.into()),
+        ]
+    );
+
+    // The type of the empty collections is taken from the values added later
+    let types: Vec<(String, Types)> = evaluator
+        .get_variables()
+        .into_iter()
+        .map(|(name, var)| (name, var.var_type))
+        .collect();
+    assert!(types.contains(&("xs".into(), Types::List(Box::new(Types::Int32)))));
+    assert!(types.contains(&(
+        "m".into(),
+        Types::Map(Box::new(Types::String), Box::new(Types::Double))
+    )));
+
+    // The call returns the new collection, a variable passed is changed too
+    let code = "var xs = [1]\nvar ys = push(xs, 2)\nxs\npush([1], 2)\nvar m = { 'a': 1, 'b': 2 }\nremove(m, 'a')\nremove({ 'c': 3 }, 'c')";
+    let program = Parser::new(Lexer::new(code).lex().unwrap())
+        .parse()
+        .unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+    let parse: Vec<String> = parse.iter().map(Token::to_string).collect();
+    assert_eq!(parse, ["[1, 2]", "[1, 2]", "{b: 2}", "{}"]);
+    let ys = evaluator.get_variable_table().get("ys").unwrap();
+    assert_eq!(ys.value.to_string(), "[1, 2]");
+    assert_eq!(ys.var_type, Types::List(Box::new(Types::Int32)));
+
+    let code = "var xs = [1]\nxs.push('a')";
+    let program = Parser::new(Lexer::new(code).lex().unwrap())
+        .parse()
+        .unwrap();
+    assert!(Evaluator::new(None, None).evaluate(&program).is_err());
+}
+
+#[test]
+fn struct_test() {
+    let mut lex = Lexer::new(
+        "
+    struct Point {
+        x: Double
+        y: Double
+    }
+    struct Line { start: Point, end: Point }
+    var p = Point { y: 2.0, x: 1.0 }
+    p.x
+    p.y = 5.5
+    p.y += 1.0
+    p
+    var line = Line {
+        start: p,
+        end: Point { x: 0.0, y: 0.0 }
+    }
+    line.end.x = 3.0
+    line.end.x + line.start.y
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        parse,
+        vec![
+            Token::Double(Double::new(1.0)),
+            Token::Struct {
+                name: "Point".into(),
+                fields: vec![
+                    ("x".into(), Token::Double(Double::new(1.0))),
+                    ("y".into(), Token::Double(Double::new(6.5))),
+                ]
+            },
+            Token::Double(Double::new(9.5)),
+        ]
+    );
+    assert_eq!(parse[1].to_string(), "Point { x: 1.0, y: 6.5 }");
+    let line = evaluator.get_variable_table().get("line").unwrap().clone();
+    assert_eq!(line.var_type, Types::Custom("Line".into(), vec![]));
+}
+
+#[test]
+fn struct_error_test() {
+    let point = "struct Point { x: Int32, y: Int32 }\n";
+    for code in [
+        "Point { x: 1, y: 2 }",
+        "Point { x: 1 }",
+        "Point { x: 1, y: 2, z: 3 }",
+        "Point { x: 1, y: \"2\" }",
+        "Point { x: 1, x: 2, y: 3 }",
+        "var p = Point { x: 1, y: 2 }\np.z",
+        "var p = Point { x: 1, y: 2 }\np.x = 1.5",
+        "var n = 1\nn.x",
+        "struct Point { x: Int32 }",
+        "struct Line { start: Pointt }",
+    ] {
+        // The first one is only an error without the declaration
+        let code = if code.starts_with("Point { x: 1, y: 2 }") {
+            code.to_string()
+        } else {
+            format!("{point}{code}")
+        };
+        let tokens = Lexer::new(&code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut evaluator = Evaluator::new(None, None);
+
+        assert!(evaluator.evaluate(&program).is_err(), "{code}");
+    }
+
+    for code in ["struct Empty {}", "struct Point { x: Int32, x: Int32 }"] {
+        let tokens = Lexer::new(code).lex().unwrap();
+        assert!(Parser::new(tokens).parse().is_err(), "{code}");
+    }
+}
+
+#[test]
+fn enum_match_test() {
+    let mut lex = Lexer::new(
+        "
+    enum Shape {
+        Circle(Double)
+        Rect(Double, Double)
+        Empty
+    }
+    var total = 0.0
+    for shape in [Circle(1.0), Rect(2.0, 3.0), Empty] {
+        total += match shape {
+            Circle(r) => 3.0 * r * r,
+            Rect(w, h) => w * h,
+            Empty => 0.0
+        }
+    }
+    total
+    var n = -1
+    match n {
+        0 => \"zero\"
+        -1 => \"minus one\"
+        _ => \"other\"
+    }
+    match Rect(1.0, 2.0) {
+        Rect(1.0, h) => {
+            var double = h * 2.0
+            double
+        }
+        _ => 0.0
+    }
+    match true {
+        true => 1
+        false => 0
+    }
+    Rect(1.0, 2.0)
+    ",
+    );
+    let tokens = lex.lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+
+    assert_eq!(
+        parse,
+        vec![
+            Token::Double(Double::new(9.0)),
+            Token::String("minus one".into()),
+            Token::Double(Double::new(4.0)),
+            Token::Int32(1.into()),
+            Token::Variant {
+                enumeration: "Shape".into(),
+                name: "Rect".into(),
+                values: vec![
+                    Token::Double(Double::new(1.0)),
+                    Token::Double(Double::new(2.0))
+                ]
+            },
+        ]
+    );
+    assert_eq!(parse[4].to_string(), "Rect(1.0, 2.0)");
+    // The names of the patterns only live in their arm
+    assert!(evaluator.get_variable_table().get("double").is_err());
+    assert!(evaluator.get_variable_table().get("h").is_err());
+}
+
+#[test]
+fn match_error_test() {
+    let shape = "enum Shape { Circle(Double), Rect(Double, Double), Empty }\n";
+
+    // Found before running anything
+    for code in [
+        "match Empty {\n Circle(r) => r\n Empty => 0.0\n}",
+        "match Empty {\n Circle(1.0) => 1\n Rect(_, _) => 2\n Empty => 3\n}",
+        "match 1 {\n 1 => 2\n}",
+        "match Empty {\n Square(x) => 1\n _ => 2\n}",
+        "match Empty {\n Circle(a, b) => 1\n _ => 2\n}",
+        "match true {\n true => 1\n}",
+    ] {
+        let code = format!("{shape}{code}");
+        let tokens = Lexer::new(&code).lex().unwrap();
+        assert!(Parser::new(tokens).parse().is_err(), "{code}");
+    }
+
+    for code in [
+        "Circle(\"a\")",
+        "Rect(1.0)",
+        "Circle",
+        "enum Other { Empty }",
+        "enum Shape { Point }",
+        "enum Box { Full(Thing) }",
+    ] {
+        let code = format!("{shape}{code}");
+        let tokens = Lexer::new(&code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut evaluator = Evaluator::new(None, None);
+
+        assert!(evaluator.evaluate(&program).is_err(), "{code}");
+    }
+}
+
+#[test]
+fn generic_type_test() {
+    let declarations = "
+    struct Pair<A, B> { first: A, second: B }
+    enum Maybe<T> { Just(T), Nothing }
+    func swap<A, B>(p: Pair<A, B>) -> Pair<B, A> {
+        return Pair { first: p.second, second: p.first }
+    }
+    func or<T>(m: Maybe<T>, default: T) -> T {
+        return match m {
+            Just(value) => value,
+            Nothing => default,
+        }
+    }
+    ";
+    let code = format!(
+        "{declarations}
+    var p = swap(Pair {{ first: 1, second: \"one\" }})
+    p.second = 2
+    p
+    or(Just(5), 0)
+    or(Nothing, 7)
+    "
+    );
+    let tokens = Lexer::new(&code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+
+    let Item::Function(swap) = &program[2] else {
+        panic!("expected a function, found {:?}", program[2]);
+    };
+    assert_eq!(swap.generics, vec!["A".into(), "B".into()]);
+    assert_eq!(
+        swap.return_type,
+        Types::Custom(
+            "Pair".into(),
+            vec![Types::Generic("B".into()), Types::Generic("A".into())]
+        )
+    );
+
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+    assert_eq!(parse[0].to_string(), "Pair { first: one, second: 2 }");
+    assert_eq!(parse[1..], [Token::Int32(5.into()), Token::Int32(7.into())]);
+
+    // The values of a type parameter must have the same type
+    for code in [
+        "var p = Pair { first: 1, second: 2 }\np.second = \"two\"",
+        "func sum(p: Pair<Int32, Int32>) -> Int32 {\n return p.first + p.second\n}\nsum(Pair { first: 1, second: \"2\" })",
+        "or(Just(5), \"zero\")",
+        "or(Just(\"a\"), Just(1))",
+    ] {
+        let code = format!("{declarations}\n{code}");
+        let tokens = Lexer::new(&code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        assert!(
+            Evaluator::new(None, None).evaluate(&program).is_err(),
+            "{code}"
+        );
+    }
+
+    for code in [
+        "struct Pair<A, A> { first: A }",
+        "func f<T(x: T) {}",
+        "struct Pair<> { first: Int32 }",
+    ] {
+        let tokens = Lexer::new(code).lex().unwrap();
+        assert!(Parser::new(tokens).parse().is_err(), "{code}");
+    }
+}
+
+#[test]
+fn generic_annotation_test() {
+    let declarations = "
+    struct Pair<A, B> { first: A, second: B }
+    enum Maybe<T> { Just(T), Nothing }
+    ";
+
+    // The types of the values must fit the types given to the type parameters
+    for (code, message) in [
+        (
+            "var q: Pair<Str, Str> = Pair { first: 'a', second: 1 }",
+            "TypeError: The type of 'q' must be <Pair<Str, Str>> but it's <Pair<Str, Int32>>.",
+        ),
+        (
+            "var m: Maybe<Int32> = Just(\"x\")",
+            "TypeError: The type of 'm' must be <Maybe<Int32>> but it's <Maybe<String>>.",
+        ),
+    ] {
+        let code = format!("{declarations}\n{code}");
+        let tokens = Lexer::new(&code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let error = Evaluator::new(None, None).evaluate(&program).unwrap_err();
+        assert_eq!(error.kind().to_string(), message, "{code}");
+    }
+
+    let code = format!(
+        "{declarations}
+    var q: Pair<Str, Int64> = Pair {{ first: 'a', second: 1 }}
+    var m: Maybe<Int32> = Just(3)
+    var n: Maybe<Int32> = Nothing
+    "
+    );
+    let tokens = Lexer::new(&code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    evaluator.evaluate(&program).unwrap();
+    let variables = evaluator.get_variables();
+    let types: Vec<String> = ["q", "m", "n"]
+        .iter()
+        .map(|name| {
+            let (_, variable) = variables.iter().find(|(key, _)| key == name).unwrap();
+            variable.var_type.to_string()
+        })
+        .collect();
+    assert_eq!(types, ["Pair<Str, Int64>", "Maybe<Int32>", "Maybe<Int32>"]);
+}
+
+#[test]
+fn undefined_annotation_test() {
+    // The annotations are checked when they are declared, even if they are never used
+    for (code, message) in [
+        (
+            "func f() -> Bool { return true }",
+            "UndefinedType: The type 'Bool' doesn't exist.",
+        ),
+        (
+            "func f(x: List<Foo>) { }",
+            "UndefinedType: The type 'Foo' doesn't exist.",
+        ),
+        (
+            "var g = |x: Foo| x",
+            "UndefinedType: The type 'Foo' doesn't exist.",
+        ),
+        (
+            "var x: Foo? = none",
+            "UndefinedType: The type 'Foo' doesn't exist.",
+        ),
+    ] {
+        let tokens = Lexer::new(code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let error = Evaluator::new(None, None).evaluate(&program).unwrap_err();
+        assert_eq!(error.kind().to_string(), message, "{code}");
+    }
+
+    // A type can be used before the place where it's declared
+    let code = "
+    func f(p: Point) -> Int32 { return p.x }
+    var y = f(Point { x: 1 })
+    struct Point { x: Int32 }
+    y
+    ";
+    let tokens = Lexer::new(code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let result = Evaluator::new(None, None).evaluate(&program).unwrap();
+    assert_eq!(result, vec![Token::Int32(1.into())]);
+}
+
+#[test]
+fn optional_type_test() {
+    let declarations = "
+    struct Node { value: Int32, next: Node? }
+    func find(xs: List<Int32>, target: Int32) -> Int32? {
+        for x in xs {
+            if x == target {
+                return x
+            }
+        }
+        return none
+    }
+    ";
+    let code = format!(
+        "{declarations}
+    var list = Node {{ value: 1, next: Node {{ value: 2, next: none }} }}
+    var name: Option<Str> = none
+    find([1, 2], 2) ?? 0
+    find([1, 2], 3) ?? 0
+    list.next?.value
+    list.next?.next?.value
+    name = 'set'
+    name ?? 'empty'
+    "
+    );
+    let tokens = Lexer::new(&code).lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+
+    let Item::Function(find) = &program[1] else {
+        panic!("expected a function, found {:?}", program[1]);
+    };
+    assert_eq!(find.return_type, Types::Option(Box::new(Types::Int32)));
+
+    let mut evaluator = Evaluator::new(None, None);
+    let parse = evaluator.evaluate(&program).unwrap();
+    assert_eq!(
+        parse,
+        vec![
+            Token::Int32(2.into()),
+            Token::Int32(0.into()),
+            Token::Int32(2.into()),
+            Token::None,
+            Token::Str("set".into()),
+        ]
+    );
+
+    // A `none` isn't used like a value of the type of the optional
+    for code in [
+        "find([1], 2) + 1",
+        "var n: Int32 = find([1], 2)",
+        "var list = Node { value: 1, next: none }\nlist.next.value",
+        "if find([1], 2) {\n 1\n}",
+    ] {
+        let code = format!("{declarations}\n{code}");
+        let tokens = Lexer::new(&code).lex().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        assert!(
+            Evaluator::new(None, None).evaluate(&program).is_err(),
+            "{code}"
+        );
+    }
+
+    // The type of `none` doesn't show the unknown type of its value
+    let tokens = Lexer::new("var o = none\nvar x: Int32 = o").lex().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let error = Evaluator::new(None, None).evaluate(&program).unwrap_err();
+    assert_eq!(
+        error.kind().to_string(),
+        "TypeError: The type of 'x' must be <Int32> but it's <none>."
+    );
+
+    // Only the value `none` is shown as `none`, the parts of a type that aren't known are `_`
+    let code = "var o: Option = 3\nvar n = none\nvar xs = []\nvar m = {}";
+    let program = Parser::new(Lexer::new(code).lex().unwrap())
+        .parse()
+        .unwrap();
+    let mut evaluator = Evaluator::new(None, None);
+    evaluator.evaluate(&program).unwrap();
+    let mut types: Vec<(String, String)> = evaluator
+        .get_variables()
+        .into_iter()
+        .map(|(name, variable)| (name, variable.var_type.to_string()))
+        .collect();
+    types.sort();
+    assert_eq!(
+        types,
+        [
+            ("m".to_string(), "Map<_, _>".to_string()),
+            ("n".to_string(), "Option<_>".to_string()),
+            ("o".to_string(), "Option<Int32>".to_string()),
+            ("xs".to_string(), "List<_>".to_string()),
+        ]
+    );
+}